@@ -3,7 +3,8 @@
 //! Measures encode, decode, and repair performance across various
 //! parameter configurations and data sizes.
 
-use clay_codes::ClayCode;
+use clay_codes::testing::partial_helper_data;
+use clay_codes::{ClayCode, EncodeWorkspace, RepairWorkspace};
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 use rand::{Rng, SeedableRng};
 use rand::rngs::StdRng;
@@ -45,7 +46,7 @@ fn bench_encode(c: &mut Criterion) {
                 &data,
                 |b, data| {
                     b.iter(|| {
-                        black_box(clay.encode(data))
+                        black_box(clay.encode(data).unwrap())
                     });
                 },
             );
@@ -55,6 +56,31 @@ fn bench_encode(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_encode_workspace(c: &mut Criterion) {
+    let mut group = c.benchmark_group("encode_workspace");
+    let size = 1024 * 1024;
+
+    for &(k, m, d) in CONFIGS {
+        let clay = ClayCode::new(k, m, d).unwrap();
+        let config_name = format!("({},{},{})", clay.n, clay.k, clay.d);
+        let data = generate_data(size, 42);
+        let chunk_size = clay.encode(&data).unwrap()[0].len();
+        let mut ws = EncodeWorkspace::new(&clay, chunk_size).unwrap();
+
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("encode", &config_name), &data, |b, data| {
+            b.iter(|| black_box(clay.encode(data).unwrap()));
+        });
+        group.bench_with_input(BenchmarkId::new("encode_with", &config_name), &data, |b, data| {
+            b.iter(|| {
+                black_box(clay.encode_with(&mut ws, data).unwrap());
+            });
+        });
+    }
+
+    group.finish();
+}
+
 fn bench_decode(c: &mut Criterion) {
     let mut group = c.benchmark_group("decode");
 
@@ -64,7 +90,7 @@ fn bench_decode(c: &mut Criterion) {
 
         for &size in DATA_SIZES {
             let data = generate_data(size, 42);
-            let chunks = clay.encode(&data);
+            let chunks = clay.encode(&data).unwrap();
 
             // Prepare decode input with 1 erasure
             let lost_node = 0;
@@ -92,6 +118,51 @@ fn bench_decode(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_decode_systematic(c: &mut Criterion) {
+    let mut group = c.benchmark_group("decode_systematic");
+    let size = 1024 * 1024;
+
+    for &(k, m, d) in CONFIGS {
+        let clay = ClayCode::new(k, m, d).unwrap();
+        let config_name = format!("({},{},{})", clay.n, clay.k, clay.d);
+        let data = generate_data(size, 42);
+        let chunks = clay.encode(&data).unwrap();
+
+        // Fast path: every chunk present, nothing erased
+        let all: HashMap<usize, Vec<u8>> = chunks.iter().cloned().enumerate().collect();
+
+        // Only parity erased: decode reads the data chunks as is, where it
+        // used to run the layered decode; decode_shards still does, as it
+        // regenerates the parity
+        let lost_parity = clay.n - 1;
+        let mut without_parity = all.clone();
+        without_parity.remove(&lost_parity);
+        let shards: Vec<Option<Vec<u8>>> =
+            (0..clay.n).map(|i| without_parity.get(&i).cloned()).collect();
+
+        group.throughput(Throughput::Bytes(size as u64));
+        group.bench_with_input(BenchmarkId::new("fast", &config_name), &all, |b, available| {
+            b.iter(|| black_box(clay.decode(available, &[]).unwrap()));
+        });
+        group.bench_with_input(
+            BenchmarkId::new("parity_erased", &config_name),
+            &without_parity,
+            |b, available| {
+                b.iter(|| black_box(clay.decode(available, &[lost_parity]).unwrap()));
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("layered", &config_name), &shards, |b, shards| {
+            b.iter(|| {
+                let mut shards = shards.clone();
+                clay.decode_shards(&mut shards).unwrap();
+                black_box(shards)
+            });
+        });
+    }
+
+    group.finish();
+}
+
 fn bench_repair(c: &mut Criterion) {
     let mut group = c.benchmark_group("repair");
 
@@ -101,7 +172,7 @@ fn bench_repair(c: &mut Criterion) {
 
         for &size in DATA_SIZES {
             let data = generate_data(size, 42);
-            let chunks = clay.encode(&data);
+            let chunks = clay.encode(&data).unwrap();
             let chunk_size = chunks[0].len();
             let sub_chunk_size = chunk_size / clay.sub_chunk_no;
 
@@ -110,16 +181,7 @@ fn bench_repair(c: &mut Criterion) {
             let available_nodes: Vec<usize> = (1..clay.n).collect();
             let helper_info = clay.minimum_to_repair(lost_node, &available_nodes).unwrap();
 
-            let mut partial_data: HashMap<usize, Vec<u8>> = HashMap::new();
-            for (helper_idx, indices) in &helper_info {
-                let mut helper_partial = Vec::new();
-                for &sc_idx in indices {
-                    let start = sc_idx * sub_chunk_size;
-                    let end = (sc_idx + 1) * sub_chunk_size;
-                    helper_partial.extend_from_slice(&chunks[*helper_idx][start..end]);
-                }
-                partial_data.insert(*helper_idx, helper_partial);
-            }
+            let partial_data = partial_helper_data(&chunks, &helper_info, sub_chunk_size);
 
             group.throughput(Throughput::Bytes(chunk_size as u64));
             group.bench_with_input(
@@ -137,6 +199,46 @@ fn bench_repair(c: &mut Criterion) {
     group.finish();
 }
 
+fn bench_repair_workspace(c: &mut Criterion) {
+    let mut group = c.benchmark_group("repair_workspace");
+    let size = 1024 * 1024;
+
+    for &(k, m, d) in CONFIGS {
+        let clay = ClayCode::new(k, m, d).unwrap();
+        let config_name = format!("({},{},{})", clay.n, clay.k, clay.d);
+        let data = generate_data(size, 42);
+        let chunks = clay.encode(&data).unwrap();
+        let chunk_size = chunks[0].len();
+        let sub_chunk_size = chunk_size / clay.sub_chunk_no;
+
+        let lost_node = 0;
+        let available_nodes: Vec<usize> = (1..clay.n).collect();
+        let helper_info = clay.minimum_to_repair(lost_node, &available_nodes).unwrap();
+        let partial_data = partial_helper_data(&chunks, &helper_info, sub_chunk_size);
+        let mut ws = RepairWorkspace::new(&clay, chunk_size).unwrap();
+
+        group.throughput(Throughput::Bytes(chunk_size as u64));
+        group.bench_with_input(
+            BenchmarkId::new("repair", &config_name),
+            &partial_data,
+            |b, partial| {
+                b.iter(|| black_box(clay.repair(lost_node, partial, chunk_size).unwrap()));
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("repair_with", &config_name),
+            &partial_data,
+            |b, partial| {
+                b.iter(|| {
+                    black_box(clay.repair_with(&mut ws, lost_node, partial).unwrap());
+                });
+            },
+        );
+    }
+
+    group.finish();
+}
+
 fn bench_metrics_report(c: &mut Criterion) {
     // This benchmark just prints a metrics report, doesn't actually bench
     println!("\n{}", "=".repeat(80));
@@ -171,7 +273,7 @@ fn bench_metrics_report(c: &mut Criterion) {
 
         for &size in DATA_SIZES {
             let data = generate_data(size, 42);
-            let chunks = clay.encode(&data);
+            let chunks = clay.encode(&data).unwrap();
             let chunk_size = chunks[0].len();
             let total_storage = chunk_size * clay.n;
 
@@ -218,8 +320,11 @@ criterion_group!(
     benches,
     bench_metrics_report,
     bench_encode,
+    bench_encode_workspace,
     bench_decode,
+    bench_decode_systematic,
     bench_repair,
+    bench_repair_workspace,
 );
 
 criterion_main!(benches);
@@ -37,7 +37,7 @@ fn main() {
     println!();
 
     // Encode data into chunks
-    let chunks = clay.encode(data);
+    let chunks = clay.encode(data).unwrap();
     println!("Encoded into {} chunks:", chunks.len());
     let chunk_size = chunks[0].len();
     let sub_chunk_size = chunk_size / clay.sub_chunk_no;
@@ -0,0 +1,180 @@
+//! Builder for ClayCode
+//!
+//! Collects parameters by name instead of position, filling in defaults for
+//! the optional ones when the code is built.
+
+use alloc::sync::Arc;
+
+use crate::codec::RsBackend;
+use crate::error::ClayError;
+use crate::transforms::DEFAULT_GAMMA;
+use crate::{ClayCode, DEFAULT_MAX_SUB_CHUNK_NO};
+
+/// Builder for [`ClayCode`], created with [`ClayCode::builder`]
+///
+/// `k` and `m` are required. `d` defaults to k + m - 1 (maximum helpers),
+/// `gamma` to 2, `max_sub_chunk_no` to [`DEFAULT_MAX_SUB_CHUNK_NO`] and
+/// `backend` to [`RsCodec`](crate::RsCodec).
+#[derive(Clone, Debug, Default)]
+pub struct ClayCodeBuilder {
+    k: Option<usize>,
+    m: Option<usize>,
+    d: Option<usize>,
+    gamma: Option<u8>,
+    max_sub_chunk_no: Option<usize>,
+    backend: Option<Arc<dyn RsBackend>>,
+}
+
+impl ClayCodeBuilder {
+    /// Create a builder with nothing set
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the number of data chunks
+    pub fn k(mut self, k: usize) -> Self {
+        self.k = Some(k);
+        self
+    }
+
+    /// Set the number of parity chunks
+    pub fn m(mut self, m: usize) -> Self {
+        self.m = Some(m);
+        self
+    }
+
+    /// Set the number of helper nodes for repair
+    pub fn d(mut self, d: usize) -> Self {
+        self.d = Some(d);
+        self
+    }
+
+    /// Set the coupling coefficient γ of the pairwise transforms
+    pub fn gamma(mut self, gamma: u8) -> Self {
+        self.gamma = Some(gamma);
+        self
+    }
+
+    /// Set the largest sub-packetization α to accept
+    pub fn max_sub_chunk_no(mut self, limit: usize) -> Self {
+        self.max_sub_chunk_no = Some(limit);
+        self
+    }
+
+    /// Set the code of the uncoupled layers, with k + nu data shards and
+    /// m parity shards
+    pub fn backend<B: RsBackend + 'static>(mut self, backend: B) -> Self {
+        self.backend = Some(Arc::new(backend));
+        self
+    }
+
+    /// Build the code, validating every parameter
+    ///
+    /// # Returns
+    /// Result with ClayCode or error if a required parameter is missing,
+    /// the parameters are invalid or the backend does not fit the code
+    pub fn build(&self) -> Result<ClayCode, ClayError> {
+        let k = self
+            .k
+            .ok_or_else(|| ClayError::InvalidParameters("k must be set".into()))?;
+        let m = self
+            .m
+            .ok_or_else(|| ClayError::InvalidParameters("m must be set".into()))?;
+        let d = self.d.unwrap_or_else(|| (k + m).saturating_sub(1));
+
+        let code = ClayCode::with_alpha_limit(
+            k,
+            m,
+            d,
+            self.gamma.unwrap_or(DEFAULT_GAMMA),
+            self.max_sub_chunk_no.unwrap_or(DEFAULT_MAX_SUB_CHUNK_NO),
+        )?;
+        match &self.backend {
+            Some(backend) => code.replace_backend(Arc::clone(backend)),
+            None => Ok(code),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder_defaults_match_new_default() {
+        for (k, m) in [(4, 2), (9, 3), (10, 4)] {
+            let built = ClayCode::builder().k(k).m(m).build().unwrap();
+            let expected = ClayCode::new_default(k, m).unwrap();
+            assert_eq!(
+                (built.k, built.m, built.d, built.q, built.sub_chunk_no, built.gamma),
+                (expected.k, expected.m, expected.d, expected.q, expected.sub_chunk_no, expected.gamma)
+            );
+        }
+    }
+
+    #[test]
+    fn test_builder_explicit_parameters() {
+        let clay = ClayCode::builder().m(3).k(6).d(7).gamma(5).build().unwrap();
+        assert_eq!((clay.k, clay.m, clay.d, clay.gamma), (6, 3, 7, 5));
+    }
+
+    #[test]
+    fn test_builder_max_sub_chunk_no() {
+        let builder = ClayCode::builder().k(4).m(2);
+        assert_eq!(builder.clone().max_sub_chunk_no(8).build().unwrap().sub_chunk_no, 8);
+        assert_eq!(
+            builder.max_sub_chunk_no(7).build().unwrap_err(),
+            ClayError::SubPacketizationTooLarge { alpha: 8, limit: 7 }
+        );
+    }
+
+    #[test]
+    fn test_builder_backend_with_other_options() {
+        use crate::RsCodec;
+
+        // GF(2^16) layers together with a custom γ and a raised α cap
+        let clay = ClayCode::builder()
+            .k(32)
+            .m(2)
+            .gamma(7)
+            .max_sub_chunk_no(1 << 17)
+            .backend(RsCodec::new_gf16(32, 2).unwrap())
+            .build()
+            .unwrap();
+        assert_eq!((clay.gamma, clay.sub_chunk_no), (7, 1 << 17));
+        assert_eq!(clay.encode_params().rs.symbol_size(), 2);
+
+        let clay = ClayCode::builder()
+            .k(4)
+            .m(2)
+            .gamma(3)
+            .backend(RsCodec::new_gf16(4, 2).unwrap())
+            .build()
+            .unwrap();
+        let data: Vec<u8> = (0..500).map(|i| (i * 7 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+        let available = (2..clay.n).map(|i| (i, chunks[i].clone())).collect();
+        assert_eq!(&clay.decode(&available, &[0, 1]).unwrap()[..data.len()], &data[..]);
+
+        // Shard counts must match the code
+        assert!(matches!(
+            ClayCode::builder().k(4).m(2).backend(RsCodec::new(5, 2).unwrap()).build(),
+            Err(ClayError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn test_builder_errors() {
+        assert!(matches!(
+            ClayCode::builder().m(2).build(),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            ClayCode::builder().k(4).build(),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        assert!(ClayCode::builder().k(4).m(2).d(6).build().is_err());
+        assert!(ClayCode::builder().k(4).m(2).gamma(1).build().is_err());
+        assert!(ClayCode::builder().k(0).m(0).build().is_err());
+    }
+}
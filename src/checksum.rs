@@ -0,0 +1,220 @@
+//! Per-sub-chunk CRC32C checksums for corruption-aware repair
+//!
+//! A checksummed chunk is the plain chunk followed by a trailer of α
+//! little-endian CRC32C values, one per sub-chunk in sub-chunk order. For
+//! repair a helper sends its β sub-chunks followed by the β trailer entries
+//! of those same sub-chunks, so every sub-chunk can be checked before it is
+//! mixed into the repair, where a corruption would otherwise spread into the
+//! recovered chunk unnoticed.
+
+use alloc::format;
+use alloc::vec::Vec;
+
+use crate::decode::DecodeParams;
+use crate::encode::{encode, validate_sub_chunk_size};
+use crate::error::ClayError;
+use crate::repair::{repair, repair_subchunk_indices};
+use crate::HashMap;
+
+/// Bytes of one sub-chunk checksum in a trailer
+pub const CHECKSUM_SIZE: usize = 4;
+
+/// CRC32C (Castagnoli) lookup table, reflected polynomial 0x82F63B78
+const CRC32C_TABLE: [u32; 256] = {
+    let mut table = [0u32; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0x82F6_3B78 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+};
+
+/// CRC32C of `data`
+pub fn crc32c(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc = CRC32C_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
+
+/// Append the checksum of each `sub_chunk_size` piece of `data` to `out`
+fn push_checksums(out: &mut Vec<u8>, data: &[u8], sub_chunk_size: usize) {
+    for sub_chunk in data.chunks(sub_chunk_size) {
+        out.extend_from_slice(&crc32c(sub_chunk).to_le_bytes());
+    }
+}
+
+/// Encode data into n chunks, each followed by its sub-chunk checksums
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `data`: Data to encode
+///
+/// # Returns
+/// n checksummed chunks of chunk_size + α·CHECKSUM_SIZE bytes each
+pub fn encode_with_checksums(
+    params: &DecodeParams,
+    data: &[u8],
+) -> Result<Vec<Vec<u8>>, ClayError> {
+    let mut chunks = encode(params, data)?;
+    let sub_chunk_size = chunks[0].len() / params.sub_chunk_no;
+    for chunk in &mut chunks {
+        let mut trailer = Vec::with_capacity(params.sub_chunk_no * CHECKSUM_SIZE);
+        push_checksums(&mut trailer, chunk, sub_chunk_size);
+        chunk.extend_from_slice(&trailer);
+    }
+    Ok(chunks)
+}
+
+/// Extract what a helper sends to repair `lost_node` from its checksummed chunk
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `lost_node`: Index of the lost node (0 to n-1)
+/// - `stored`: The helper's checksummed chunk
+///
+/// # Returns
+/// The β repair sub-chunks followed by their β checksums
+pub fn checksummed_helper_data(
+    params: &DecodeParams,
+    lost_node: usize,
+    stored: &[u8],
+) -> Result<Vec<u8>, ClayError> {
+    let trailer_size = params.sub_chunk_no * CHECKSUM_SIZE;
+    let chunk_size = stored.len().saturating_sub(trailer_size);
+    validate_sub_chunk_size(params.sub_chunk_no, chunk_size)?;
+    let sub_chunk_size = chunk_size / params.sub_chunk_no;
+    let indices = repair_subchunk_indices(params, lost_node)?;
+
+    let mut data = Vec::with_capacity(indices.len() * (sub_chunk_size + CHECKSUM_SIZE));
+    for &sc in &indices {
+        data.extend_from_slice(&stored[sc * sub_chunk_size..(sc + 1) * sub_chunk_size]);
+    }
+    for &sc in &indices {
+        let offset = chunk_size + sc * CHECKSUM_SIZE;
+        data.extend_from_slice(&stored[offset..offset + CHECKSUM_SIZE]);
+    }
+    Ok(data)
+}
+
+/// Repair a lost chunk, checking every helper sub-chunk against its checksum
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `lost_node`: Index of the lost node (0 to n-1)
+/// - `helper_data`: Map from helper node index to its β repair sub-chunks
+///   followed by their β checksums, as built by checksummed_helper_data()
+/// - `chunk_size`: Full chunk size, without the checksum trailer
+///
+/// # Returns
+/// The recovered checksummed chunk, or ReconstructionFailed naming the first
+/// helper whose sub-chunk does not match its checksum
+pub fn repair_with_checksums(
+    params: &DecodeParams,
+    lost_node: usize,
+    helper_data: &HashMap<usize, Vec<u8>>,
+    chunk_size: usize,
+) -> Result<Vec<u8>, ClayError> {
+    validate_sub_chunk_size(params.sub_chunk_no, chunk_size)?;
+    let sub_chunk_size = chunk_size / params.sub_chunk_no;
+    let indices = repair_subchunk_indices(params, lost_node)?;
+    let payload = indices.len() * sub_chunk_size;
+
+    let mut helpers: Vec<usize> = helper_data.keys().copied().collect();
+    helpers.sort_unstable();
+    let mut plain: HashMap<usize, Vec<u8>> = HashMap::new();
+    for helper in helpers {
+        let data = &helper_data[&helper];
+        if data.len() != payload + indices.len() * CHECKSUM_SIZE {
+            return Err(ClayError::InsufficientHelperData {
+                helper,
+                expected: payload + indices.len() * CHECKSUM_SIZE,
+                actual: data.len(),
+            });
+        }
+        let (sub_chunks, checksums) = data.split_at(payload);
+        for ((sub_chunk, expected), &sc) in sub_chunks
+            .chunks(sub_chunk_size)
+            .zip(checksums.chunks(CHECKSUM_SIZE))
+            .zip(&indices)
+        {
+            if crc32c(sub_chunk).to_le_bytes() != expected {
+                return Err(ClayError::ReconstructionFailed(format!(
+                    "Helper {} sub-chunk {} fails its checksum",
+                    helper, sc
+                )));
+            }
+        }
+        plain.insert(helper, sub_chunks.to_vec());
+    }
+
+    let mut recovered = repair(params, lost_node, &plain, chunk_size)?;
+    let mut trailer = Vec::with_capacity(params.sub_chunk_no * CHECKSUM_SIZE);
+    push_checksums(&mut trailer, &recovered, sub_chunk_size);
+    recovered.extend_from_slice(&trailer);
+    Ok(recovered)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ClayCode;
+
+    #[test]
+    fn test_crc32c_check_value() {
+        assert_eq!(crc32c(b"123456789"), 0xE306_9283);
+        assert_eq!(crc32c(b""), 0);
+    }
+
+    #[test]
+    fn test_repair_with_checksums() {
+        let code = ClayCode::new(4, 2, 5).unwrap();
+        let params = code.encode_params();
+        let data: Vec<u8> = (0..2000).map(|i| (i * 13 % 256) as u8).collect();
+        let stored = encode_with_checksums(&params, &data).unwrap();
+        let plain = encode(&params, &data).unwrap();
+        let chunk_size = plain[0].len();
+        assert_eq!(stored[0].len(), chunk_size + params.sub_chunk_no * CHECKSUM_SIZE);
+        assert_eq!(&stored[0][..chunk_size], &plain[0][..]);
+
+        for lost_node in 0..params.n {
+            let mut helper_data: HashMap<usize, Vec<u8>> = (0..params.n)
+                .filter(|&i| i != lost_node)
+                .map(|i| (i, checksummed_helper_data(&params, lost_node, &stored[i]).unwrap()))
+                .collect();
+            let recovered =
+                repair_with_checksums(&params, lost_node, &helper_data, chunk_size).unwrap();
+            assert_eq!(recovered, stored[lost_node], "Repair failed for node {}", lost_node);
+
+            // A flipped byte in any helper is reported before repairing
+            let helper = (lost_node + 1) % params.n;
+            helper_data.get_mut(&helper).unwrap()[1] ^= 0x01;
+            match repair_with_checksums(&params, lost_node, &helper_data, chunk_size) {
+                Err(ClayError::ReconstructionFailed(msg)) => {
+                    assert!(msg.contains(&format!("Helper {} ", helper)), "{}", msg)
+                }
+                other => panic!("expected a checksum failure, got {:?}", other),
+            }
+        }
+
+        // One-byte sub-chunks
+        let alpha = params.sub_chunk_no;
+        let tiny = vec![0u8; alpha + alpha * CHECKSUM_SIZE];
+        assert!(matches!(
+            checksummed_helper_data(&params, 0, &tiny),
+            Err(ClayError::ChunkTooSmall { min: 16, actual: 8 })
+        ));
+        assert!(matches!(
+            repair_with_checksums(&params, 0, &HashMap::new(), alpha),
+            Err(ClayError::ChunkTooSmall { min: 16, actual: 8 })
+        ));
+    }
+}
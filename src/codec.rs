@@ -0,0 +1,230 @@
+//! Reed-Solomon codec for the uncoupled layers
+//!
+//! Each uncoupled layer is an RS codeword. GF(2^8) supports at most 256
+//! shards, so wider codes switch to GF(2^16), where a shard is read as a
+//! sequence of 2-byte symbols and must have even length.
+//!
+//! The pairwise transforms stay byte-wise in GF(2^8) either way. Decoding
+//! and repair only need each PRT/PFT pair to be invertible and each layer
+//! to be MDS, so the field of the RS layer is independent of the field of
+//! the transforms.
+//!
+//! Encode, decode, repair and verify reach the layer code only through
+//! [`RsBackend`], so another MDS implementation can stand in for it.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::fmt;
+
+use reed_solomon_erasure::{galois_16, galois_8, Error, ReedSolomon};
+
+use crate::error::ClayError;
+
+/// Largest total shard count GF(2^8) supports
+pub const GF8_MAX_SHARDS: usize = 256;
+
+/// RS codec shared by every layer, over GF(2^8) or GF(2^16)
+#[derive(Debug)]
+pub enum RsCodec {
+    Gf8(Box<ReedSolomon<galois_8::Field>>),
+    Gf16(Box<ReedSolomon<galois_16::Field>>),
+}
+
+impl RsCodec {
+    /// Build a codec, using GF(2^16) when the shards do not fit GF(2^8)
+    pub fn new(data_shards: usize, parity_shards: usize) -> Result<Self, Error> {
+        if data_shards + parity_shards > GF8_MAX_SHARDS {
+            Self::new_gf16(data_shards, parity_shards)
+        } else {
+            Ok(Self::Gf8(Box::new(ReedSolomon::new(data_shards, parity_shards)?)))
+        }
+    }
+
+    /// Build a GF(2^16) codec regardless of width
+    pub fn new_gf16(data_shards: usize, parity_shards: usize) -> Result<Self, Error> {
+        Ok(Self::Gf16(Box::new(ReedSolomon::new(data_shards, parity_shards)?)))
+    }
+}
+
+/// Erasure code applied to every uncoupled layer
+///
+/// Shard 0..data_shard_count() are data, the rest parity. Any backend must
+/// be MDS: every data_shard_count() shards of a codeword determine the rest,
+/// since decode and repair rely on nothing else from the layer code. The
+/// default is [`RsCodec`]; supply another through `ClayCode::with_backend`
+/// or `ClayCodeBuilder::backend`.
+pub trait RsBackend: Send + Sync + fmt::Debug {
+    /// Number of data shards per layer codeword
+    fn data_shard_count(&self) -> usize;
+
+    /// Number of parity shards per layer codeword
+    fn parity_shard_count(&self) -> usize;
+
+    /// Bytes per field symbol; every shard length must be a multiple
+    fn symbol_size(&self) -> usize {
+        1
+    }
+
+    /// Compute the parity shards from the data shards in place
+    fn encode(&self, shards: &mut [Vec<u8>]) -> Result<(), ClayError>;
+
+    /// Reconstruct the shards flagged absent in place
+    ///
+    /// Present shards must be left untouched and absent ones overwritten
+    /// within their existing buffers.
+    fn reconstruct(&self, shards: &mut [(Vec<u8>, bool)]) -> Result<(), ClayError>;
+
+    /// Check that the parity shards match the data shards
+    fn verify(&self, shards: &[Vec<u8>]) -> Result<bool, ClayError> {
+        let data_shards = self.data_shard_count();
+        let mut expected = shards.to_vec();
+        self.encode(&mut expected)?;
+        Ok(expected[data_shards..] == shards[data_shards..])
+    }
+}
+
+impl RsBackend for RsCodec {
+    fn data_shard_count(&self) -> usize {
+        match self {
+            Self::Gf8(rs) => rs.data_shard_count(),
+            Self::Gf16(rs) => rs.data_shard_count(),
+        }
+    }
+
+    fn parity_shard_count(&self) -> usize {
+        match self {
+            Self::Gf8(rs) => rs.parity_shard_count(),
+            Self::Gf16(rs) => rs.parity_shard_count(),
+        }
+    }
+
+    fn symbol_size(&self) -> usize {
+        match self {
+            Self::Gf8(_) => 1,
+            Self::Gf16(_) => 2,
+        }
+    }
+
+    fn encode(&self, shards: &mut [Vec<u8>]) -> Result<(), ClayError> {
+        match self {
+            Self::Gf8(rs) => rs.encode(shards)?,
+            Self::Gf16(rs) => {
+                let mut wide = shards
+                    .iter()
+                    .map(|s| to_symbols(s))
+                    .collect::<Result<Vec<_>, Error>>()?;
+                rs.encode(&mut wide)?;
+                let data_shards = rs.data_shard_count();
+                for (shard, symbols) in shards.iter_mut().zip(wide).skip(data_shards) {
+                    *shard = from_symbols(&symbols);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Every shard keeps its own buffer, so on GF(2^8) nothing is
+    /// allocated once the decode matrix for the pattern is cached.
+    fn reconstruct(&self, shards: &mut [(Vec<u8>, bool)]) -> Result<(), ClayError> {
+        match self {
+            Self::Gf8(rs) => rs.reconstruct(shards)?,
+            Self::Gf16(rs) => {
+                let mut wide = shards
+                    .iter()
+                    .map(|(s, present)| Ok((to_symbols(s)?, *present)))
+                    .collect::<Result<Vec<_>, Error>>()?;
+                rs.reconstruct(&mut wide)?;
+                for ((shard, present), (symbols, _)) in shards.iter_mut().zip(wide) {
+                    if !*present {
+                        shard.copy_from_slice(&from_symbols(&symbols));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn verify(&self, shards: &[Vec<u8>]) -> Result<bool, ClayError> {
+        match self {
+            Self::Gf8(rs) => Ok(rs.verify(shards)?),
+            Self::Gf16(rs) => {
+                let wide = shards
+                    .iter()
+                    .map(|s| to_symbols(s))
+                    .collect::<Result<Vec<_>, Error>>()?;
+                Ok(rs.verify(&wide)?)
+            }
+        }
+    }
+}
+
+/// Read a shard as 2-byte GF(2^16) symbols
+fn to_symbols(shard: &[u8]) -> Result<Vec<[u8; 2]>, Error> {
+    if shard.len() % 2 != 0 {
+        return Err(Error::IncorrectShardSize);
+    }
+    Ok(shard.chunks_exact(2).map(|p| [p[0], p[1]]).collect())
+}
+
+/// Flatten 2-byte GF(2^16) symbols back into a shard
+fn from_symbols(symbols: &[[u8; 2]]) -> Vec<u8> {
+    symbols.iter().flatten().copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_selection() {
+        assert!(matches!(RsCodec::new(4, 2).unwrap(), RsCodec::Gf8(_)));
+        assert!(matches!(RsCodec::new(2, 254).unwrap(), RsCodec::Gf8(_)));
+        assert!(matches!(RsCodec::new(2, 255).unwrap(), RsCodec::Gf16(_)));
+    }
+
+    #[test]
+    fn test_gf16_roundtrip() {
+        let rs = RsCodec::new_gf16(4, 2).unwrap();
+        let mut shards: Vec<Vec<u8>> = (0..6).map(|i| vec![i as u8 * 17; 6]).collect();
+        rs.encode(&mut shards).unwrap();
+        assert!(rs.verify(&shards).unwrap());
+
+        let mut partial: Vec<(Vec<u8>, bool)> = shards.iter().cloned().map(|s| (s, true)).collect();
+        partial[1].1 = false;
+        partial[4].1 = false;
+        rs.reconstruct(&mut partial).unwrap();
+        let restored: Vec<Vec<u8>> = partial.into_iter().map(|(s, _)| s).collect();
+        assert_eq!(restored, shards);
+
+        shards[0][3] ^= 1;
+        assert!(!rs.verify(&shards).unwrap());
+    }
+
+    #[test]
+    fn test_reconstruct_in_place() {
+        for rs in [RsCodec::new(4, 2).unwrap(), RsCodec::new_gf16(4, 2).unwrap()] {
+            let mut shards: Vec<Vec<u8>> = (0..6).map(|i| vec![i as u8 * 29 + 3; 8]).collect();
+            rs.encode(&mut shards).unwrap();
+
+            // One data and one parity shard absent, their buffers stale
+            let mut flagged: Vec<(Vec<u8>, bool)> =
+                shards.iter().cloned().map(|s| (s, true)).collect();
+            for i in [2, 5] {
+                flagged[i] = (vec![0xEE; 8], false);
+            }
+            rs.reconstruct(&mut flagged).unwrap();
+            let restored: Vec<Vec<u8>> = flagged.into_iter().map(|(s, _)| s).collect();
+            assert_eq!(restored, shards);
+        }
+    }
+
+    #[test]
+    fn test_gf16_rejects_odd_shards() {
+        let rs = RsCodec::new_gf16(2, 1).unwrap();
+        let mut shards = vec![vec![1u8; 3]; 3];
+        assert_eq!(
+            rs.encode(&mut shards),
+            Err(ClayError::ReedSolomon(Error::IncorrectShardSize))
+        );
+    }
+}
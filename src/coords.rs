@@ -11,6 +11,36 @@
 //! - **Intersection Score (IS)**: Count of erased "red" vertices in a layer
 //! - **Plane vector**: The z-coordinates that make up layer z (base-q representation)
 
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// Map an external chunk index to its internal node index
+///
+/// Data chunks keep their index; parity chunks move past the nu shortened
+/// nodes, which sit at internal indices k..k+nu. Saturates instead of
+/// overflowing, so an out-of-range index stays out of range.
+pub fn to_internal(external: usize, k: usize, nu: usize) -> usize {
+    if external < k {
+        external
+    } else {
+        external.saturating_add(nu)
+    }
+}
+
+/// Map an internal node index back to its external chunk index
+///
+/// # Returns
+/// The chunk index, or None for a shortened node, which is never stored
+pub fn to_external(internal: usize, k: usize, nu: usize) -> Option<usize> {
+    if internal < k {
+        Some(internal)
+    } else if internal < k + nu {
+        None
+    } else {
+        Some(internal - nu)
+    }
+}
+
 /// Get the plane (layer) vector for a given z
 ///
 /// Converts z to base-q representation, giving the z_y value for each y-section.
@@ -39,10 +69,55 @@ pub fn get_plane_vector(z: usize, t: usize, q: usize) -> Vec<usize> {
     result
 }
 
+/// Plane vectors of all α layers, flattened
+///
+/// Layer z occupies `z * t..(z + 1) * t`. Built once per code so the
+/// decode and repair loops index into it instead of recomputing.
+pub fn plane_vector_table(sub_chunk_no: usize, t: usize, q: usize) -> Vec<usize> {
+    (0..sub_chunk_no).flat_map(|z| get_plane_vector(z, t, q)).collect()
+}
+
+/// Companion layer of every vertex, flattened
+///
+/// The companion of vertex (x, y, z) is layer z with its digit y replaced
+/// by x: z_sw = z + (x - z_y) * q^(t-1-y). Entry `(y * q + x) * α + z`
+/// holds z_sw, so each internal node (y * q + x) owns a run of α entries.
+/// Built once per code like [`plane_vector_table`].
+pub fn companion_layer_table(sub_chunk_no: usize, t: usize, q: usize) -> Vec<usize> {
+    let mut table = Vec::with_capacity(t * q * sub_chunk_no);
+    let mut place = sub_chunk_no;
+    for _ in 0..t {
+        // Place value q^(t-1-y) of digit y
+        place /= q;
+        for x in 0..q {
+            table.extend((0..sub_chunk_no).map(|z| z - (z / place % q) * place + x * place));
+        }
+    }
+    table
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_index_mapping() {
+        // k = 5, nu = 1: internal 5 is shortened, parity 5 and 6 sit at 6 and 7
+        let (k, nu) = (5, 1);
+        let internal: Vec<usize> = (0..7).map(|e| to_internal(e, k, nu)).collect();
+        assert_eq!(internal, [0, 1, 2, 3, 4, 6, 7]);
+        for (e, &i) in internal.iter().enumerate() {
+            assert_eq!(to_external(i, k, nu), Some(e));
+        }
+        assert_eq!(to_external(5, k, nu), None);
+
+        // No shortening: the identity
+        assert_eq!(to_internal(4, 4, 0), 4);
+        assert_eq!(to_external(4, 4, 0), Some(4));
+
+        assert_eq!(to_internal(usize::MAX, k, nu), usize::MAX);
+    }
+
     #[test]
     fn test_plane_vector() {
         // For q=2, t=2 (MSB at index 0, LSB at index t-1):
@@ -58,4 +133,36 @@ mod tests {
         // For q=3, t=2: z=5 = 1*3 + 2 -> [1,2] (MSB=1, LSB=2)
         assert_eq!(get_plane_vector(5, 2, 3), vec![1, 2]);
     }
+
+    #[test]
+    fn test_companion_layer_table() {
+        let (q, t) = (3, 3);
+        let alpha = 27;
+        let table = companion_layer_table(alpha, t, q);
+        assert_eq!(table.len(), t * q * alpha);
+        for y in 0..t {
+            for x in 0..q {
+                for z in 0..alpha {
+                    let z_sw = table[(y * q + x) * alpha + z];
+                    // Only digit y changes, and it becomes x
+                    let mut expected = get_plane_vector(z, t, q);
+                    expected[y] = x;
+                    assert_eq!(get_plane_vector(z_sw, t, q), expected);
+                    // Pairing is symmetric: the companion's companion is z
+                    let z_y = get_plane_vector(z, t, q)[y];
+                    assert_eq!(table[(y * q + z_y) * alpha + z_sw], z);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_plane_vector_table() {
+        let (q, t) = (3, 4);
+        let table = plane_vector_table(81, t, q);
+        assert_eq!(table.len(), 81 * t);
+        for z in 0..81 {
+            assert_eq!(&table[z * t..(z + 1) * t], get_plane_vector(z, t, q).as_slice());
+        }
+    }
 }
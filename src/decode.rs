@@ -4,27 +4,50 @@
 //! It handles both full decoding (all chunks available) and erasure recovery
 //! (up to m chunks missing).
 
-use std::collections::{BTreeSet, HashMap};
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::io::Write;
 
-use reed_solomon_erasure::galois_8::{self, add as gf_add, mul as gf_mul};
+use reed_solomon_erasure::galois_8::add as gf_add;
 
-use crate::coords::get_plane_vector;
-use crate::encode::EncodeParams;
+use crate::encode::{validate_sub_chunk_size, EncodeParams, ObjectManifest, LENGTH_HEADER_SIZE};
 use crate::error::ClayError;
+use crate::HashMap;
+#[cfg(feature = "profile")]
+use crate::profile::PhaseTimings;
+use crate::repair::repair_from_full_chunks;
 use crate::transforms::{
-    compute_c_from_u_and_cstar, compute_u_from_c_and_ustar, pft_compute_both, prt_compute_both,
-    GAMMA,
+    compute_c_from_u_and_cstar_into, compute_u_from_c_and_ustar_into, pft_compute_both_into,
+    prt_compute_single_into, GfMulTable,
 };
 
 /// Parameters needed for decoding (same as encode for now)
-pub type DecodeParams = EncodeParams;
+pub type DecodeParams<'a> = EncodeParams<'a>;
 
-/// Recover original data from available chunks
+/// Decoded data with the rebuilt erased chunks: (data, index -> chunk)
+pub type HealedStripe = (Vec<u8>, HashMap<usize, Vec<u8>>);
+
+/// What a layered decode records about itself
+#[cfg(feature = "profile")]
+pub(crate) type Timings = PhaseTimings;
+
+/// Stand-in for the phase timings; nothing is timed without `profile`
+#[cfg(not(feature = "profile"))]
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct Timings {}
+
+/// Recover original data from whatever chunks survived
+///
+/// Any node missing from `available` is treated as erased, so `erasures`
+/// may be left empty; at least k chunks must be present.
 ///
 /// # Parameters
 /// - `params`: Code parameters
 /// - `available`: Map from chunk index to chunk data
-/// - `erasures`: Set of erased chunk indices
+/// - `erasures`: Erased chunk indices, in addition to those not provided
 ///
 /// # Returns
 /// Recovered original data, or error if decoding fails
@@ -33,131 +56,950 @@ pub fn decode(
     available: &HashMap<usize, Vec<u8>>,
     erasures: &[usize],
 ) -> Result<Vec<u8>, ClayError> {
-    if available.is_empty() && erasures.is_empty() {
+    decode_strict(params, available, &infer_erasures(params, available, erasures))
+}
+
+/// Recover original data from available chunks and an exact erasure list
+///
+/// Every node must be either in `available` or in `erasures`.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `available`: Map from chunk index to chunk data
+/// - `erasures`: Set of erased chunk indices
+///
+/// # Returns
+/// Recovered original data, or error if decoding fails
+pub fn decode_strict(
+    params: &DecodeParams,
+    available: &HashMap<usize, Vec<u8>>,
+    erasures: &[usize],
+) -> Result<Vec<u8>, ClayError> {
+    let capacity = available.values().next().map_or(0, |c| c.len() * params.k);
+    let mut decoded = Vec::with_capacity(capacity);
+    decode_into(params, available, erasures, |chunk| {
+        decoded.extend_from_slice(chunk);
+        Ok(())
+    })?;
+    Ok(decoded)
+}
+
+/// Recover original data and the time each decoding phase took
+///
+/// Same as [`decode`]. With only parity erased the data chunks are read
+/// as they are, so no layer is decoded and every counter is zero.
+#[cfg(feature = "profile")]
+pub fn decode_profiled(
+    params: &DecodeParams,
+    available: &HashMap<usize, Vec<u8>>,
+    erasures: &[usize],
+) -> Result<(Vec<u8>, PhaseTimings), ClayError> {
+    let erasures = infer_erasures(params, available, erasures);
+    if validate_available(params, available, &erasures)?.is_none() {
+        return Ok((Vec::new(), PhaseTimings::default()));
+    }
+    if erasures.iter().all(|&e| e >= params.k) {
+        let data = (0..params.k).flat_map(|i| available[&i].iter().copied()).collect();
+        return Ok((data, PhaseTimings::default()));
+    }
+
+    let mut shards: Vec<Option<Vec<u8>>> = vec![None; params.n];
+    for (&idx, data) in available.iter() {
+        shards[idx] = Some(data.clone());
+    }
+    let scratch = decode_shards_with_scratch(params, &mut shards)?;
+    let timings = scratch.map_or_else(PhaseTimings::default, |scratch| scratch.timings());
+    Ok((concat_data_chunks(params, shards), timings))
+}
+
+/// Recover original data from borrowed chunks
+///
+/// Same as [`decode`], but the chunks are lent as slices (e.g. views into a
+/// larger arena). Only the chunks the working set needs are copied.
+pub fn decode_refs(
+    params: &DecodeParams,
+    available: &HashMap<usize, &[u8]>,
+    erasures: &[usize],
+) -> Result<Vec<u8>, ClayError> {
+    let erasures = infer_erasures(params, available, erasures);
+    let capacity = available.values().next().map_or(0, |c| c.len() * params.k);
+    let mut decoded = Vec::with_capacity(capacity);
+    decode_into(params, available, &erasures, |chunk| {
+        decoded.extend_from_slice(chunk);
+        Ok(())
+    })?;
+    Ok(decoded)
+}
+
+/// Recover original data from an n-slot shard array
+///
+/// The most forgiving entry point: `None` marks a missing node, every
+/// present shard is used, and decoding succeeds whenever at least k are
+/// present. Unlike [`decode_shards`] nothing is written back.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `shards`: Exactly n shards in node order; `None` marks a missing node
+///
+/// # Returns
+/// Recovered original data, or error if the array is not n long, fewer
+/// than k shards are present or decoding fails
+pub fn decode_lenient(
+    params: &DecodeParams,
+    shards: &[Option<Vec<u8>>],
+) -> Result<Vec<u8>, ClayError> {
+    if shards.len() != params.n {
+        return Err(ClayError::InvalidParameters(format!(
+            "Expected {} shards, got {}",
+            params.n,
+            shards.len()
+        )));
+    }
+    let available: HashMap<usize, &[u8]> = shards
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, shard)| shard.as_deref().map(|chunk| (idx, chunk)))
+        .collect();
+    if available.len() < params.k {
+        return Err(ClayError::TooManyErasures {
+            max: params.m,
+            actual: params.n - available.len(),
+        });
+    }
+    decode_refs(params, &available, &[])
+}
+
+/// Recover original data from a list of (index, chunk) pairs
+///
+/// Same as [`decode`], for chunk lists gathered from several sources. An
+/// index listed twice is an error instead of one copy silently replacing
+/// the other.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `pairs`: (chunk index, chunk data) pairs, each index at most once
+/// - `erasures`: Erased chunk indices, in addition to those not provided
+///
+/// # Returns
+/// Recovered original data, or error if an index repeats or decoding fails
+pub fn decode_from_pairs(
+    params: &DecodeParams,
+    pairs: &[(usize, Vec<u8>)],
+    erasures: &[usize],
+) -> Result<Vec<u8>, ClayError> {
+    let mut available: HashMap<usize, &[u8]> = HashMap::new();
+    for (idx, chunk) in pairs {
+        if available.insert(*idx, chunk.as_slice()).is_some() {
+            return Err(ClayError::InvalidParameters(format!(
+                "Chunk index {} appears more than once",
+                idx
+            )));
+        }
+    }
+    decode_refs(params, &available, erasures)
+}
+
+/// Recover original data and list the chunks the decode read
+///
+/// With only parity erased the k data chunks are read as they are and
+/// nothing else is touched. Otherwise the layered decode transforms the C
+/// values of every available chunk in every layer, so all of them are read.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `available`: Map from chunk index to chunk data
+/// - `erasures`: Erased chunk indices, in addition to those not provided
+///
+/// # Returns
+/// Recovered original data and the indices of the chunks read, ascending,
+/// or error if decoding fails
+pub fn decode_with_report(
+    params: &DecodeParams,
+    available: &HashMap<usize, Vec<u8>>,
+    erasures: &[usize],
+) -> Result<(Vec<u8>, Vec<usize>), ClayError> {
+    let erasures = infer_erasures(params, available, erasures);
+    let data = decode_strict(params, available, &erasures)?;
+    if data.is_empty() {
+        return Ok((data, Vec::new()));
+    }
+    // Same test as the systematic read in decode_into
+    let read: Vec<usize> = if erasures.iter().all(|&e| e >= params.k) {
+        (0..params.k).collect()
+    } else {
+        let mut read: Vec<usize> = available.keys().copied().collect();
+        read.sort_unstable();
+        read
+    };
+    Ok((data, read))
+}
+
+/// Recover original data from all n chunks
+///
+/// With nothing erased the data chunks hold the data as is, so this only
+/// validates the chunks and concatenates the first k.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `chunks`: Exactly n chunks in node order
+///
+/// # Returns
+/// The original data (may include padding), or error if the chunks are
+/// malformed
+pub fn decode_all(params: &DecodeParams, chunks: &[Vec<u8>]) -> Result<Vec<u8>, ClayError> {
+    if chunks.len() != params.n {
+        return Err(ClayError::InvalidParameters(format!(
+            "Expected {} chunks, got {}",
+            params.n,
+            chunks.len()
+        )));
+    }
+    validate_chunk_sizes(params, chunks.iter().map(Vec::as_slice).enumerate())?;
+    Ok(chunks[..params.k].concat())
+}
+
+/// Recover original data from available chunks, writing it to `out`
+///
+/// The k data chunks are written in order straight from the working set,
+/// so the concatenated result is never allocated.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `available`: Map from chunk index to chunk data
+/// - `erasures`: Set of erased chunk indices
+/// - `out`: Sink for the recovered data
+///
+/// # Returns
+/// Number of bytes written, or error if decoding or writing fails
+#[cfg(feature = "std")]
+pub fn decode_to_writer(
+    params: &DecodeParams,
+    available: &HashMap<usize, Vec<u8>>,
+    erasures: &[usize],
+    out: &mut impl Write,
+) -> Result<usize, ClayError> {
+    let erasures = infer_erasures(params, available, erasures);
+    decode_into(params, available, &erasures, |chunk| {
+        out.write_all(chunk)
+            .map_err(|e| ClayError::Io(format!("Data write failed: {}", e)))
+    })
+}
+
+/// Recover the k data chunks, passing each to `sink` in order
+///
+/// Returns the number of bytes passed to `sink`.
+fn decode_into<C: AsRef<[u8]>>(
+    params: &DecodeParams,
+    available: &HashMap<usize, C>,
+    erasures: &[usize],
+    mut sink: impl FnMut(&[u8]) -> Result<(), ClayError>,
+) -> Result<usize, ClayError> {
+    if validate_available(params, available, erasures)?.is_none() {
+        return Ok(0);
+    }
+
+    // Systematic read: with only parity erased the data chunks are stored
+    // as is, and the parity is not needed to read them
+    if erasures.iter().all(|&e| e >= params.k) {
+        return sink_chunks(&mut sink, (0..params.k).map(|i| available[&i].as_ref()));
+    }
+
+    // Build the shard array: available chunks are Some, erasures are None
+    let mut shards: Vec<Option<Vec<u8>>> = vec![None; params.n];
+    for (&idx, data) in available.iter() {
+        shards[idx] = Some(data.as_ref().to_vec());
+    }
+
+    decode_shards(params, &mut shards)?;
+    sink_chunks(&mut sink, shards.iter().take(params.k).flatten().map(Vec::as_slice))
+}
+
+/// Pass chunks to `sink` in order, returning the number of bytes passed
+fn sink_chunks<'a>(
+    sink: &mut impl FnMut(&[u8]) -> Result<(), ClayError>,
+    chunks: impl Iterator<Item = &'a [u8]>,
+) -> Result<usize, ClayError> {
+    let mut written = 0;
+    for chunk in chunks {
+        sink(chunk)?;
+        written += chunk.len();
+    }
+    Ok(written)
+}
+
+/// Recover original data from available chunks, taking ownership of them
+///
+/// Same as [`decode`], but the chunks are moved into the working set
+/// instead of copied, so peak memory holds one copy of the stripe.
+pub fn decode_owned(
+    params: &DecodeParams,
+    available: HashMap<usize, Vec<u8>>,
+    erasures: &[usize],
+) -> Result<Vec<u8>, ClayError> {
+    let erasures = &infer_erasures(params, &available, erasures)[..];
+    if validate_available(params, &available, erasures)?.is_none() {
         return Ok(Vec::new());
     }
+
+    // Systematic read: with only parity erased the data chunks are stored as is
+    if erasures.iter().all(|&e| e >= params.k) {
+        let mut available = available;
+        return Ok((0..params.k).flat_map(|i| available.remove(&i)).collect::<Vec<_>>().concat());
+    }
+
+    let mut shards: Vec<Option<Vec<u8>>> = vec![None; params.n];
+    for (idx, data) in available {
+        shards[idx] = Some(data);
+    }
+
+    decode_shards(params, &mut shards)?;
+    Ok(concat_data_chunks(params, shards))
+}
+
+/// Recover only the requested chunks
+///
+/// Available chunks are copied as they are. A single erased chunk is
+/// rebuilt with the low-bandwidth repair when enough helpers are present;
+/// otherwise the full layered decode runs. Nodes missing from `available`
+/// count as erased.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `available`: Map from chunk index to chunk data
+/// - `erasures`: Erased chunk indices, in addition to those not provided
+/// - `wanted`: Chunk indices to return (each 0 to n-1)
+///
+/// # Returns
+/// Map from each wanted index to its chunk, or error if decoding fails
+pub fn decode_nodes(
+    params: &DecodeParams,
+    available: &HashMap<usize, Vec<u8>>,
+    erasures: &[usize],
+    wanted: &[usize],
+) -> Result<HashMap<usize, Vec<u8>>, ClayError> {
+    for &node in wanted {
+        if node >= params.n {
+            return Err(ClayError::InvalidParameters(format!(
+                "Wanted index {} out of range [0, {})",
+                node, params.n
+            )));
+        }
+    }
+    let erasures = &infer_erasures(params, available, erasures)[..];
+    let chunk_size = match validate_available(params, available, erasures)? {
+        Some(size) => size,
+        None => return Ok(HashMap::new()),
+    };
+
+    let mut result: HashMap<usize, Vec<u8>> = wanted
+        .iter()
+        .filter_map(|node| available.get(node).map(|chunk| (*node, chunk.clone())))
+        .collect();
+    let missing: BTreeSet<usize> =
+        wanted.iter().copied().filter(|node| !result.contains_key(node)).collect();
+
+    if missing.len() == 1 {
+        let lost = *missing.iter().next().unwrap();
+        if let Ok(chunk) = repair_from_full_chunks(params, lost, available, chunk_size) {
+            result.insert(lost, chunk);
+            return Ok(result);
+        }
+    }
+
+    if !missing.is_empty() {
+        let mut shards: Vec<Option<Vec<u8>>> = vec![None; params.n];
+        for (&idx, data) in available.iter() {
+            shards[idx] = Some(data.clone());
+        }
+        decode_shards(params, &mut shards)?;
+        for node in missing {
+            result.insert(node, shards[node].take().unwrap_or_default());
+        }
+    }
+
+    Ok(result)
+}
+
+/// Rebuild the full chunks of every erased node
+///
+/// Nodes missing from `available` count as erased, as in [`decode`]. A
+/// single erasure goes through the low-bandwidth repair; several go
+/// through the full layered decode.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `available`: Map from chunk index to chunk data
+/// - `erasures`: Erased chunk indices, in addition to those not provided
+///
+/// # Returns
+/// Map from each erased index to its rebuilt chunk, or error if decoding
+/// fails
+pub fn reconstruct_chunks(
+    params: &DecodeParams,
+    available: &HashMap<usize, Vec<u8>>,
+    erasures: &[usize],
+) -> Result<HashMap<usize, Vec<u8>>, ClayError> {
+    let erasures = infer_erasures(params, available, erasures);
+    decode_nodes(params, available, &erasures, &erasures)
+}
+
+/// Recover the original data and rebuild the erased chunks in one pass
+///
+/// The layered decode fills every erased slot, data and parity alike, so
+/// the erased chunks are taken out of the working set before the data is
+/// assembled instead of decoding a second time.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `available`: Map from chunk index to chunk data
+/// - `erasures`: Erased chunk indices, in addition to those not provided
+///
+/// # Returns
+/// The original data (may include padding) and a map from each erased
+/// index to its rebuilt chunk, or error if decoding fails
+pub fn decode_and_heal(
+    params: &DecodeParams,
+    available: &HashMap<usize, Vec<u8>>,
+    erasures: &[usize],
+) -> Result<HealedStripe, ClayError> {
+    let erasures = infer_erasures(params, available, erasures);
+    if validate_available(params, available, &erasures)?.is_none() {
+        return Ok((Vec::new(), HashMap::new()));
+    }
+
+    let mut shards: Vec<Option<Vec<u8>>> = vec![None; params.n];
+    for (&idx, data) in available.iter() {
+        shards[idx] = Some(data.clone());
+    }
+    if !erasures.is_empty() {
+        decode_shards(params, &mut shards)?;
+    }
+
+    // Erased data chunks are also part of the data, so those are copied
+    let healed = erasures
+        .iter()
+        .map(|&e| {
+            let chunk = if e < params.k { shards[e].clone() } else { shards[e].take() };
+            (e, chunk.unwrap_or_default())
+        })
+        .collect();
+    Ok((concat_data_chunks(params, shards), healed))
+}
+
+/// Read a byte range of the original data, decoding only the layers it needs
+///
+/// Data byte `i` lives in data chunk `i / chunk_size`. If every data chunk
+/// the range touches is available the bytes are copied directly; otherwise
+/// only the layers covering the range in the erased chunks, plus the layers
+/// they depend on, are decoded. Nodes missing from `available` count as
+/// erased.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `available`: Map from chunk index to chunk data
+/// - `erasures`: Erased chunk indices, in addition to those not provided
+/// - `byte_range`: Range of the (padded) data, within 0..k * chunk_size
+///
+/// # Returns
+/// The bytes in `byte_range`, or error if the range is out of bounds or
+/// decoding fails
+pub fn read_range(
+    params: &DecodeParams,
+    available: &HashMap<usize, Vec<u8>>,
+    erasures: &[usize],
+    byte_range: Range<usize>,
+) -> Result<Vec<u8>, ClayError> {
+    let erasures = &infer_erasures(params, available, erasures)[..];
+    let chunk_size = validate_available(params, available, erasures)?.unwrap_or(0);
+    let data_len = params.k * chunk_size;
+    if byte_range.start > byte_range.end || byte_range.end > data_len {
+        return Err(ClayError::InvalidParameters(format!(
+            "Byte range {:?} out of bounds for {} data bytes",
+            byte_range, data_len
+        )));
+    }
+    if byte_range.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Per data node, the part of the range it holds
+    let first = byte_range.start / chunk_size;
+    let last = (byte_range.end - 1) / chunk_size;
+    let pieces: Vec<(usize, Range<usize>)> = (first..=last)
+        .map(|node| {
+            let base = node * chunk_size;
+            let start = byte_range.start.max(base) - base;
+            let end = byte_range.end.min(base + chunk_size) - base;
+            (node, start..end)
+        })
+        .collect();
+
+    let sub_chunk_size = chunk_size / params.sub_chunk_no;
+    let wanted_layers: Vec<usize> = pieces
+        .iter()
+        .filter(|(node, _)| !available.contains_key(node))
+        .flat_map(|(_, r)| r.start / sub_chunk_size..=(r.end - 1) / sub_chunk_size)
+        .collect();
+
+    let mut out = Vec::with_capacity(byte_range.len());
+    if wanted_layers.is_empty() {
+        for (node, r) in pieces {
+            out.extend_from_slice(&available[&node][r]);
+        }
+        return Ok(out);
+    }
+
+    // Lay the stripe out by internal index; shortened nodes are known zeros
+    let total_nodes = params.q * params.t;
+    let mut chunks: Vec<Vec<u8>> = vec![vec![0u8; chunk_size]; total_nodes];
+    for (&idx, data) in available {
+        chunks[params.to_internal(idx)].copy_from_slice(data);
+    }
+    let erased_set: BTreeSet<usize> = erasures.iter().map(|&e| params.to_internal(e)).collect();
+
+    decode_layered_subset(params, &erased_set, &mut chunks, sub_chunk_size, &wanted_layers)?;
+
+    for (node, r) in pieces {
+        out.extend_from_slice(&chunks[node][r]);
+    }
+    Ok(out)
+}
+
+/// Add every chunk missing from `available` to the erasures
+///
+/// With no chunks at all the erasures are returned unchanged, so that
+/// validation reports the empty input as before.
+///
+/// # Parameters
+/// - `params`: Decoding parameters
+/// - `available`: Chunks present, keyed by index
+/// - `erasures`: Indices the caller marked as erased
+///
+/// # Returns
+/// `erasures` plus every index in [0, n) that `available` does not provide
+fn infer_erasures<C>(
+    params: &DecodeParams,
+    available: &HashMap<usize, C>,
+    erasures: &[usize],
+) -> Vec<usize> {
+    let mut inferred = erasures.to_vec();
+    if !available.is_empty() {
+        inferred.extend(
+            (0..params.n).filter(|node| !available.contains_key(node) && !erasures.contains(node)),
+        );
+    }
+    inferred
+}
+
+/// Check that chunks share one size that splits into α sub-chunks
+///
+/// The first chunk sets the size.
+///
+/// # Parameters
+/// - `params`: Decoding parameters
+/// - `chunks`: (index, chunk) pairs; the index is only used in errors
+///
+/// # Returns
+/// The common chunk size, 0 if there are no chunks, or error if a size is
+/// invalid or differs from the first
+fn validate_chunk_sizes<'a>(
+    params: &DecodeParams,
+    mut chunks: impl Iterator<Item = (usize, &'a [u8])>,
+) -> Result<usize, ClayError> {
+    let chunk_size = match chunks.next() {
+        Some((_, first)) => first.len(),
+        None => return Ok(0),
+    };
+
+    // Validate chunk_size splits into sub-chunks of at least 2 bytes
+    validate_sub_chunk_size(params.sub_chunk_no, chunk_size)?;
+
+    // Validate all chunks have same size
+    for (idx, chunk) in chunks {
+        if chunk.len() != chunk_size {
+            return Err(ClayError::InconsistentChunkSizes {
+                first_size: chunk_size,
+                mismatched_idx: idx,
+                mismatched_size: chunk.len(),
+            });
+        }
+    }
+    Ok(chunk_size)
+}
+
+/// Validate the inputs of [`decode`] and [`decode_owned`]
+///
+/// # Parameters
+/// - `params`: Decoding parameters
+/// - `available`: Chunks present, keyed by index
+/// - `erasures`: Indices to recover
+///
+/// # Returns
+/// The common chunk size, None when there is nothing to decode, or error
+/// if an index, the erasure count or a chunk size is invalid
+fn validate_available<C: AsRef<[u8]>>(
+    params: &DecodeParams,
+    available: &HashMap<usize, C>,
+    erasures: &[usize],
+) -> Result<Option<usize>, ClayError> {
+    if available.is_empty() && erasures.is_empty() {
+        return Ok(None);
+    }
     if available.is_empty() {
         return Err(ClayError::InvalidParameters(
             "No available chunks provided but erasures are non-empty".into(),
         ));
     }
 
-    // Validate erasure count
+    // Validate erasure count
+    if erasures.len() > params.m {
+        return Err(ClayError::TooManyErasures {
+            max: params.m,
+            actual: erasures.len(),
+        });
+    }
+
+    let chunk_size =
+        validate_chunk_sizes(params, available.iter().map(|(&idx, c)| (idx, c.as_ref())))?;
+
+    // Validate chunk indices are in valid range
+    for &idx in available.keys() {
+        if idx >= params.n {
+            return Err(ClayError::InvalidParameters(format!(
+                "Chunk index {} out of range [0, {})",
+                idx, params.n
+            )));
+        }
+    }
+    for &e in erasures {
+        if e >= params.n {
+            return Err(ClayError::InvalidParameters(format!(
+                "Erasure index {} out of range [0, {})",
+                e, params.n
+            )));
+        }
+    }
+
+    // Validate consistency between available and erasures
+    // 1. Erasures and available keys must be disjoint
+    for &e in erasures {
+        if available.contains_key(&e) {
+            return Err(ClayError::InvalidParameters(format!(
+                "Node {} is both in available chunks and marked as erased",
+                e
+            )));
+        }
+    }
+
+    // 2. We need exactly n - erasures.len() available chunks
+    let expected_available = params.n - erasures.len();
+    if available.len() != expected_available {
+        return Err(ClayError::InvalidParameters(format!(
+            "Expected {} available chunks (n={} - erasures={}), but got {}",
+            expected_available,
+            params.n,
+            erasures.len(),
+            available.len()
+        )));
+    }
+
+    // 3. All non-erased nodes must be present in available
+    for node in 0..params.n {
+        if !erasures.contains(&node) && !available.contains_key(&node) {
+            return Err(ClayError::InvalidParameters(format!(
+                "Node {} is neither erased nor provided in available chunks",
+                node
+            )));
+        }
+    }
+
+    Ok(Some(chunk_size))
+}
+
+/// Extract original data from the first k chunks of a decoded shard array
+fn concat_data_chunks(params: &DecodeParams, shards: Vec<Option<Vec<u8>>>) -> Vec<u8> {
+    shards.into_iter().take(params.k).flatten().collect::<Vec<_>>().concat()
+}
+
+/// Recover data encoded with `encode::encode_with_length`, trimmed to its exact length
+///
+/// Decodes as usual, then reads the 8-byte little-endian length header at
+/// the start of the first data chunk and returns exactly that many bytes.
+pub fn decode_exact(
+    params: &DecodeParams,
+    available: &HashMap<usize, Vec<u8>>,
+    erasures: &[usize],
+) -> Result<Vec<u8>, ClayError> {
+    strip_length_header(decode(params, available, erasures)?)
+}
+
+/// Recover an object encoded with `encode::encode_object`
+///
+/// Each stripe is decoded from its own survivors, as by [`decode`], and
+/// the object is trimmed to the length in its manifest.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `manifest`: The manifest `encode_object` returned
+/// - `stripes`: Surviving chunks of every stripe, in stripe order; each map
+///   needs at least k chunks
+///
+/// # Returns
+/// The original object, or error if any stripe fails to decode or the
+/// stripes do not match the manifest
+pub fn decode_object(
+    params: &DecodeParams,
+    manifest: &ObjectManifest,
+    stripes: &[HashMap<usize, Vec<u8>>],
+) -> Result<Vec<u8>, ClayError> {
+    if stripes.len() != manifest.stripe_count {
+        return Err(ClayError::InvalidParameters(format!(
+            "Expected {} stripes, got {}",
+            manifest.stripe_count,
+            stripes.len()
+        )));
+    }
+
+    let mut decoded = Vec::with_capacity(manifest.stripe_count * params.k * manifest.chunk_size);
+    for (stripe, available) in stripes.iter().enumerate() {
+        if let Some((&idx, chunk)) =
+            available.iter().find(|(_, chunk)| chunk.len() != manifest.chunk_size)
+        {
+            return Err(ClayError::InvalidParameters(format!(
+                "Stripe {} chunk {} has {} bytes, the manifest says {}",
+                stripe,
+                idx,
+                chunk.len(),
+                manifest.chunk_size
+            )));
+        }
+        decoded.extend(decode(params, available, &[])?);
+    }
+    if manifest.len > decoded.len() {
+        return Err(ClayError::InvalidParameters(format!(
+            "Object is {} bytes but only {} were decoded",
+            manifest.len,
+            decoded.len()
+        )));
+    }
+    decoded.truncate(manifest.len);
+    Ok(decoded)
+}
+
+/// Remove the length header `encode::encode_with_length` wrote and trim
+/// the padding after the data
+fn strip_length_header(mut decoded: Vec<u8>) -> Result<Vec<u8>, ClayError> {
+    if decoded.len() < LENGTH_HEADER_SIZE {
+        return Err(ClayError::InvalidParameters(format!(
+            "Decoded data is {} bytes, too short for the {}-byte length header",
+            decoded.len(),
+            LENGTH_HEADER_SIZE
+        )));
+    }
+
+    let mut header = [0u8; LENGTH_HEADER_SIZE];
+    header.copy_from_slice(&decoded[..LENGTH_HEADER_SIZE]);
+    let len = u64::from_le_bytes(header);
+    let available_len = decoded.len() - LENGTH_HEADER_SIZE;
+    if len > available_len as u64 {
+        return Err(ClayError::InvalidParameters(format!(
+            "Length header claims {} bytes but only {} were decoded",
+            len, available_len
+        )));
+    }
+
+    decoded.drain(..LENGTH_HEADER_SIZE);
+    decoded.truncate(len as usize);
+    Ok(decoded)
+}
+
+/// Recover data encoded with `PaddingScheme::Pkcs7`, with the padding removed
+///
+/// Decodes as usual, then checks that the data ends in p bytes of value p
+/// for some 1 <= p <= 255 and strips them.
+pub fn decode_unpad(
+    params: &DecodeParams,
+    available: &HashMap<usize, Vec<u8>>,
+    erasures: &[usize],
+) -> Result<Vec<u8>, ClayError> {
+    let mut decoded = decode(params, available, erasures)?;
+    let padding = decoded.last().map_or(0, |&last| usize::from(last));
+    let valid = padding > 0
+        && padding <= decoded.len()
+        && decoded[decoded.len() - padding..].iter().all(|&b| usize::from(b) == padding);
+    if !valid {
+        return Err(ClayError::InvalidParameters(
+            "Decoded data does not end in valid PKCS#7 padding".into(),
+        ));
+    }
+    decoded.truncate(decoded.len() - padding);
+    Ok(decoded)
+}
+
+/// Reconstruct erased shards in place
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `shards`: Exactly n shards in node order; `None` marks an erased node
+///
+/// # Returns
+/// Ok once every `None` slot has been filled with its reconstructed chunk,
+/// or error if the shard array is malformed or decoding fails. Available
+/// shards are left untouched.
+pub fn decode_shards(
+    params: &DecodeParams,
+    shards: &mut [Option<Vec<u8>>],
+) -> Result<(), ClayError> {
+    decode_shards_with_scratch(params, shards)?;
+    Ok(())
+}
+
+/// [`decode_shards`], returning the working buffers of the layered decode
+/// if one ran
+fn decode_shards_with_scratch(
+    params: &DecodeParams,
+    shards: &mut [Option<Vec<u8>>],
+) -> Result<Option<LayerScratch>, ClayError> {
+    if shards.len() != params.n {
+        return Err(ClayError::InvalidParameters(format!(
+            "Expected {} shards, got {}",
+            params.n,
+            shards.len()
+        )));
+    }
+
+    let erasures: Vec<usize> = (0..params.n).filter(|&i| shards[i].is_none()).collect();
     if erasures.len() > params.m {
         return Err(ClayError::TooManyErasures {
             max: params.m,
             actual: erasures.len(),
         });
     }
-
-    // Get chunk size from first available chunk and validate all chunks match
-    let mut iter = available.iter();
-    let (_, first_chunk) = iter.next().unwrap();
-    let chunk_size = first_chunk.len();
-
-    // Validate chunk_size is divisible by sub_chunk_no
-    if chunk_size == 0 || chunk_size % params.sub_chunk_no != 0 {
-        return Err(ClayError::InvalidChunkSize {
-            expected: params.sub_chunk_no,
-            actual: chunk_size,
-        });
+    if erasures.is_empty() {
+        return Ok(None);
     }
 
-    // Validate all chunks have same size
-    for (&idx, chunk) in iter {
-        if chunk.len() != chunk_size {
+    // Get chunk size from first present shard and validate all shards match
+    let mut present = shards
+        .iter()
+        .enumerate()
+        .filter_map(|(i, s)| s.as_ref().map(|c| (i, c.len())));
+    let (_, chunk_size) = present.next().ok_or_else(|| {
+        ClayError::InvalidParameters("No shards present".into())
+    })?;
+
+    validate_sub_chunk_size(params.sub_chunk_no, chunk_size)?;
+    for (idx, len) in present {
+        if len != chunk_size {
             return Err(ClayError::InconsistentChunkSizes {
                 first_size: chunk_size,
                 mismatched_idx: idx,
-                mismatched_size: chunk.len(),
+                mismatched_size: len,
             });
         }
     }
 
-    // Validate chunk indices are in valid range
-    for &idx in available.keys() {
-        if idx >= params.n {
-            return Err(ClayError::InvalidParameters(format!(
-                "Chunk index {} out of range [0, {})",
-                idx, params.n
-            )));
-        }
+    let sub_chunk_size = chunk_size / params.sub_chunk_no;
+    let total_nodes = params.q * params.t;
+
+    // Build full chunks array with proper node indices. Present shards are
+    // moved in (not copied) and handed back once decoding is done.
+    let mut chunks: Vec<Vec<u8>> = vec![Vec::new(); total_nodes];
+    for chunk in &mut chunks[params.k..params.k + params.nu] {
+        // Shortened nodes are KNOWN zeros, not erasures
+        *chunk = vec![0u8; chunk_size];
     }
-    for &e in erasures {
-        if e >= params.n {
-            return Err(ClayError::InvalidParameters(format!(
-                "Erasure index {} out of range [0, {})",
-                e, params.n
-            )));
-        }
+    for (idx, shard) in shards.iter_mut().enumerate() {
+        chunks[params.to_internal(idx)] = shard.take().unwrap_or_else(|| vec![0u8; chunk_size]);
     }
 
-    // Validate consistency between available and erasures
-    // 1. Erasures and available keys must be disjoint
-    for &e in erasures {
-        if available.contains_key(&e) {
-            return Err(ClayError::InvalidParameters(format!(
-                "Node {} is both in available chunks and marked as erased",
-                e
-            )));
-        }
-    }
+    // Build erasure set with internal indices
+    let erased_set: BTreeSet<usize> = erasures.iter().map(|&e| params.to_internal(e)).collect();
 
-    // 2. We need exactly n - erasures.len() available chunks
-    let expected_available = params.n - erasures.len();
-    if available.len() != expected_available {
-        return Err(ClayError::InvalidParameters(format!(
-            "Expected {} available chunks (n={} - erasures={}), but got {}",
-            expected_available,
-            params.n,
-            erasures.len(),
-            available.len()
-        )));
-    }
+    let mut scratch = LayerScratch::new(params, chunk_size);
+    let result =
+        decode_layered_reusing(params, &erased_set, &mut chunks, sub_chunk_size, &mut scratch);
 
-    // 3. All non-erased nodes must be present in available
-    for node in 0..params.n {
-        if !erasures.contains(&node) && !available.contains_key(&node) {
-            return Err(ClayError::InvalidParameters(format!(
-                "Node {} is neither erased nor provided in available chunks",
-                node
-            )));
+    // Hand every chunk back; erased slots are only filled on success
+    for (idx, shard) in shards.iter_mut().enumerate() {
+        let chunk = core::mem::take(&mut chunks[params.to_internal(idx)]);
+        if result.is_ok() || !erasures.contains(&idx) {
+            *shard = Some(chunk);
         }
     }
 
-    let sub_chunk_size = chunk_size / params.sub_chunk_no;
-    let total_nodes = params.q * params.t;
+    result.map(|()| Some(scratch))
+}
 
-    // Build full chunks array with proper node indices
-    let mut chunks: Vec<Vec<u8>> = vec![vec![0u8; chunk_size]; total_nodes];
+/// Chunk buffers of the layered decode, indexed by internal node
+///
+/// Lets the decode run over views that map internal nodes onto
+/// caller-provided buffers as well as over plain chunk vectors.
+pub(crate) trait NodeBuffers: Sync {
+    /// Chunk of internal node `node`
+    fn node(&self, node: usize) -> &[u8];
+
+    /// Mutable chunk of internal node `node`
+    fn node_mut(&mut self, node: usize) -> &mut [u8];
+}
 
-    // Copy available chunks, mapping from external (k data + m parity) to internal indices
-    for (&idx, data) in available.iter() {
-        let internal_idx = if idx < params.k { idx } else { idx + params.nu };
-        chunks[internal_idx] = data.clone();
+impl NodeBuffers for [Vec<u8>] {
+    fn node(&self, node: usize) -> &[u8] {
+        &self[node]
     }
 
-    // Build erasure set with internal indices
-    // Note: shortened nodes are NOT erasures - they are known zeros
-    let mut erased_set: BTreeSet<usize> = BTreeSet::new();
-    for &e in erasures {
-        let internal_idx = if e < params.k { e } else { e + params.nu };
-        erased_set.insert(internal_idx);
+    fn node_mut(&mut self, node: usize) -> &mut [u8] {
+        &mut self[node]
     }
+}
 
-    // Shortened nodes are KNOWN zeros, already set in chunks array
-    // They should NOT be added to erased_set
-
-    // Decode
-    decode_layered(params, &erased_set, &mut chunks, sub_chunk_size)?;
+/// Working buffers of the layered decode for one chunk size
+///
+/// Reusing one across stripes avoids reallocating them. Without the
+/// `rayon` feature the layered decode allocates nothing else.
+#[derive(Debug)]
+pub struct LayerScratch {
+    /// U values of every node, laid out like the chunks
+    u_buf: Vec<Vec<u8>>,
+    /// Which U values have been computed, per node and layer
+    u_computed: Vec<Vec<bool>>,
+    /// Intersection score of every layer
+    order: Vec<usize>,
+    /// Layers of the intersection score being decoded
+    layers: Vec<usize>,
+    /// U values of one layer as RS shards, flagged present when known
+    /// (with `rayon` each layer of a level gets its own)
+    #[cfg(not(feature = "rayon"))]
+    layer_u: Vec<(Vec<u8>, bool)>,
+    /// Scratch sub-chunks for the C values of the second pass
+    scratch_a: Vec<u8>,
+    scratch_b: Vec<u8>,
+    /// Phase timings of the last decode
+    timings: Timings,
+}
 
-    // Extract original data from first k chunks
-    let mut result = Vec::with_capacity(params.k * chunk_size);
-    for i in 0..params.k {
-        result.extend_from_slice(&chunks[i]);
+impl LayerScratch {
+    /// Allocate buffers for chunks of `chunk_size` bytes
+    pub fn new(params: &DecodeParams, chunk_size: usize) -> Self {
+        let total_nodes = params.q * params.t;
+        let sub_chunk_size = chunk_size / params.sub_chunk_no;
+        LayerScratch {
+            u_buf: vec![vec![0u8; chunk_size]; total_nodes],
+            u_computed: vec![vec![false; params.sub_chunk_no]; total_nodes],
+            order: vec![0; params.sub_chunk_no],
+            layers: Vec::with_capacity(params.sub_chunk_no),
+            #[cfg(not(feature = "rayon"))]
+            layer_u: vec![(vec![0u8; sub_chunk_size], true); total_nodes],
+            scratch_a: vec![0u8; sub_chunk_size],
+            scratch_b: vec![0u8; sub_chunk_size],
+            timings: Timings::default(),
+        }
     }
 
-    Ok(result)
+    /// Phase timings recorded by the last decode using these buffers
+    pub(crate) fn timings(&self) -> Timings {
+        self.timings
+    }
 }
 
 /// Main layered decoding algorithm
@@ -167,150 +1009,366 @@ pub fn decode(
 pub fn decode_layered(
     params: &DecodeParams,
     erased_chunks: &BTreeSet<usize>,
-    chunks: &mut Vec<Vec<u8>>,
+    chunks: &mut [Vec<u8>],
     sub_chunk_size: usize,
 ) -> Result<(), ClayError> {
-    let total_nodes = params.q * params.t;
+    let mut scratch = LayerScratch::new(params, chunks[0].len());
+    decode_layered_reusing(params, erased_chunks, chunks, sub_chunk_size, &mut scratch)
+}
+
+/// Layered decoding with caller-provided working buffers
+///
+/// Same as [`decode_layered`]; `scratch` must have been created for the
+/// same code and chunk size.
+pub fn decode_layered_reusing(
+    params: &DecodeParams,
+    erased_chunks: &BTreeSet<usize>,
+    chunks: &mut [Vec<u8>],
+    sub_chunk_size: usize,
+    scratch: &mut LayerScratch,
+) -> Result<(), ClayError> {
+    decode_selected_layers(params, erased_chunks, chunks, sub_chunk_size, None, scratch)
+}
+
+/// [`decode_layered_reusing`] over any [`NodeBuffers`]
+pub(crate) fn decode_layered_over<B: NodeBuffers + ?Sized>(
+    params: &DecodeParams,
+    erased_chunks: &BTreeSet<usize>,
+    chunks: &mut B,
+    sub_chunk_size: usize,
+    scratch: &mut LayerScratch,
+) -> Result<(), ClayError> {
+    decode_selected_layers(params, erased_chunks, chunks, sub_chunk_size, None, scratch)
+}
 
-    // Create RS codec once for all layers
-    let rs = reed_solomon_erasure::ReedSolomon::<galois_8::Field>::new(
-        params.original_count,
-        params.recovery_count,
+/// Layered decoding restricted to the layers needed for `wanted_layers`
+///
+/// Recovers the erased C values of every wanted layer. The lower-score
+/// layers they depend on through erased companions are decoded too; all
+/// other layers are skipped and their erased C values left untouched.
+pub fn decode_layered_subset(
+    params: &DecodeParams,
+    erased_chunks: &BTreeSet<usize>,
+    chunks: &mut [Vec<u8>],
+    sub_chunk_size: usize,
+    wanted_layers: &[usize],
+) -> Result<(), ClayError> {
+    let included = layer_dependencies(params, erased_chunks, wanted_layers);
+    let mut scratch = LayerScratch::new(params, chunks[0].len());
+    decode_selected_layers(
+        params,
+        erased_chunks,
+        chunks,
+        sub_chunk_size,
+        Some(&included),
+        &mut scratch,
     )
-    .map_err(|e| ClayError::ReconstructionFailed(format!("RS init failed: {:?}", e)))?;
+}
 
-    // Initialize U buffers
-    let chunk_size = chunks[0].len();
-    let mut u_buf: Vec<Vec<u8>> = vec![vec![0u8; chunk_size]; total_nodes];
+/// Mark the wanted layers and every layer they depend on
+///
+/// A layer needs the companion layer of each pair whose companion node is
+/// erased: a surviving node reads the erased companion's U from there, and
+/// an erased pair needs both U values to recover its C values.
+fn layer_dependencies(
+    params: &DecodeParams,
+    erased_chunks: &BTreeSet<usize>,
+    wanted_layers: &[usize],
+) -> Vec<bool> {
+    let mut included = vec![false; params.sub_chunk_no];
+    let mut pending: Vec<usize> = Vec::new();
+    for &z in wanted_layers {
+        if !included[z] {
+            included[z] = true;
+            pending.push(z);
+        }
+    }
+
+    while let Some(z) = pending.pop() {
+        for (y, &z_y) in params.plane_vector(z).iter().enumerate() {
+            if !erased_chunks.contains(&(y * params.q + z_y)) {
+                continue;
+            }
+            for x in (0..params.q).filter(|&x| x != z_y) {
+                let z_sw = get_companion_layer(params, z, x, y);
+                if !included[z_sw] {
+                    included[z_sw] = true;
+                    pending.push(z_sw);
+                }
+            }
+        }
+    }
+
+    included
+}
 
-    // Track which U values have been computed (for using across iterations)
-    let mut u_computed: Vec<Vec<bool>> = vec![vec![false; params.sub_chunk_no]; total_nodes];
+/// Layered decoding over all layers, or only those marked in `included`
+fn decode_selected_layers<B: NodeBuffers + ?Sized>(
+    params: &DecodeParams,
+    erased_chunks: &BTreeSet<usize>,
+    chunks: &mut B,
+    sub_chunk_size: usize,
+    included: Option<&[bool]>,
+    scratch: &mut LayerScratch,
+) -> Result<(), ClayError> {
+    // No U value is known yet (the buffers may hold a previous stripe)
+    for computed in &mut scratch.u_computed {
+        computed.fill(false);
+    }
+    scratch.timings = Timings::default();
 
     // Compute layer order by intersection score
-    let mut order: Vec<usize> = vec![0; params.sub_chunk_no];
-    set_planes_sequential_decoding_order(params, &mut order, erased_chunks);
+    set_planes_sequential_decoding_order(params, &mut scratch.order, erased_chunks);
 
     let max_iscore = get_max_iscore(params, erased_chunks);
 
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!(
+        "decode_layered",
+        erasures = ?erased_chunks,
+        sub_chunk_no = params.sub_chunk_no,
+        max_iscore
+    )
+    .entered();
+
     // Process layers in order of increasing intersection score
     for iscore in 0..=max_iscore {
-        // First pass: decode erasures for layers with this iscore
-        for z in 0..params.sub_chunk_no {
-            if order[z] == iscore {
-                decode_layered_with_tracking(
-                    params,
-                    erased_chunks,
-                    z,
-                    chunks,
-                    &mut u_buf,
-                    &mut u_computed,
-                    sub_chunk_size,
-                    &rs,
-                )?;
-            }
-        }
+        let order = &scratch.order;
+        scratch.layers.clear();
+        scratch.layers.extend(
+            order
+                .iter()
+                .enumerate()
+                .filter(|&(z, &score)| score == iscore && included.map_or(true, |inc| inc[z]))
+                .map(|(z, _)| z),
+        );
+
+        #[cfg(feature = "tracing")]
+        let _level = tracing::debug_span!("iscore_level", iscore, layers = scratch.layers.len())
+            .entered();
+
+        // First pass: decode U values for layers with this iscore
+        decode_level(params, erased_chunks, chunks, sub_chunk_size, scratch)?;
 
         // Second pass: recover C values from U values
-        for z in 0..params.sub_chunk_no {
-            if order[z] == iscore {
-                let z_vec = get_plane_vector(z, params.t, params.q);
-
-                for &node_xy in erased_chunks {
-                    let x = node_xy % params.q;
-                    let y = node_xy / params.q;
-                    let z_y = z_vec[y];
-                    let node_sw = y * params.q + z_y;
-                    let z_sw = get_companion_layer(params, z, x, y, z_y);
-
-                    if z_y != x {
-                        if !erased_chunks.contains(&node_sw) {
-                            // Type 1: companion is not erased
-                            recover_type1_erasure(
-                                params,
-                                chunks,
-                                &u_buf,
-                                x,
-                                y,
-                                z,
-                                z_y,
-                                z_sw,
-                                sub_chunk_size,
-                            );
-                        } else if z_y < x {
-                            // Both erased, process once (when z_y < x)
-                            get_coupled_from_uncoupled(
-                                params, chunks, &u_buf, x, y, z, z_y, z_sw, sub_chunk_size,
-                            );
-                        }
-                    } else {
-                        // Red vertex: C = U
-                        let offset = z * sub_chunk_size;
-                        chunks[node_xy][offset..offset + sub_chunk_size]
-                            .copy_from_slice(&u_buf[node_xy][offset..offset + sub_chunk_size]);
+        #[cfg(feature = "profile")]
+        let start = std::time::Instant::now();
+        let LayerScratch { u_buf, layers, scratch_a, scratch_b, .. } = &mut *scratch;
+        for &z in layers.iter() {
+            let z_vec = params.plane_vector(z);
+
+            for &node_xy in erased_chunks {
+                let x = node_xy % params.q;
+                let y = node_xy / params.q;
+                let z_y = z_vec[y];
+                let node_sw = y * params.q + z_y;
+                let z_sw = get_companion_layer(params, z, x, y);
+
+                if z_y != x {
+                    if !erased_chunks.contains(&node_sw) {
+                        // Type 1: companion is not erased
+                        recover_type1_erasure(
+                            params,
+                            chunks,
+                            u_buf,
+                            x,
+                            y,
+                            z,
+                            z_y,
+                            z_sw,
+                            sub_chunk_size,
+                            scratch_a,
+                        );
+                    } else if z_y < x {
+                        // Both erased, process once (when z_y < x)
+                        get_coupled_from_uncoupled(
+                            params,
+                            chunks,
+                            u_buf,
+                            x,
+                            y,
+                            z,
+                            z_y,
+                            z_sw,
+                            sub_chunk_size,
+                            scratch_a,
+                            scratch_b,
+                        );
                     }
+                } else {
+                    // Red vertex: C = U
+                    let offset = z * sub_chunk_size;
+                    chunks.node_mut(node_xy)[offset..offset + sub_chunk_size]
+                        .copy_from_slice(&u_buf[node_xy][offset..offset + sub_chunk_size]);
                 }
             }
         }
+        #[cfg(feature = "profile")]
+        scratch.timings.record_transforms(start);
     }
 
     Ok(())
 }
 
-/// Decode erasures for a single layer with U tracking
-fn decode_layered_with_tracking(
+/// Decode the U values of all layers sharing one intersection score
+///
+/// A layer only reads U values of layers with a lower score, so the layers
+/// of one level are decoded independently and stored afterwards.
+#[cfg(not(feature = "rayon"))]
+fn decode_level<B: NodeBuffers + ?Sized>(
     params: &DecodeParams,
     erased_chunks: &BTreeSet<usize>,
-    z: usize,
-    chunks: &[Vec<u8>],
+    chunks: &B,
+    sub_chunk_size: usize,
+    scratch: &mut LayerScratch,
+) -> Result<(), ClayError> {
+    let LayerScratch { u_buf, u_computed, layers, layer_u, timings, .. } = scratch;
+
+    for &z in layers.iter() {
+        decode_layered_with_tracking(
+            params,
+            erased_chunks,
+            z,
+            chunks,
+            u_buf,
+            u_computed,
+            sub_chunk_size,
+            layer_u,
+            timings,
+        )?;
+        store_layer(u_buf, u_computed, z, layer_u, sub_chunk_size);
+    }
+
+    Ok(())
+}
+
+/// Decode the U values of all layers sharing one intersection score
+///
+/// Layers of one level are independent, so they are decoded in parallel
+/// into their own buffers and stored once the whole level is done.
+#[cfg(feature = "rayon")]
+fn decode_level<B: NodeBuffers + ?Sized>(
+    params: &DecodeParams,
+    erased_chunks: &BTreeSet<usize>,
+    chunks: &B,
+    sub_chunk_size: usize,
+    scratch: &mut LayerScratch,
+) -> Result<(), ClayError> {
+    use rayon::prelude::*;
+
+    let total_nodes = params.q * params.t;
+    let LayerScratch { u_buf, u_computed, layers, .. } = scratch;
+    let (u_prev, computed_prev) = (&*u_buf, &*u_computed);
+
+    let decoded = layers
+        .par_iter()
+        .map(|&z| {
+            let mut layer_u = vec![(vec![0u8; sub_chunk_size], true); total_nodes];
+            let mut layer_timings = Timings::default();
+            decode_layered_with_tracking(
+                params,
+                erased_chunks,
+                z,
+                chunks,
+                u_prev,
+                computed_prev,
+                sub_chunk_size,
+                &mut layer_u,
+                &mut layer_timings,
+            )?;
+            Ok((z, layer_u, layer_timings))
+        })
+        .collect::<Result<Vec<_>, ClayError>>()?;
+
+    for (z, layer_u, _) in &decoded {
+        store_layer(u_buf, u_computed, *z, layer_u, sub_chunk_size);
+    }
+    #[cfg(feature = "profile")]
+    for (_, _, layer_timings) in &decoded {
+        scratch.timings.add(layer_timings);
+    }
+
+    Ok(())
+}
+
+/// Copy a decoded layer into the U buffer and mark it as computed
+fn store_layer(
     u_buf: &mut [Vec<u8>],
     u_computed: &mut [Vec<bool>],
+    z: usize,
+    layer_u: &[(Vec<u8>, bool)],
+    sub_chunk_size: usize,
+) {
+    let offset = z * sub_chunk_size;
+    for (node, (u, _)) in layer_u.iter().enumerate() {
+        u_buf[node][offset..offset + sub_chunk_size].copy_from_slice(u);
+        u_computed[node][z] = true;
+    }
+}
+
+/// Decode erasures for a single layer with U tracking
+///
+/// Reads U values of lower-score layers from `u_buf` and writes every U
+/// value of layer `z` into `layer_u` (one sub-chunk per node). The layer's
+/// transform and RS time is added to `timings`.
+#[allow(clippy::too_many_arguments)]
+#[cfg_attr(not(feature = "profile"), allow(unused_variables))]
+fn decode_layered_with_tracking<B: NodeBuffers + ?Sized>(
+    params: &DecodeParams,
+    erased_chunks: &BTreeSet<usize>,
+    z: usize,
+    chunks: &B,
+    u_buf: &[Vec<u8>],
+    u_computed: &[Vec<bool>],
     sub_chunk_size: usize,
-    rs: &reed_solomon_erasure::ReedSolomon<galois_8::Field>,
+    layer_u: &mut [(Vec<u8>, bool)],
+    timings: &mut Timings,
 ) -> Result<(), ClayError> {
-    let z_vec = get_plane_vector(z, params.t, params.q);
+    #[cfg(feature = "profile")]
+    let start = std::time::Instant::now();
+    let z_vec = params.plane_vector(z);
+    let offset_z = z * sub_chunk_size;
 
-    // Track nodes that need MDS recovery for this layer
-    let mut needs_mds: BTreeSet<usize> = erased_chunks.clone();
+    // Track nodes that need MDS recovery for this layer: a shard is
+    // flagged absent when its U value is unknown
+    for (node, (_, present)) in layer_u.iter_mut().enumerate() {
+        *present = !erased_chunks.contains(&node);
+    }
 
     // Compute U values for non-erased nodes
     for x in 0..params.q {
-        for y in 0..params.t {
+        for (y, &z_y) in z_vec.iter().enumerate() {
             let node_xy = params.q * y + x;
-            let z_y = z_vec[y];
             let node_sw = params.q * y + z_y;
-            let z_sw = get_companion_layer(params, z, x, y, z_y);
+            let z_sw = get_companion_layer(params, z, x, y);
+            let offset_zsw = z_sw * sub_chunk_size;
 
             if !erased_chunks.contains(&node_xy) {
+                let c_xy = &chunks.node(node_xy)[offset_z..offset_z + sub_chunk_size];
+
                 if z_y == x {
                     // Red vertex: U = C (no companion needed)
-                    let offset = z * sub_chunk_size;
-                    u_buf[node_xy][offset..offset + sub_chunk_size]
-                        .copy_from_slice(&chunks[node_xy][offset..offset + sub_chunk_size]);
-                    u_computed[node_xy][z] = true;
+                    layer_u[node_xy].0.copy_from_slice(c_xy);
                 } else if !erased_chunks.contains(&node_sw) {
-                    // Both nodes available - apply PRT (only process once when z_y < x)
-                    if z_y < x {
-                        get_uncoupled_from_coupled(
-                            params, chunks, u_buf, x, y, z, z_y, z_sw, sub_chunk_size,
-                        );
-                        u_computed[node_xy][z] = true;
-                        u_computed[node_sw][z_sw] = true;
-                    }
+                    // Both nodes available - apply PRT for this side of the pair;
+                    // the companion layer computes the other side itself
+                    let c_sw = &chunks.node(node_sw)[offset_zsw..offset_zsw + sub_chunk_size];
+                    prt_compute_single_into(c_xy, c_sw, params.mul, &mut layer_u[node_xy].0);
                 } else {
                     // Companion is erased - check if companion's U* is available
                     // from a previous iteration (lower intersection score layer)
                     if u_computed[node_sw][z_sw] {
                         // Use U = det*C + γ*U* to compute U from C and known U*
-                        let offset_z = z * sub_chunk_size;
-                        let offset_zsw = z_sw * sub_chunk_size;
-                        let c_xy = &chunks[node_xy][offset_z..offset_z + sub_chunk_size];
                         let u_sw = &u_buf[node_sw][offset_zsw..offset_zsw + sub_chunk_size];
-                        let u_xy = compute_u_from_c_and_ustar(c_xy, u_sw);
-                        u_buf[node_xy][offset_z..offset_z + sub_chunk_size].copy_from_slice(&u_xy);
-                        u_computed[node_xy][z] = true;
+                        compute_u_from_c_and_ustar_into(
+                            c_xy,
+                            u_sw,
+                            params.mul,
+                            &mut layer_u[node_xy].0,
+                        );
                     } else {
                         // Companion's U not available yet - mark for MDS
-                        needs_mds.insert(node_xy);
+                        layer_u[node_xy].1 = false;
                     }
                 }
             }
@@ -318,159 +1376,41 @@ fn decode_layered_with_tracking(
     }
 
     // Decode uncoupled layer using MDS
-    decode_uncoupled_layer(params, &needs_mds, z, sub_chunk_size, u_buf, rs)?;
-
-    // Mark reconstructed nodes as computed
-    for &node in &needs_mds {
-        u_computed[node][z] = true;
-    }
-
-    Ok(())
-}
-
-/// Decode uncoupled layer using RS MDS code
-pub fn decode_uncoupled_layer(
-    params: &DecodeParams,
-    erased_chunks: &BTreeSet<usize>,
-    z: usize,
-    sub_chunk_size: usize,
-    u_buf: &mut [Vec<u8>],
-    rs: &reed_solomon_erasure::ReedSolomon<galois_8::Field>,
-) -> Result<(), ClayError> {
-    let total_nodes = params.q * params.t;
-    let offset = z * sub_chunk_size;
-    let parity_start = params.original_count; // k + nu
-
-    // Check if we have too many erasures for this layer
-    if erased_chunks.len() > params.m {
+    #[cfg(feature = "profile")]
+    let uncoupled = std::time::Instant::now();
+    let missing = layer_u.iter().filter(|(_, present)| !present).count();
+    if missing > params.m {
         return Err(ClayError::TooManyErasures {
             max: params.m,
-            actual: erased_chunks.len(),
+            actual: missing,
         });
     }
-
-    // If no erasures, nothing to do
-    if erased_chunks.is_empty() {
+    #[cfg(feature = "tracing")]
+    tracing::trace!(layer = z, mds_recoveries = missing, "uncoupled layer");
+    if missing == 0 {
+        #[cfg(feature = "profile")]
+        timings.record_layer(start, uncoupled, false);
         return Ok(());
     }
-
-    // Check if we have erased originals or parities
-    let has_erased_originals = erased_chunks.iter().any(|&i| i < parity_start);
-    let has_erased_parities = erased_chunks.iter().any(|&i| i >= parity_start);
-
-    if has_erased_originals {
-        // Build shards as Option<Vec<u8>> for reconstruction
-        let mut shards: Vec<Option<Vec<u8>>> = Vec::with_capacity(total_nodes);
-
-        for i in 0..total_nodes {
-            if erased_chunks.contains(&i) {
-                shards.push(None);
-            } else {
-                shards.push(Some(u_buf[i][offset..offset + sub_chunk_size].to_vec()));
-            }
-        }
-
-        // Reconstruct missing shards
-        rs.reconstruct(&mut shards).map_err(|e| {
-            ClayError::ReconstructionFailed(format!("Layer {} RS reconstruct failed: {:?}", z, e))
-        })?;
-
-        // Copy restored shards back
-        for i in 0..total_nodes {
-            if erased_chunks.contains(&i) {
-                if let Some(ref data) = shards[i] {
-                    u_buf[i][offset..offset + sub_chunk_size].copy_from_slice(data);
-                }
-            }
-        }
-    } else if has_erased_parities {
-        // Only parity shards erased - just re-encode
-        let mut shards: Vec<Vec<u8>> = Vec::with_capacity(total_nodes);
-
-        for i in 0..total_nodes {
-            shards.push(u_buf[i][offset..offset + sub_chunk_size].to_vec());
-        }
-
-        // Encode to regenerate parity shards
-        rs.encode(&mut shards).map_err(|e| {
-            ClayError::ReconstructionFailed(format!("Layer {} RS encode failed: {:?}", z, e))
-        })?;
-
-        // Copy regenerated parity shards back
-        for i in parity_start..total_nodes {
-            if erased_chunks.contains(&i) {
-                u_buf[i][offset..offset + sub_chunk_size].copy_from_slice(&shards[i]);
-            }
-        }
-    }
-
+    params.rs.reconstruct(layer_u)?;
+    #[cfg(feature = "profile")]
+    timings.record_layer(start, uncoupled, true);
     Ok(())
 }
 
-/// Get companion layer index with proper modular arithmetic
-///
-/// z_sw = (z + (x - z_y) * q^(t-1-y)) mod α
-pub fn get_companion_layer(params: &DecodeParams, z: usize, x: usize, y: usize, z_y: usize) -> usize {
-    debug_assert!(y < params.t, "y={} must be < t={}", y, params.t);
-    debug_assert!(x < params.q, "x={} must be < q={}", x, params.q);
-    debug_assert!(z_y < params.q, "z_y={} must be < q={}", z_y, params.q);
-    debug_assert!(
-        z < params.sub_chunk_no,
-        "z={} must be < α={}",
-        z,
-        params.sub_chunk_no
-    );
-
-    let alpha = params.sub_chunk_no as isize;
-    let multiplier = params.q.pow((params.t - 1 - y) as u32) as isize;
-    let diff = x as isize - z_y as isize;
-    let z_sw = ((z as isize) + diff * multiplier).rem_euclid(alpha) as usize;
-    debug_assert!(
-        z_sw < params.sub_chunk_no,
-        "z_sw out of bounds: {} >= {}",
-        z_sw,
-        params.sub_chunk_no
-    );
-    z_sw
-}
-
-/// Get uncoupled values from coupled values using PRT
-fn get_uncoupled_from_coupled(
-    params: &DecodeParams,
-    chunks: &[Vec<u8>],
-    u_buf: &mut [Vec<u8>],
-    x: usize,
-    y: usize,
-    z: usize,
-    z_y: usize,
-    z_sw: usize,
-    sub_chunk_size: usize,
-) {
-    let node_xy = y * params.q + x;
-    let node_sw = y * params.q + z_y;
-
-    let offset_z = z * sub_chunk_size;
-    let offset_zsw = z_sw * sub_chunk_size;
-
-    let c_xy = &chunks[node_xy][offset_z..offset_z + sub_chunk_size];
-    let c_sw = &chunks[node_sw][offset_zsw..offset_zsw + sub_chunk_size];
-
-    // Determine which is C and which is C* based on x vs z_y
-    let (u_xy, u_sw) = if x < z_y {
-        prt_compute_both(c_xy, c_sw)
-    } else {
-        let (u_sw, u_xy) = prt_compute_both(c_sw, c_xy);
-        (u_xy, u_sw)
-    };
-
-    u_buf[node_xy][offset_z..offset_z + sub_chunk_size].copy_from_slice(&u_xy);
-    u_buf[node_sw][offset_zsw..offset_zsw + sub_chunk_size].copy_from_slice(&u_sw);
+/// Get the companion layer of vertex (x, y, z) from the code's table
+///
+/// z_sw = z + (x - z_y) * q^(t-1-y), i.e. z with its digit y set to x
+#[inline]
+pub fn get_companion_layer(params: &DecodeParams, z: usize, x: usize, y: usize) -> usize {
+    params.companions[(y * params.q + x) * params.sub_chunk_no + z]
 }
 
 /// Recover type 1 erasure (companion not erased)
-fn recover_type1_erasure(
+#[allow(clippy::too_many_arguments)]
+fn recover_type1_erasure<B: NodeBuffers + ?Sized>(
     params: &DecodeParams,
-    chunks: &mut [Vec<u8>],
+    chunks: &mut B,
     u_buf: &[Vec<u8>],
     x: usize,
     y: usize,
@@ -478,6 +1418,7 @@ fn recover_type1_erasure(
     z_y: usize,
     z_sw: usize,
     sub_chunk_size: usize,
+    scratch: &mut [u8],
 ) {
     let node_xy = y * params.q + x;
     let node_sw = y * params.q + z_y;
@@ -485,19 +1426,20 @@ fn recover_type1_erasure(
     let offset_z = z * sub_chunk_size;
     let offset_zsw = z_sw * sub_chunk_size;
 
-    let c_sw = &chunks[node_sw][offset_zsw..offset_zsw + sub_chunk_size];
+    let c_sw = &chunks.node(node_sw)[offset_zsw..offset_zsw + sub_chunk_size];
     let u_xy = &u_buf[node_xy][offset_z..offset_z + sub_chunk_size];
 
     // Compute C from U and C*
-    let c_xy = compute_c_from_u_and_cstar(u_xy, c_sw);
+    compute_c_from_u_and_cstar_into(u_xy, c_sw, params.mul, scratch);
 
-    chunks[node_xy][offset_z..offset_z + sub_chunk_size].copy_from_slice(&c_xy);
+    chunks.node_mut(node_xy)[offset_z..offset_z + sub_chunk_size].copy_from_slice(scratch);
 }
 
 /// Get coupled values from uncoupled values using PFT
-fn get_coupled_from_uncoupled(
+#[allow(clippy::too_many_arguments)]
+fn get_coupled_from_uncoupled<B: NodeBuffers + ?Sized>(
     params: &DecodeParams,
-    chunks: &mut [Vec<u8>],
+    chunks: &mut B,
     u_buf: &[Vec<u8>],
     x: usize,
     y: usize,
@@ -505,6 +1447,8 @@ fn get_coupled_from_uncoupled(
     z_y: usize,
     z_sw: usize,
     sub_chunk_size: usize,
+    scratch_xy: &mut [u8],
+    scratch_sw: &mut [u8],
 ) {
     let node_xy = y * params.q + x;
     let node_sw = y * params.q + z_y;
@@ -516,15 +1460,34 @@ fn get_coupled_from_uncoupled(
     let u_sw = &u_buf[node_sw][offset_zsw..offset_zsw + sub_chunk_size];
 
     // PFT: compute C from U pair
-    let (c_xy, c_sw) = if x < z_y {
-        pft_compute_both(u_xy, u_sw)
+    if x < z_y {
+        pft_compute_both_into(u_xy, u_sw, params.mul, scratch_xy, scratch_sw);
     } else {
-        let (c_sw, c_xy) = pft_compute_both(u_sw, u_xy);
-        (c_xy, c_sw)
-    };
+        pft_compute_both_into(u_sw, u_xy, params.mul, scratch_sw, scratch_xy);
+    }
+
+    chunks.node_mut(node_xy)[offset_z..offset_z + sub_chunk_size].copy_from_slice(scratch_xy);
+    chunks.node_mut(node_sw)[offset_zsw..offset_zsw + sub_chunk_size].copy_from_slice(scratch_sw);
+}
+
+/// Intersection score of every layer for a set of erased internal nodes
+///
+/// A layer's score is the number of erased nodes that are red (unpaired)
+/// in it. Layers are decoded in order of increasing score.
+pub fn layer_scores(params: &DecodeParams, erasures: &BTreeSet<usize>) -> Vec<usize> {
+    let mut order = vec![0; params.sub_chunk_no];
+    set_planes_sequential_decoding_order(params, &mut order, erasures);
+    order
+}
 
-    chunks[node_xy][offset_z..offset_z + sub_chunk_size].copy_from_slice(&c_xy);
-    chunks[node_sw][offset_zsw..offset_zsw + sub_chunk_size].copy_from_slice(&c_sw);
+/// Layers in the order [`decode_layered`] processes them
+///
+/// Sorted by intersection score, ties broken by layer index.
+pub fn decoding_order(params: &DecodeParams, erasures: &BTreeSet<usize>) -> Vec<usize> {
+    let scores = layer_scores(params, erasures);
+    let mut layers: Vec<usize> = (0..params.sub_chunk_no).collect();
+    layers.sort_by_key(|&z| scores[z]);
+    layers
 }
 
 /// Set decoding order based on intersection scores
@@ -533,12 +1496,12 @@ fn set_planes_sequential_decoding_order(
     order: &mut [usize],
     erasures: &BTreeSet<usize>,
 ) {
-    for z in 0..params.sub_chunk_no {
-        let z_vec = get_plane_vector(z, params.t, params.q);
-        order[z] = 0;
+    for (z, score) in order.iter_mut().enumerate().take(params.sub_chunk_no) {
+        let z_vec = params.plane_vector(z);
+        *score = 0;
         for &i in erasures {
             if i % params.q == z_vec[i / params.q] {
-                order[z] += 1;
+                *score += 1;
             }
         }
     }
@@ -546,13 +1509,14 @@ fn set_planes_sequential_decoding_order(
 
 /// Get maximum intersection score
 fn get_max_iscore(params: &DecodeParams, erased_chunks: &BTreeSet<usize>) -> usize {
-    let mut weight_vec = vec![false; params.t];
+    let mut last_y = None;
     let mut iscore = 0;
 
+    // Erasures are sorted, so the nodes of one y-section are adjacent
     for &i in erased_chunks {
         let y = i / params.q;
-        if !weight_vec[y] {
-            weight_vec[y] = true;
+        if last_y != Some(y) {
+            last_y = Some(y);
             iscore += 1;
         }
     }
@@ -560,48 +1524,39 @@ fn get_max_iscore(params: &DecodeParams, erased_chunks: &BTreeSet<usize>) -> usi
     iscore
 }
 
-/// Compute C* from C and U (for repair)
+/// Compute C* from C and U (for repair) into a caller-provided slice
 ///
 /// companion_value = (U + C) / γ
-pub fn compute_cstar_from_c_and_u(c_helper: &[u8], u_helper: &[u8]) -> Vec<u8> {
-    let len = c_helper.len();
-    let mut companion_c = vec![0u8; len];
-    let gamma_inv = crate::transforms::gf_inv(GAMMA);
-
-    for i in 0..len {
-        companion_c[i] = gf_mul(gf_add(u_helper[i], c_helper[i]), gamma_inv);
+pub fn compute_cstar_from_c_and_u_into(
+    c_helper: &[u8],
+    u_helper: &[u8],
+    mul: &GfMulTable,
+    out_companion_c: &mut [u8],
+) {
+    for i in 0..c_helper.len() {
+        out_companion_c[i] = mul.div_gamma(gf_add(u_helper[i], c_helper[i]));
     }
-
-    companion_c
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ClayCode;
 
-    fn test_params() -> DecodeParams {
-        DecodeParams {
-            k: 4,
-            m: 2,
-            n: 6,
-            q: 2,
-            t: 3,
-            nu: 0,
-            sub_chunk_no: 8,
-            original_count: 4,
-            recovery_count: 2,
-        }
+    fn test_code() -> ClayCode {
+        // (4, 2, 5) configuration
+        ClayCode::new(4, 2, 5).unwrap()
     }
 
     #[test]
     fn test_companion_layer_valid_range() {
-        let params = test_params();
+        let code = test_code();
+        let params = code.encode_params();
 
         for z in 0..params.sub_chunk_no {
-            let z_vec = get_plane_vector(z, params.t, params.q);
             for y in 0..params.t {
                 for x in 0..params.q {
-                    let z_sw = get_companion_layer(&params, z, x, y, z_vec[y]);
+                    let z_sw = get_companion_layer(&params, z, x, y);
                     assert!(
                         z_sw < params.sub_chunk_no,
                         "z_sw {} out of range for z={}, x={}, y={}",
@@ -617,16 +1572,105 @@ mod tests {
 
     #[test]
     fn test_decode_empty_both() {
-        let params = test_params();
+        let code = test_code();
+        let params = code.encode_params();
         let available: HashMap<usize, Vec<u8>> = HashMap::new();
         let result = decode(&params, &available, &[]);
         assert!(result.is_ok());
         assert!(result.unwrap().is_empty());
     }
 
+    #[test]
+    fn test_decode_no_erasures_reads_data_chunks() {
+        let code = test_code();
+        let params = code.encode_params();
+        let data: Vec<u8> = (0..300).map(|i| (i * 13 % 256) as u8).collect();
+        let mut chunks = code.encode(&data).unwrap();
+
+        // Parity is never read on the systematic path
+        chunks[params.n - 1].fill(0xFF);
+        let available: HashMap<usize, Vec<u8>> = chunks.into_iter().enumerate().collect();
+
+        let decoded = decode(&params, &available, &[]).unwrap();
+        assert_eq!(&decoded[..data.len()], &data[..]);
+        let owned = decode_owned(&params, available, &[]).unwrap();
+        assert_eq!(owned, decoded);
+    }
+
+    #[test]
+    fn test_decode_parity_erasures_reads_data_chunks() {
+        let code = test_code();
+        let params = code.encode_params();
+        let data: Vec<u8> = (0..300).map(|i| (i * 11 % 256) as u8).collect();
+        let mut chunks = code.encode(&data).unwrap();
+
+        // Erase one parity and corrupt the other: neither is read
+        chunks[params.k].fill(0xFF);
+        let available: HashMap<usize, Vec<u8>> =
+            chunks.into_iter().enumerate().filter(|&(i, _)| i != params.n - 1).collect();
+
+        let decoded = decode(&params, &available, &[params.n - 1]).unwrap();
+        assert_eq!(&decoded[..data.len()], &data[..]);
+        let owned = decode_owned(&params, available, &[]).unwrap();
+        assert_eq!(owned, decoded);
+    }
+
+    #[test]
+    fn test_decode_lenient() {
+        let code = test_code();
+        let params = code.encode_params();
+        let data: Vec<u8> = (0..300).map(|i| (i * 13 % 256) as u8).collect();
+        let chunks = code.encode(&data).unwrap();
+        let without = |lost: &[usize]| -> Vec<Option<Vec<u8>>> {
+            (0..params.n).map(|i| (!lost.contains(&i)).then(|| chunks[i].clone())).collect()
+        };
+
+        // Anything from all n present down to exactly k
+        for lost in [vec![], vec![0], vec![5], vec![1, 2], vec![0, 4]] {
+            let decoded = decode_lenient(&params, &without(&lost)).unwrap();
+            assert_eq!(&decoded[..data.len()], &data[..], "lost {:?}", lost);
+        }
+
+        assert!(matches!(
+            decode_lenient(&params, &without(&[0, 1, 2])),
+            Err(ClayError::TooManyErasures { max: 2, actual: 3 })
+        ));
+        assert!(matches!(
+            decode_lenient(&params, &without(&[])[1..]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_with_report() {
+        let code = test_code();
+        let params = code.encode_params();
+        let data: Vec<u8> = (0..300).map(|i| (i * 11 % 256) as u8).collect();
+        let chunks = code.encode(&data).unwrap();
+        let without = |lost: &[usize]| -> HashMap<usize, Vec<u8>> {
+            (0..params.n).filter(|i| !lost.contains(i)).map(|i| (i, chunks[i].clone())).collect()
+        };
+
+        // All present or only parity lost: just the data chunks
+        for lost in [vec![], vec![5], vec![4, 5]] {
+            let (decoded, read) = decode_with_report(&params, &without(&lost), &[]).unwrap();
+            assert_eq!(&decoded[..data.len()], &data[..]);
+            assert_eq!(read, [0, 1, 2, 3]);
+        }
+
+        // A data chunk lost: every survivor goes through the layered decode
+        let (decoded, read) = decode_with_report(&params, &without(&[1, 4]), &[]).unwrap();
+        assert_eq!(&decoded[..data.len()], &data[..]);
+        assert_eq!(read, [0, 2, 3, 5]);
+
+        assert_eq!(decode_with_report(&params, &HashMap::new(), &[]).unwrap(), (vec![], vec![]));
+        assert!(decode_with_report(&params, &without(&[0, 1, 2]), &[]).is_err());
+    }
+
     #[test]
     fn test_get_max_iscore() {
-        let params = test_params();
+        let code = test_code();
+        let params = code.encode_params();
 
         // No erasures
         let empty: BTreeSet<usize> = BTreeSet::new();
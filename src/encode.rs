@@ -2,12 +2,21 @@
 //!
 //! This module handles encoding data into Clay code chunks.
 
-use std::collections::BTreeSet;
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use alloc::{format, vec};
 
-use crate::decode::decode_layered;
+use crate::codec::RsBackend;
+use crate::coords;
+use crate::decode::{decode_layered_over, LayerScratch, NodeBuffers, Timings};
+use crate::error::ClayError;
+#[cfg(feature = "profile")]
+use crate::profile::PhaseTimings;
+use crate::transforms::GfMulTable;
+use crate::{checked_buffer_bytes, ClayCode, HashMap};
 
 /// Parameters needed for encoding
-pub struct EncodeParams {
+pub struct EncodeParams<'a> {
     pub k: usize,
     pub m: usize,
     pub n: usize,
@@ -15,8 +24,307 @@ pub struct EncodeParams {
     pub t: usize,
     pub nu: usize,
     pub sub_chunk_no: usize,
-    pub original_count: usize,
-    pub recovery_count: usize,
+    /// Multiply tables for the coupling coefficient γ of the pairwise transforms
+    pub mul: &'a GfMulTable,
+    /// RS codec shared by every layer, built once per ClayCode
+    pub rs: &'a dyn RsBackend,
+    /// Plane vectors of all layers, flattened (see `coords::plane_vector_table`)
+    pub planes: &'a [usize],
+    /// Companion layers of all vertices, flattened (see `coords::companion_layer_table`)
+    pub companions: &'a [usize],
+}
+
+impl EncodeParams<'_> {
+    /// Plane vector of layer z: element y is the red x of y-section y
+    pub fn plane_vector(&self, z: usize) -> &[usize] {
+        &self.planes[z * self.t..(z + 1) * self.t]
+    }
+
+    /// Internal node index of chunk `external` (see `coords::to_internal`)
+    pub fn to_internal(&self, external: usize) -> usize {
+        coords::to_internal(external, self.k, self.nu)
+    }
+
+    /// Chunk index of internal node `internal`, None if it is shortened
+    pub fn to_external(&self, internal: usize) -> Option<usize> {
+        coords::to_external(internal, self.k, self.nu)
+    }
+}
+
+/// Reusable buffers for encoding stripes of one chunk size
+///
+/// Holds the stripe and every working buffer of the parity computation,
+/// allocated once by [`EncodeWorkspace::new`]. Encoding into it with
+/// [`ClayCode::encode_with`] performs no heap allocation after the first
+/// call (without the `rayon` feature, and on GF(2^8) codes).
+#[derive(Debug)]
+pub struct EncodeWorkspace {
+    k: usize,
+    nu: usize,
+    sub_chunk_no: usize,
+    chunk_size: usize,
+    /// Stripe in node order (k data, m parity, then nu shortened); moved to
+    /// internal order (k data, nu shortened, m parity) while encoding
+    chunks: Vec<Vec<u8>>,
+    /// Internal indices of the parity nodes, recovered as erasures
+    parity_nodes: BTreeSet<usize>,
+    scratch: LayerScratch,
+}
+
+impl EncodeWorkspace {
+    /// Allocate a workspace for `code` and chunks of `chunk_size` bytes
+    ///
+    /// # Parameters
+    /// - `code`: Code to encode with
+    /// - `chunk_size`: Bytes per chunk; a multiple of α of at least 2 * α,
+    ///   as for `encode_with_chunk_size`
+    ///
+    /// # Returns
+    /// Result with EncodeWorkspace or error if the chunk size is misaligned
+    pub fn new(code: &ClayCode, chunk_size: usize) -> Result<Self, ClayError> {
+        let params = code.encode_params();
+        validate_chunk_size(&params, chunk_size)?;
+        let total_nodes = code.q * code.t;
+        checked_buffer_bytes(total_nodes, chunk_size)?;
+        Ok(EncodeWorkspace {
+            k: code.k,
+            nu: code.nu,
+            sub_chunk_no: code.sub_chunk_no,
+            chunk_size,
+            chunks: vec![vec![0u8; chunk_size]; total_nodes],
+            parity_nodes: (code.k + code.nu..total_nodes).collect(),
+            scratch: LayerScratch::new(&params, chunk_size),
+        })
+    }
+
+    /// Size of each chunk produced
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+
+    /// Largest input accepted per stripe (k * chunk_size bytes)
+    pub fn stripe_size(&self) -> usize {
+        self.k * self.chunk_size
+    }
+}
+
+/// Encode data into the n chunks held by a workspace
+///
+/// `data` is zero-padded to the workspace's stripe size.
+///
+/// # Parameters
+/// - `params`: Encoding parameters from ClayCode
+/// - `ws`: Workspace created for the same code
+/// - `data`: At most k * chunk_size bytes
+///
+/// # Returns
+/// The n chunks, borrowed from the workspace, or error if the workspace
+/// belongs to a different code or the data does not fit
+pub fn encode_with<'w>(
+    params: &EncodeParams,
+    ws: &'w mut EncodeWorkspace,
+    data: &[u8],
+) -> Result<&'w [Vec<u8>], ClayError> {
+    check_workspace(params, ws, data.len())?;
+    load_data(&mut ws.chunks[..params.k], data);
+
+    // Shortened nodes stay zero; rotating only swaps buffer pointers
+    let chunk_size = ws.chunk_size;
+    ws.chunks[params.k..].rotate_right(params.nu);
+    let result = compute_parity_with(
+        params,
+        &ws.parity_nodes,
+        &mut ws.chunks[..],
+        chunk_size / params.sub_chunk_no,
+        &mut ws.scratch,
+    );
+    ws.chunks[params.k..].rotate_left(params.nu);
+    result?;
+
+    Ok(&ws.chunks[..params.n])
+}
+
+/// Encode data into n caller-provided buffers with a workspace's working
+/// memory
+///
+/// Same chunks as [`encode_with`]; they are written straight into `out`
+/// and the workspace's own chunk buffers are left alone. Nothing is
+/// allocated once the workspace has been used.
+///
+/// # Parameters
+/// - `params`: Encoding parameters from ClayCode
+/// - `ws`: Workspace created for the same code
+/// - `data`: At most k * chunk_size bytes
+/// - `out`: Exactly n buffers of the workspace's chunk size
+///
+/// # Returns
+/// Ok once every buffer holds its chunk, or InvalidParameters if the
+/// workspace belongs to a different code, the data does not fit or the
+/// buffer count or any buffer length is wrong (nothing is written then)
+pub fn encode_into_with(
+    params: &EncodeParams,
+    ws: &mut EncodeWorkspace,
+    data: &[u8],
+    out: &mut [&mut [u8]],
+) -> Result<(), ClayError> {
+    check_workspace(params, ws, data.len())?;
+    check_output_buffers(params, out, ws.chunk_size)?;
+
+    // The workspace keeps its shortened nodes zeroed past the n chunks
+    let zeros = ws.chunks.get_mut(params.n).map_or(&mut [][..], |chunk| &mut chunk[..]);
+    encode_stripe_into(params, data, out, zeros, &ws.parity_nodes, &mut ws.scratch)
+}
+
+/// Check that `ws` was created for this code and holds `data_len` bytes
+fn check_workspace(
+    params: &EncodeParams,
+    ws: &EncodeWorkspace,
+    data_len: usize,
+) -> Result<(), ClayError> {
+    if (ws.k, ws.nu, ws.sub_chunk_no) != (params.k, params.nu, params.sub_chunk_no)
+        || ws.chunks.len() != params.q * params.t
+    {
+        return Err(ClayError::InvalidParameters(
+            "Workspace was created for a different code".into(),
+        ));
+    }
+    if data_len > ws.stripe_size() {
+        return Err(ClayError::InvalidParameters(format!(
+            "{} data bytes exceed the workspace stripe size of {}",
+            data_len,
+            ws.stripe_size()
+        )));
+    }
+    Ok(())
+}
+
+/// Copy `data` into `chunks` in order, zero-padding the rest
+fn load_data<C: AsMut<[u8]>>(chunks: &mut [C], data: &[u8]) {
+    let mut rest = data;
+    for chunk in chunks {
+        let chunk = chunk.as_mut();
+        let (head, tail) = rest.split_at(rest.len().min(chunk.len()));
+        chunk[..head.len()].copy_from_slice(head);
+        chunk[head.len()..].fill(0);
+        rest = tail;
+    }
+}
+
+/// Size in bytes of the little-endian length header written by `encode_with_length`
+pub const LENGTH_HEADER_SIZE: usize = 8;
+
+/// Encode data into n chunks, prefixed with its exact length
+///
+/// An 8-byte little-endian length header is prepended to `data` before
+/// padding, so it lands at the start of the first data chunk and is
+/// included in chunk sizing. `decode::decode_exact` reverses this.
+pub fn encode_with_length(params: &EncodeParams, data: &[u8]) -> Result<Vec<Vec<u8>>, ClayError> {
+    let mut framed = Vec::with_capacity(LENGTH_HEADER_SIZE + data.len());
+    framed.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    framed.extend_from_slice(data);
+    encode(params, &framed)
+}
+
+/// How `encode_with_padding` fills the stripe past the end of the data
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PaddingScheme {
+    /// Zero bytes, as `encode` pads
+    #[default]
+    Zero,
+    /// Copies of one byte, e.g. to tell padding from data when debugging
+    Byte(u8),
+    /// PKCS#7: p bytes of value p, 1 <= p <= 255, so `decode::decode_unpad`
+    /// can recover the exact length without a header
+    Pkcs7,
+}
+
+/// Largest padding PKCS#7 can describe in its one-byte count
+const PKCS7_MAX_PADDING: usize = 255;
+
+/// What `decode::decode_object` needs to know about an encoded object
+///
+/// Store it alongside the stripes; with the `serde` feature it implements
+/// `Serialize` and `Deserialize`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ObjectManifest {
+    /// Length of the original object in bytes
+    pub len: usize,
+    /// Bytes per chunk in every stripe
+    pub chunk_size: usize,
+    /// Number of stripes, ceil(len / (k * chunk_size))
+    pub stripe_count: usize,
+}
+
+/// An object split into stripes by `encode_object`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncodedObject {
+    /// Length and layout of the object
+    pub manifest: ObjectManifest,
+    /// The stripes in order, each n chunks of `manifest.chunk_size` bytes
+    pub stripes: Vec<Vec<Vec<u8>>>,
+}
+
+/// Split an object into stripes of k chunks of `chunk_size` bytes and
+/// encode each one
+///
+/// There are ceil(data.len() / (k * chunk_size)) stripes, coded
+/// independently; the last is zero-padded. Nothing is added to the data:
+/// the length goes in the returned manifest instead.
+///
+/// # Returns
+/// The manifest and the stripes, or error if the chunk size is misaligned
+pub fn encode_object(
+    params: &EncodeParams,
+    data: &[u8],
+    chunk_size: usize,
+) -> Result<EncodedObject, ClayError> {
+    validate_chunk_size(params, chunk_size)?;
+    let stripe_size = chunk_size.checked_mul(params.k).ok_or_else(|| {
+        ClayError::Overflow(format!("k * chunk_size ({} * {})", params.k, chunk_size))
+    })?;
+
+    let stripes = data
+        .chunks(stripe_size)
+        .map(|stripe| encode_with_chunk_size(params, stripe, chunk_size))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(EncodedObject {
+        manifest: ObjectManifest { len: data.len(), chunk_size, stripe_count: stripes.len() },
+        stripes,
+    })
+}
+
+/// Encode data into n chunks, padding with the given scheme
+///
+/// Chunks are sized as by `encode`, except that PKCS#7 always adds at
+/// least one byte of padding and so sizes for `data.len() + 1`. PKCS#7 is
+/// an error when the stripe would need more than 255 bytes of padding,
+/// which happens for short data or a large k * α; `encode_with_length`
+/// works for any size.
+pub fn encode_with_padding(
+    params: &EncodeParams,
+    data: &[u8],
+    pad: PaddingScheme,
+) -> Result<Vec<Vec<u8>>, ClayError> {
+    let (chunk_size, fill) = match pad {
+        PaddingScheme::Zero => return encode(params, data),
+        PaddingScheme::Byte(byte) => (chunk_size_for(params, data.len()), byte),
+        PaddingScheme::Pkcs7 => {
+            let chunk_size = chunk_size_for(params, data.len() + 1);
+            let padding = chunk_size * params.k - data.len();
+            if padding > PKCS7_MAX_PADDING {
+                return Err(ClayError::InvalidParameters(format!(
+                    "PKCS#7 cannot describe {} bytes of padding (at most {})",
+                    padding, PKCS7_MAX_PADDING
+                )));
+            }
+            (chunk_size, padding as u8)
+        }
+    };
+    let mut padded = Vec::with_capacity(chunk_size * params.k);
+    padded.extend_from_slice(data);
+    padded.resize(chunk_size * params.k, fill);
+    encode_padded(params, &padded, chunk_size)
 }
 
 /// Encode data into n chunks
@@ -26,19 +334,161 @@ pub struct EncodeParams {
 /// - `data`: Raw data bytes to encode
 ///
 /// # Returns
-/// Vector of n chunks, each containing α sub-chunks
-pub fn encode(params: &EncodeParams, data: &[u8]) -> Vec<Vec<u8>> {
-    // Calculate chunk size: must be divisible by (k * sub_chunk_no)
-    // Also ensure sub_chunk_size >= 2 bytes (reed-solomon-erasure requirement)
-    let min_sub_chunk_size = 2;
-    let min_size = params.k * params.sub_chunk_no * min_sub_chunk_size;
-    let padded_len = if data.is_empty() {
-        min_size
-    } else {
-        let aligned = ((data.len() + min_size - 1) / min_size) * min_size;
-        aligned.max(min_size)
-    };
-    let chunk_size = padded_len / params.k;
+/// Vector of n chunks, each containing α sub-chunks, or error if the
+/// layered parity computation fails
+pub fn encode(params: &EncodeParams, data: &[u8]) -> Result<Vec<Vec<u8>>, ClayError> {
+    encode_padded(params, data, chunk_size_for(params, data.len()))
+}
+
+/// Encode data into n chunks of exactly `chunk_size` bytes
+///
+/// # Parameters
+/// - `params`: Encoding parameters from ClayCode
+/// - `data`: Raw data bytes to encode, at most k * chunk_size
+/// - `chunk_size`: Bytes per chunk; a multiple of α of at least 2 * α
+///
+/// # Returns
+/// Vector of n chunks of `chunk_size` bytes, or error if the chunk size is
+/// misaligned or the data does not fit
+pub fn encode_with_chunk_size(
+    params: &EncodeParams,
+    data: &[u8],
+    chunk_size: usize,
+) -> Result<Vec<Vec<u8>>, ClayError> {
+    validate_chunk_size(params, chunk_size)?;
+    let capacity = chunk_size.checked_mul(params.k).ok_or_else(|| {
+        ClayError::Overflow(format!("k * chunk_size ({} * {})", params.k, chunk_size))
+    })?;
+    if data.len() > capacity {
+        return Err(ClayError::InvalidParameters(format!(
+            "Data of {} bytes does not fit in {} chunks of {} bytes",
+            data.len(),
+            params.k,
+            chunk_size
+        )));
+    }
+    encode_padded(params, data, chunk_size)
+}
+
+/// Encode data into n caller-provided chunk buffers
+///
+/// # Parameters
+/// - `params`: Encoding parameters from ClayCode
+/// - `data`: Raw data bytes to encode
+/// - `out`: Exactly n buffers of chunk_size_for(data.len()) bytes each
+///
+/// # Returns
+/// Ok once every buffer holds its chunk, or InvalidParameters if the
+/// buffer count or any buffer length is wrong (nothing is written then)
+pub fn encode_into(
+    params: &EncodeParams,
+    data: &[u8],
+    out: &mut [&mut [u8]],
+) -> Result<(), ClayError> {
+    let chunk_size = chunk_size_for(params, data.len());
+    check_output_buffers(params, out, chunk_size)?;
+
+    let parity_nodes: BTreeSet<usize> = (params.k + params.nu..params.q * params.t).collect();
+    let mut zeros = vec![0u8; if params.nu > 0 { chunk_size } else { 0 }];
+    let mut scratch = LayerScratch::new(params, chunk_size);
+    encode_stripe_into(params, data, out, &mut zeros, &parity_nodes, &mut scratch)
+}
+
+/// Check that `out` holds n buffers of `chunk_size` bytes
+fn check_output_buffers(
+    params: &EncodeParams,
+    out: &[&mut [u8]],
+    chunk_size: usize,
+) -> Result<(), ClayError> {
+    if out.len() != params.n {
+        return Err(ClayError::InvalidParameters(format!(
+            "Expected {} output buffers, got {}",
+            params.n,
+            out.len()
+        )));
+    }
+    if let Some((idx, buf)) = out.iter().enumerate().find(|(_, buf)| buf.len() != chunk_size) {
+        return Err(ClayError::InvalidParameters(format!(
+            "Output buffer {} has {} bytes, expected {}",
+            idx,
+            buf.len(),
+            chunk_size
+        )));
+    }
+    Ok(())
+}
+
+/// The n output buffers of `encode_into` in internal node order
+///
+/// Every shortened node maps to the one `zeros` chunk; the layered encode
+/// only writes parity nodes, so it stays zero.
+struct OutputNodes<'a, 'b> {
+    k: usize,
+    nu: usize,
+    out: &'a mut [&'b mut [u8]],
+    zeros: &'a mut [u8],
+}
+
+impl NodeBuffers for OutputNodes<'_, '_> {
+    fn node(&self, node: usize) -> &[u8] {
+        match coords::to_external(node, self.k, self.nu) {
+            Some(idx) => &*self.out[idx],
+            None => &*self.zeros,
+        }
+    }
+
+    fn node_mut(&mut self, node: usize) -> &mut [u8] {
+        match coords::to_external(node, self.k, self.nu) {
+            Some(idx) => &mut *self.out[idx],
+            None => &mut *self.zeros,
+        }
+    }
+}
+
+/// Load `data` into the first k buffers of `out` and compute the parity
+/// into the rest, in place
+fn encode_stripe_into(
+    params: &EncodeParams,
+    data: &[u8],
+    out: &mut [&mut [u8]],
+    zeros: &mut [u8],
+    parity_nodes: &BTreeSet<usize>,
+    scratch: &mut LayerScratch,
+) -> Result<(), ClayError> {
+    let sub_chunk_size = out[0].len() / params.sub_chunk_no;
+    load_data(&mut out[..params.k], data);
+    let mut nodes = OutputNodes { k: params.k, nu: params.nu, out, zeros };
+    compute_parity_with(params, parity_nodes, &mut nodes, sub_chunk_size, scratch)
+}
+
+/// Encode data into n chunks and report the time each phase took
+///
+/// Same as [`encode`]; the parity is computed by the layered decode, so
+/// the timings count its layers and transforms.
+#[cfg(feature = "profile")]
+pub fn encode_profiled(
+    params: &EncodeParams,
+    data: &[u8],
+) -> Result<(Vec<Vec<u8>>, PhaseTimings), ClayError> {
+    encode_padded_timed(params, data, chunk_size_for(params, data.len()))
+}
+
+/// Zero-pad `data` to k chunks of `chunk_size` bytes and encode it
+fn encode_padded(
+    params: &EncodeParams,
+    data: &[u8],
+    chunk_size: usize,
+) -> Result<Vec<Vec<u8>>, ClayError> {
+    encode_padded_timed(params, data, chunk_size).map(|(chunks, _)| chunks)
+}
+
+/// [`encode_padded`], also returning what the layered decode recorded
+fn encode_padded_timed(
+    params: &EncodeParams,
+    data: &[u8],
+    chunk_size: usize,
+) -> Result<(Vec<Vec<u8>>, Timings), ClayError> {
+    let padded_len = chunk_size * params.k;
     let sub_chunk_size = chunk_size / params.sub_chunk_no;
 
     // Create padded data
@@ -50,66 +500,331 @@ pub fn encode(params: &EncodeParams, data: &[u8]) -> Vec<Vec<u8>> {
     let mut chunks: Vec<Vec<u8>> = vec![vec![0u8; chunk_size]; total_nodes];
 
     // Load data into first k nodes
-    for i in 0..params.k {
-        chunks[i].copy_from_slice(&padded_data[i * chunk_size..(i + 1) * chunk_size]);
+    for (chunk, data_chunk) in chunks.iter_mut().zip(padded_data.chunks(chunk_size)).take(params.k) {
+        chunk.copy_from_slice(data_chunk);
     }
 
-    // Shortened nodes (k to k+nu-1) are already zeros - they are KNOWN zeros,
-    // not erasures. We mark only parity nodes as needing computation.
-    let parity_start = params.k + params.nu;
-    let mut nodes_to_compute: BTreeSet<usize> = BTreeSet::new();
-    for i in parity_start..total_nodes {
-        nodes_to_compute.insert(i);
+    let parity_nodes: BTreeSet<usize> = (params.k + params.nu..total_nodes).collect();
+    let mut scratch = LayerScratch::new(params, chunk_size);
+    compute_parity_with(params, &parity_nodes, &mut chunks[..], sub_chunk_size, &mut scratch)?;
+
+    // Return only the k data + m parity chunks (exclude shortened nodes)
+    let mut result = Vec::with_capacity(params.n);
+    result.extend(chunks.drain(..params.k));
+    result.extend(chunks.drain(params.nu..));
+
+    Ok((result, scratch.timings()))
+}
+
+/// Whether a chunk holds data or parity
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ShardKind {
+    /// One of the k systematic chunks, a slice of the padded data
+    Data,
+    /// One of the m parity chunks
+    Parity,
+}
+
+/// A chunk tagged with its node index and kind
+///
+/// Carries its own identity, so placement logic and transports that
+/// filter or reorder chunks cannot mix them up.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Shard {
+    /// Node index (0 to n-1)
+    pub index: usize,
+    /// Data for index < k, parity otherwise
+    pub kind: ShardKind,
+    /// Chunk contents
+    pub bytes: Vec<u8>,
+}
+
+/// Encode data into n chunks, each tagged with its index and kind
+///
+/// Same chunks as [`encode`], in node order.
+pub fn encode_tagged(params: &EncodeParams, data: &[u8]) -> Result<Vec<Shard>, ClayError> {
+    let chunks = encode(params, data)?;
+    Ok(chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, bytes)| Shard {
+            index,
+            kind: if index < params.k { ShardKind::Data } else { ShardKind::Parity },
+            bytes,
+        })
+        .collect())
+}
+
+/// Chunk layout `encode` produces for a given data length
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StripeGeometry {
+    /// Bytes per chunk
+    pub chunk_size: usize,
+    /// Bytes per sub-chunk (chunk_size / α)
+    pub sub_chunk_size: usize,
+    /// Data length after padding (k * chunk_size)
+    pub padded_len: usize,
+    /// Zero bytes appended to the data
+    pub padding: usize,
+}
+
+/// Chunk layout of `data_len` bytes, without encoding anything
+pub fn geometry(params: &EncodeParams, data_len: usize) -> StripeGeometry {
+    let chunk_size = chunk_size_for(params, data_len);
+    let padded_len = chunk_size * params.k;
+    StripeGeometry {
+        chunk_size,
+        sub_chunk_size: chunk_size / params.sub_chunk_no,
+        padded_len,
+        padding: padded_len - data_len,
     }
+}
 
-    // Encode by treating parity computation as recovery
-    // This should never fail for valid parameters (parity count = m <= m)
-    decode_layered(params, &nodes_to_compute, &mut chunks, sub_chunk_size)
-        .expect("Encode failed: this indicates a bug in ClayCode");
+/// Smallest sub-chunk, in bytes, that `encode` produces and `decode` and
+/// `repair` accept
+pub(crate) const MIN_SUB_CHUNK_SIZE: usize = 2;
+
+/// Check that a chunk splits into α sub-chunks of at least
+/// MIN_SUB_CHUNK_SIZE bytes
+///
+/// Misaligned sizes report InvalidChunkSize with `expected: α`; aligned
+/// sizes that are too small report ChunkTooSmall with `min: 2 * α`.
+pub(crate) fn validate_sub_chunk_size(
+    sub_chunk_no: usize,
+    chunk_size: usize,
+) -> Result<(), ClayError> {
+    if chunk_size == 0 || chunk_size % sub_chunk_no != 0 {
+        return Err(ClayError::InvalidChunkSize {
+            expected: sub_chunk_no,
+            actual: chunk_size,
+        });
+    }
+    if chunk_size / sub_chunk_no < MIN_SUB_CHUNK_SIZE {
+        return Err(ClayError::ChunkTooSmall {
+            min: MIN_SUB_CHUNK_SIZE * sub_chunk_no,
+            actual: chunk_size,
+        });
+    }
+    Ok(())
+}
+
+/// Check a chunk size for encoding or decoding with `params`
+///
+/// Beyond [`validate_sub_chunk_size`], each sub-chunk must hold whole RS
+/// symbols; a misaligned size reports `expected: α * symbol_size`.
+pub(crate) fn validate_chunk_size(
+    params: &EncodeParams,
+    chunk_size: usize,
+) -> Result<(), ClayError> {
+    validate_sub_chunk_size(params.sub_chunk_no, chunk_size)?;
+    let alignment = params.sub_chunk_no * params.rs.symbol_size();
+    if chunk_size % alignment != 0 {
+        return Err(ClayError::InvalidChunkSize {
+            expected: alignment,
+            actual: chunk_size,
+        });
+    }
+    Ok(())
+}
+
+/// Chunk size `encode` produces for `data_len` bytes
+///
+/// Each of the k data chunks takes ceil(data_len / k) bytes, rounded up to
+/// whole sub-chunks (and whole symbols of the RS field). Sub-chunks hold at
+/// least 2 bytes, so small inputs, including empty ones, still get 2 * α.
+pub fn chunk_size_for(params: &EncodeParams, data_len: usize) -> usize {
+    let alignment = params.sub_chunk_no * params.rs.symbol_size();
+    let per_chunk = data_len / params.k + usize::from(data_len % params.k != 0);
+    let aligned = (per_chunk / alignment + usize::from(per_chunk % alignment != 0)) * alignment;
+    aligned.max(params.sub_chunk_no * MIN_SUB_CHUNK_SIZE)
+}
+
+/// Encode k pre-split data chunks into n chunks
+///
+/// # Parameters
+/// - `params`: Encoding parameters from ClayCode
+/// - `data_chunks`: Exactly k chunks of equal length, divisible by α
+///
+/// # Returns
+/// The k data chunks unchanged followed by the m parity chunks, or error if
+/// the chunks are malformed
+pub fn encode_chunks(
+    params: &EncodeParams,
+    data_chunks: &[Vec<u8>],
+) -> Result<Vec<Vec<u8>>, ClayError> {
+    let mut chunks = encode_data_chunks(params, data_chunks)?;
 
-    // Return only the k data + m parity chunks (exclude shortened nodes)
     let mut result = Vec::with_capacity(params.n);
-    for i in 0..params.k {
-        result.push(chunks[i].clone());
+    result.extend(chunks.drain(..params.k));
+    result.extend(chunks.drain(params.nu..));
+    Ok(result)
+}
+
+/// Compute only the m parity chunks for k pre-split data chunks
+///
+/// Same as [`encode_chunks`] without copying the data chunks into the result.
+pub fn encode_parity(
+    params: &EncodeParams,
+    data_chunks: &[Vec<u8>],
+) -> Result<Vec<Vec<u8>>, ClayError> {
+    let mut chunks = encode_data_chunks(params, data_chunks)?;
+    Ok(chunks.split_off(params.k + params.nu))
+}
+
+/// Rebuild selected parity chunks from the k data chunks
+///
+/// # Parameters
+/// - `params`: Encoding parameters from ClayCode
+/// - `data_chunks`: Map from data node index (0 to k-1) to its chunk; all
+///   k must be present
+/// - `lost_parity`: Parity node indices to rebuild (each k to n-1)
+///
+/// # Returns
+/// Map from each requested parity index to its chunk, or error if a data
+/// chunk is missing or an index is not a parity node
+pub fn regenerate_parity(
+    params: &EncodeParams,
+    data_chunks: &HashMap<usize, Vec<u8>>,
+    lost_parity: &[usize],
+) -> Result<HashMap<usize, Vec<u8>>, ClayError> {
+    if let Some(&idx) = lost_parity.iter().find(|&&idx| idx < params.k || idx >= params.n) {
+        return Err(ClayError::InvalidParameters(format!(
+            "Parity index {} out of range [{}, {})",
+            idx, params.k, params.n
+        )));
+    }
+    if let Some(&idx) = data_chunks.keys().find(|&&idx| idx >= params.k) {
+        return Err(ClayError::InvalidParameters(format!(
+            "Data chunk index {} out of range [0, {})",
+            idx, params.k
+        )));
+    }
+    let ordered = (0..params.k)
+        .map(|idx| {
+            data_chunks.get(&idx).cloned().ok_or_else(|| {
+                ClayError::InvalidParameters(format!("Data chunk {} is missing", idx))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if lost_parity.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let chunks = encode_data_chunks(params, &ordered)?;
+    Ok(lost_parity.iter().map(|&idx| (idx, chunks[params.to_internal(idx)].clone())).collect())
+}
+
+/// Validate k data chunks and encode them into a stripe by internal index
+fn encode_data_chunks(
+    params: &EncodeParams,
+    data_chunks: &[Vec<u8>],
+) -> Result<Vec<Vec<u8>>, ClayError> {
+    if data_chunks.len() != params.k {
+        return Err(ClayError::InvalidParameters(format!(
+            "Expected {} data chunks, got {}",
+            params.k,
+            data_chunks.len()
+        )));
     }
-    for i in (params.k + params.nu)..total_nodes {
-        result.push(chunks[i].clone());
+
+    let chunk_size = data_chunks[0].len();
+    validate_chunk_size(params, chunk_size)?;
+    for (idx, chunk) in data_chunks.iter().enumerate() {
+        if chunk.len() != chunk_size {
+            return Err(ClayError::InconsistentChunkSizes {
+                first_size: chunk_size,
+                mismatched_idx: idx,
+                mismatched_size: chunk.len(),
+            });
+        }
     }
 
-    result
+    // Data nodes, then zeroed shortened and parity nodes
+    let total_nodes = params.q * params.t;
+    let mut chunks = Vec::with_capacity(total_nodes);
+    chunks.extend(data_chunks.iter().cloned());
+    chunks.resize(total_nodes, vec![0u8; chunk_size]);
+
+    compute_parity(params, &mut chunks, chunk_size / params.sub_chunk_no)?;
+    Ok(chunks)
+}
+
+/// Fill the parity nodes of a stripe laid out by internal node index
+///
+/// The k data nodes must already hold the data and the nu shortened nodes
+/// must be zero. Parity buffers are overwritten.
+pub fn compute_parity(
+    params: &EncodeParams,
+    chunks: &mut [Vec<u8>],
+    sub_chunk_size: usize,
+) -> Result<(), ClayError> {
+    // Shortened nodes (k to k+nu-1) are already zeros - they are KNOWN zeros,
+    // not erasures. We mark only parity nodes as needing computation.
+    let total_nodes = params.q * params.t;
+    let parity_start = params.k + params.nu;
+    let nodes_to_compute: BTreeSet<usize> = (parity_start..total_nodes).collect();
+
+    let mut scratch = LayerScratch::new(params, chunks[0].len());
+    compute_parity_with(params, &nodes_to_compute, chunks, sub_chunk_size, &mut scratch)
+}
+
+/// [`compute_parity`] with the parity node set and working buffers supplied
+fn compute_parity_with<B: NodeBuffers + ?Sized>(
+    params: &EncodeParams,
+    nodes_to_compute: &BTreeSet<usize>,
+    chunks: &mut B,
+    sub_chunk_size: usize,
+    scratch: &mut LayerScratch,
+) -> Result<(), ClayError> {
+    // Encode by treating parity computation as recovery
+    // This should never fail for valid parameters (parity count = m <= m)
+    decode_layered_over(params, nodes_to_compute, chunks, sub_chunk_size, scratch)
+        .map_err(|e| match e {
+            ClayError::ReedSolomon(_) => e,
+            e => ClayError::ReconstructionFailed(format!("Encode failed: {}", e)),
+        })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ClayCode;
 
-    fn test_params() -> EncodeParams {
+    fn test_code() -> ClayCode {
         // (4, 2, 5) configuration
-        EncodeParams {
-            k: 4,
-            m: 2,
-            n: 6,
-            q: 2,
-            t: 3,
-            nu: 0,
-            sub_chunk_no: 8,
-            original_count: 4,
-            recovery_count: 2,
-        }
+        ClayCode::new(4, 2, 5).unwrap()
+    }
+
+    #[test]
+    fn test_encode_known_answer() {
+        // Pinned output: the serial and `rayon` builds must agree bit for bit
+        let code = ClayCode::new(6, 3, 8).unwrap();
+        let data: Vec<u8> = (0..6 * code.sub_chunk_no * 16).map(|i| (i * 37 % 251) as u8).collect();
+        let chunks = encode(&code.encode_params(), &data).unwrap();
+        let crcs: Vec<u32> = chunks.iter().map(|chunk| crate::crc32c(chunk)).collect();
+        assert_eq!(
+            crcs,
+            [
+                0x904e8d1b, 0x1b913b8a, 0xa5b87157, 0x8f55dc43, 0xad47c75c, 0x78ee4020, 0xcf30e46f,
+                0xdb740ea1, 0xe0a9cbc8,
+            ]
+        );
     }
 
     #[test]
     fn test_encode_produces_correct_chunk_count() {
-        let params = test_params();
+        let code = test_code();
+        let params = code.encode_params();
         let data = b"Test data for encoding";
-        let chunks = encode(&params, data);
+        let chunks = encode(&params, data).unwrap();
         assert_eq!(chunks.len(), params.n);
     }
 
     #[test]
     fn test_encode_empty_data() {
-        let params = test_params();
-        let chunks = encode(&params, &[]);
+        let code = test_code();
+        let params = code.encode_params();
+        let chunks = encode(&params, &[]).unwrap();
         assert_eq!(chunks.len(), params.n);
         // All chunks should have same size
         let chunk_size = chunks[0].len();
@@ -118,11 +833,382 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_encode_chunk_sizes() {
+        let code = test_code();
+        let params = code.encode_params();
+
+        // (data_len, chunk_size): ceil(len / 4) rounded up to α = 8, with a
+        // floor of 2 * α = 16
+        for (len, expected) in [
+            (0, 16),
+            (1, 16),
+            (1024, 256),
+            (1032, 264),
+            (64 * 1024, 16 * 1024),
+            (1024 * 1024, 256 * 1024),
+        ] {
+            assert_eq!(chunk_size_for(&params, len), expected, "len {}", len);
+            let data: Vec<u8> = (0..len).map(|i| (i % 241) as u8).collect();
+            let chunks = encode(&params, &data).unwrap();
+            assert!(chunks.iter().all(|c| c.len() == expected), "len {}", len);
+        }
+
+        // Odd sub-chunk sizes still decode and repair
+        let data: Vec<u8> = (0..1032).map(|i| (i * 7 % 256) as u8).collect();
+        let chunks = code.encode(&data).unwrap();
+        let available: crate::HashMap<usize, Vec<u8>> =
+            (2..code.n).map(|i| (i, chunks[i].clone())).collect();
+        assert_eq!(&code.decode(&available, &[0, 1]).unwrap()[..1032], &data[..]);
+        let helpers: crate::HashMap<usize, Vec<u8>> =
+            (1..code.n).map(|i| (i, chunks[i].clone())).collect();
+        assert_eq!(code.repair_from_full_chunks(0, &helpers, 264).unwrap(), chunks[0]);
+    }
+
+    #[test]
+    fn test_geometry_matches_encode() {
+        let code = test_code();
+        let params = code.encode_params();
+
+        assert_eq!(
+            geometry(&params, 0),
+            StripeGeometry { chunk_size: 16, sub_chunk_size: 2, padded_len: 64, padding: 64 }
+        );
+        assert_eq!(
+            geometry(&params, 1032),
+            StripeGeometry { chunk_size: 264, sub_chunk_size: 33, padded_len: 1056, padding: 24 }
+        );
+
+        for len in [1, 63, 64, 65, 1000, 4096, 10_001] {
+            let g = geometry(&params, len);
+            let chunks = encode(&params, &vec![1u8; len]).unwrap();
+            assert_eq!(chunks[0].len(), g.chunk_size, "len {}", len);
+            assert_eq!(g.padded_len, len + g.padding);
+            assert_eq!(g.sub_chunk_size * params.sub_chunk_no, g.chunk_size);
+        }
+    }
+
+    #[test]
+    fn test_encode_with_chunk_size() {
+        let code = test_code();
+        let params = code.encode_params();
+        let data: Vec<u8> = (0..100).collect();
+
+        let chunks = encode_with_chunk_size(&params, &data, 64).unwrap();
+        assert_eq!(chunks.len(), params.n);
+        assert!(chunks.iter().all(|c| c.len() == 64));
+        assert_eq!(&chunks[0][..64], &data[..64]);
+        assert_eq!(&chunks[1][..36], &data[64..]);
+        assert!(chunks[1][36..].iter().chain(&chunks[2]).all(|&b| b == 0));
+
+        // Same stripe as encode() when the sizes agree
+        let natural = encode(&params, &data).unwrap();
+        let fixed = encode_with_chunk_size(&params, &data, natural[0].len()).unwrap();
+        assert_eq!(fixed, natural);
+
+        // Exactly full, and empty
+        assert!(encode_with_chunk_size(&params, &[1u8; 64], 16).is_ok());
+        assert!(encode_with_chunk_size(&params, &[], 16).is_ok());
+
+        // One-byte sub-chunks cannot be decoded or repaired
+        assert!(matches!(
+            encode_with_chunk_size(&params, &[1u8; 32], 8),
+            Err(ClayError::ChunkTooSmall { min: 16, actual: 8 })
+        ));
+
+        assert!(matches!(
+            encode_with_chunk_size(&params, &data, 0),
+            Err(ClayError::InvalidChunkSize { expected: 8, actual: 0 })
+        ));
+        assert!(matches!(
+            encode_with_chunk_size(&params, &data, 60),
+            Err(ClayError::InvalidChunkSize { expected: 8, actual: 60 })
+        ));
+        assert!(matches!(
+            encode_with_chunk_size(&params, &data, 24),
+            Err(ClayError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn test_regenerate_parity() {
+        let code = ClayCode::new(5, 2, 6).unwrap();
+        let params = code.encode_params();
+        let data: Vec<u8> = (0..900).map(|i| (i * 29 % 256) as u8).collect();
+        let chunks = encode(&params, &data).unwrap();
+        let data_chunks: HashMap<usize, Vec<u8>> =
+            (0..params.k).map(|i| (i, chunks[i].clone())).collect();
+
+        let rebuilt = regenerate_parity(&params, &data_chunks, &[6, 5, 6]).unwrap();
+        assert_eq!(rebuilt.len(), 2);
+        assert_eq!(rebuilt[&5], chunks[5]);
+        assert_eq!(rebuilt[&6], chunks[6]);
+        assert!(regenerate_parity(&params, &data_chunks, &[]).unwrap().is_empty());
+
+        // Data indices are not parity, and every data chunk is required
+        assert!(matches!(
+            regenerate_parity(&params, &data_chunks, &[4]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            regenerate_parity(&params, &data_chunks, &[7]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        let mut partial = data_chunks.clone();
+        partial.remove(&2);
+        assert!(matches!(
+            regenerate_parity(&params, &partial, &[5]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        partial.insert(2, chunks[2].clone());
+        partial.insert(5, chunks[5].clone());
+        assert!(matches!(
+            regenerate_parity(&params, &partial, &[6]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn test_encode_into() {
+        let code = test_code();
+        let params = code.encode_params();
+        let data: Vec<u8> = (0..100).collect();
+        let expected = encode(&params, &data).unwrap();
+        let chunk_size = expected[0].len();
+
+        let mut bufs = vec![vec![0xAAu8; chunk_size]; params.n];
+        let mut out: Vec<&mut [u8]> = bufs.iter_mut().map(|b| b.as_mut_slice()).collect();
+        encode_into(&params, &data, &mut out).unwrap();
+        assert_eq!(bufs, expected);
+
+        // Wrong count or length is rejected before anything is written
+        let mut bufs = vec![vec![0xAAu8; chunk_size]; params.n];
+        let mut out: Vec<&mut [u8]> = bufs.iter_mut().map(|b| b.as_mut_slice()).collect();
+        assert!(matches!(
+            encode_into(&params, &data, &mut out[1..]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        out[3] = &mut [];
+        assert!(matches!(
+            encode_into(&params, &data, &mut out),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        assert!(bufs.iter().flatten().all(|&b| b == 0xAA));
+
+        // Shortened code (nu = 1)
+        let code = ClayCode::new(5, 2, 6).unwrap();
+        let params = code.encode_params();
+        let expected = encode(&params, &data).unwrap();
+        let mut bufs = vec![vec![0xAAu8; expected[0].len()]; params.n];
+        let mut out: Vec<&mut [u8]> = bufs.iter_mut().map(|b| b.as_mut_slice()).collect();
+        encode_into(&params, &data, &mut out).unwrap();
+        assert_eq!(bufs, expected);
+    }
+
+    #[test]
+    fn test_encode_into_with_workspace() {
+        for code in [test_code(), ClayCode::new(5, 2, 6).unwrap()] {
+            let params = code.encode_params();
+            let chunk_size = 4 * params.sub_chunk_no;
+            let mut ws = EncodeWorkspace::new(&code, chunk_size).unwrap();
+            let mut bufs = vec![vec![0xAAu8; chunk_size]; params.n];
+
+            // Reused across stripes of different lengths
+            for len in [ws.stripe_size(), 10, 0] {
+                let data: Vec<u8> = (0..len).map(|i| (i * 13 % 256) as u8).collect();
+                let expected = encode_with_chunk_size(&params, &data, chunk_size).unwrap();
+                let mut out: Vec<&mut [u8]> = bufs.iter_mut().map(|b| b.as_mut_slice()).collect();
+                encode_into_with(&params, &mut ws, &data, &mut out).unwrap();
+                assert_eq!(bufs, expected, "len {}", len);
+            }
+
+            let mut out: Vec<&mut [u8]> = bufs.iter_mut().map(|b| b.as_mut_slice()).collect();
+            assert!(matches!(
+                encode_into_with(&params, &mut ws, &[0u8; 1], &mut out[1..]),
+                Err(ClayError::InvalidParameters(_))
+            ));
+            let too_long = vec![0u8; ws.stripe_size() + 1];
+            assert!(matches!(
+                encode_into_with(&params, &mut ws, &too_long, &mut out),
+                Err(ClayError::InvalidParameters(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_encode_with_length_header() {
+        let code = test_code();
+        let params = code.encode_params();
+        let data = vec![0x5Au8; 37];
+        let chunks = encode_with_length(&params, &data).unwrap();
+        assert_eq!(chunks.len(), params.n);
+        assert_eq!(&chunks[0][..LENGTH_HEADER_SIZE], &37u64.to_le_bytes());
+        assert_eq!(&chunks[0][LENGTH_HEADER_SIZE..LENGTH_HEADER_SIZE + 4], &[0x5A; 4]);
+    }
+
+    #[test]
+    fn test_encode_chunks_matches_encode() {
+        let code = test_code();
+        let params = code.encode_params();
+        let data: Vec<u8> = (0..256).map(|i| (i * 5 % 256) as u8).collect();
+        let expected = encode(&params, &data).unwrap();
+
+        let data_chunks: Vec<Vec<u8>> = expected[..params.k].to_vec();
+        assert_eq!(encode_chunks(&params, &data_chunks).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_encode_tagged() {
+        let code = test_code();
+        let params = code.encode_params();
+        let data: Vec<u8> = (0..200).map(|i| (i * 9) as u8).collect();
+        let chunks = encode(&params, &data).unwrap();
+        let shards = encode_tagged(&params, &data).unwrap();
+
+        assert_eq!(shards.len(), params.n);
+        for (i, shard) in shards.iter().enumerate() {
+            assert_eq!(shard.index, i);
+            let kind = if i < params.k { ShardKind::Data } else { ShardKind::Parity };
+            assert_eq!(shard.kind, kind);
+            assert_eq!(shard.bytes, chunks[i]);
+        }
+    }
+
+    #[test]
+    fn test_encode_parity_returns_parity_chunks() {
+        let code = ClayCode::new(5, 3, 7).unwrap();
+        let params = code.encode_params();
+        let data: Vec<u8> = (0..900).map(|i| (i * 3 % 256) as u8).collect();
+        let expected = encode(&params, &data).unwrap();
+
+        let parity = encode_parity(&params, &expected[..params.k]).unwrap();
+        assert_eq!(parity.len(), params.m);
+        assert_eq!(parity, &expected[params.k..]);
+    }
+
+    #[test]
+    fn test_encode_chunks_rejects_malformed_input() {
+        let code = test_code();
+        let params = code.encode_params();
+        let mut data_chunks = vec![vec![1u8; 16]; params.k];
+
+        assert!(matches!(
+            encode_chunks(&params, &data_chunks[1..]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+
+        data_chunks[2].truncate(8);
+        assert!(matches!(
+            encode_chunks(&params, &data_chunks),
+            Err(ClayError::InconsistentChunkSizes { mismatched_idx: 2, .. })
+        ));
+
+        let odd = vec![vec![1u8; 12]; params.k];
+        assert!(matches!(
+            encode_chunks(&params, &odd),
+            Err(ClayError::InvalidChunkSize { expected: 8, actual: 12 })
+        ));
+
+        // One-byte sub-chunks
+        let tiny = vec![vec![7u8; code.sub_chunk_no]; params.k];
+        assert!(matches!(
+            encode_chunks(&params, &tiny),
+            Err(ClayError::ChunkTooSmall { min: 16, actual: 8 })
+        ));
+        assert!(matches!(
+            encode_parity(&params, &tiny),
+            Err(ClayError::ChunkTooSmall { min: 16, actual: 8 })
+        ));
+    }
+
+    #[test]
+    fn test_encode_with_workspace_matches_encode() {
+        // Unshortened and shortened (nu = 1) codes
+        for code in [test_code(), ClayCode::new(5, 3, 7).unwrap()] {
+            let params = code.encode_params();
+            let chunk_size = 4 * code.sub_chunk_no;
+            let mut ws = EncodeWorkspace::new(&code, chunk_size).unwrap();
+            assert_eq!(ws.stripe_size(), code.k * chunk_size);
+
+            // Reuse the workspace for full, short and empty stripes
+            for len in [ws.stripe_size(), ws.stripe_size() - 7, 1, 0] {
+                let data: Vec<u8> = (0..len).map(|i| (i * 13 + len) as u8).collect();
+                let mut padded = data.clone();
+                padded.resize(ws.stripe_size(), 0);
+                let expected = encode(&params, &padded).unwrap();
+
+                assert_eq!(encode_with(&params, &mut ws, &data).unwrap(), &expected[..]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_with_workspace_errors() {
+        let code = test_code();
+        let params = code.encode_params();
+
+        assert!(matches!(
+            EncodeWorkspace::new(&code, 12),
+            Err(ClayError::InvalidChunkSize { expected: 8, actual: 12 })
+        ));
+        assert!(matches!(
+            EncodeWorkspace::new(&code, 8),
+            Err(ClayError::ChunkTooSmall { min: 16, actual: 8 })
+        ));
+
+        // Same sizes as encode_with_chunk_size
+        let data: Vec<u8> = (0..96).collect();
+        let mut ws = EncodeWorkspace::new(&code, 24).unwrap();
+        let expected = encode_with_chunk_size(&params, &data, 24).unwrap();
+        assert_eq!(encode_with(&params, &mut ws, &data).unwrap(), &expected[..]);
+
+        let mut ws = EncodeWorkspace::new(&code, 16).unwrap();
+        assert!(matches!(
+            encode_with(&params, &mut ws, &[0u8; 65]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+
+        let other = ClayCode::new(5, 3, 7).unwrap();
+        assert!(matches!(
+            encode_with(&other.encode_params(), &mut ws, &[1, 2, 3]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn test_encode_batch() {
+        let code = ClayCode::new(5, 3, 7).unwrap();
+        let chunk_size = 2 * code.sub_chunk_no;
+        let blocks: Vec<Vec<u8>> = (0..5usize)
+            .map(|b| (0..b * 37).map(|i| (i * 7 + b) as u8).collect())
+            .collect();
+        let refs: Vec<&[u8]> = blocks.iter().map(Vec::as_slice).collect();
+
+        let batch = code.encode_batch(&refs, chunk_size).unwrap();
+        assert_eq!(batch.len(), blocks.len());
+        for (block, chunks) in blocks.iter().zip(&batch) {
+            assert_eq!(*chunks, code.encode_with_chunk_size(block, chunk_size).unwrap());
+        }
+        assert!(code.encode_batch(&[], chunk_size).unwrap().is_empty());
+
+        // One block too large fails the whole batch
+        let big = vec![0u8; code.k * chunk_size + 1];
+        assert!(matches!(
+            code.encode_batch(&[&blocks[1], &big], chunk_size),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            code.encode_batch(&refs, code.sub_chunk_no),
+            Err(ClayError::ChunkTooSmall { .. })
+        ));
+    }
+
     #[test]
     fn test_encode_chunk_alignment() {
-        let params = test_params();
+        let code = test_code();
+        let params = code.encode_params();
         let data = vec![0xABu8; 100];
-        let chunks = encode(&params, &data);
+        let chunks = encode(&params, &data).unwrap();
 
         // Chunk size should be divisible by sub_chunk_no
         for chunk in &chunks {
@@ -1,41 +1,66 @@
 //! Error types for Clay code operations
 
+use alloc::string::String;
+use alloc::vec::Vec;
+
 /// Error type for Clay code operations
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ClayError {
     /// Invalid code parameters (k, m, d)
     InvalidParameters(String),
-    /// Not enough helper nodes for repair
-    InsufficientHelpers { needed: usize, provided: usize },
+    /// Not enough helper nodes for repair; any of `candidates` (nodes that
+    /// are neither lost nor already provided) would count toward `needed`
+    InsufficientHelpers { needed: usize, provided: usize, candidates: Vec<usize> },
     /// Chunk size doesn't match expected sub-chunk alignment
     InvalidChunkSize { expected: usize, actual: usize },
+    /// Chunk size leaves sub-chunks under the 2-byte minimum; `min` is 2 * α
+    ChunkTooSmall { min: usize, actual: usize },
     /// Helper provided insufficient data
     InsufficientHelperData { helper: usize, expected: usize, actual: usize },
+    /// Helpers sent different amounts of repair data
+    InconsistentHelperSizes { first: usize, offending_helper: usize, offending_size: usize },
     /// Chunks have inconsistent sizes
     InconsistentChunkSizes { first_size: usize, mismatched_idx: usize, mismatched_size: usize },
     /// Too many erasures to recover (max is m)
     TooManyErasures { max: usize, actual: usize },
-    /// RS reconstruction failed
+    /// Reconstruction failed for a reason described by the message
     ReconstructionFailed(String),
+    /// The underlying Reed-Solomon codec rejected a layer
+    ReedSolomon(reed_solomon_erasure::Error),
     /// Missing required y-section helper for repair
     MissingYSectionHelper { lost_node: usize, missing_helper: usize },
+    /// A helper did not supply a sub-chunk the repair needs
+    MissingSubChunk { helper: usize, sub_chunk: usize },
+    /// Sub-packetization α = q^t exceeds the limit a code was built with
+    SubPacketizationTooLarge { alpha: usize, limit: usize },
     /// Arithmetic overflow in parameter calculation
     Overflow(String),
+    /// Reading input or writing output failed
+    Io(String),
 }
 
-impl std::fmt::Display for ClayError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl core::fmt::Display for ClayError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             ClayError::InvalidParameters(msg) => write!(f, "Invalid parameters: {}", msg),
-            ClayError::InsufficientHelpers { needed, provided } => {
-                write!(f, "Insufficient helpers: need {}, got {}", needed, provided)
+            ClayError::InsufficientHelpers { needed, provided, candidates } => {
+                write!(f, "Insufficient helpers: need {}, got {}; candidates {:?}",
+                       needed, provided, candidates)
             }
             ClayError::InvalidChunkSize { expected, actual } => {
                 write!(f, "Invalid chunk size: expected divisible by {}, got {}", expected, actual)
             }
+            ClayError::ChunkTooSmall { min, actual } => {
+                write!(f, "Chunk size {} is too small: sub-chunks need at least 2 bytes, \
+                           so chunks need at least {}", actual, min)
+            }
             ClayError::InsufficientHelperData { helper, expected, actual } => {
                 write!(f, "Helper {} provided {} bytes, expected {}", helper, actual, expected)
             }
+            ClayError::InconsistentHelperSizes { first, offending_helper, offending_size } => {
+                write!(f, "Helper {} provided {} bytes but the first helper provided {}",
+                       offending_helper, offending_size, first)
+            }
             ClayError::InconsistentChunkSizes { first_size, mismatched_idx, mismatched_size } => {
                 write!(f, "Chunk {} has size {} but expected {} (same as first chunk)",
                        mismatched_idx, mismatched_size, first_size)
@@ -44,13 +69,35 @@ impl std::fmt::Display for ClayError {
                 write!(f, "Too many erasures: max {} supported, got {}", max, actual)
             }
             ClayError::ReconstructionFailed(msg) => write!(f, "RS reconstruction failed: {}", msg),
+            ClayError::ReedSolomon(e) => write!(f, "Reed-Solomon error: {}", e),
             ClayError::MissingYSectionHelper { lost_node, missing_helper } => {
                 write!(f, "Missing required y-section helper {} for repairing node {}",
                        missing_helper, lost_node)
             }
+            ClayError::MissingSubChunk { helper, sub_chunk } => {
+                write!(f, "Helper {} did not supply sub-chunk {}", helper, sub_chunk)
+            }
+            ClayError::SubPacketizationTooLarge { alpha, limit } => {
+                write!(f, "Sub-packetization {} exceeds the limit of {}", alpha, limit)
+            }
             ClayError::Overflow(msg) => write!(f, "Arithmetic overflow: {}", msg),
+            ClayError::Io(msg) => write!(f, "I/O error: {}", msg),
         }
     }
 }
 
-impl std::error::Error for ClayError {}
+impl From<reed_solomon_erasure::Error> for ClayError {
+    fn from(e: reed_solomon_erasure::Error) -> Self {
+        ClayError::ReedSolomon(e)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ClayError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ClayError::ReedSolomon(e) => Some(e),
+            _ => None,
+        }
+    }
+}
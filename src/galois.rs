@@ -0,0 +1,47 @@
+//! GF(2^8) arithmetic used by the coupling transforms
+//!
+//! The field and the coupling matrix are the ones [`ClayCode`](crate::ClayCode)
+//! uses, so downstream code can check parity or reproduce the PRT/PFT
+//! without depending on `reed_solomon_erasure` directly.
+
+pub use reed_solomon_erasure::galois_8::{add, div, mul};
+
+/// Multiplicative inverse: a^(-1) = 1/a
+///
+/// # Panics
+/// If `a` is 0
+#[inline]
+pub fn inv(a: u8) -> u8 {
+    div(1, a)
+}
+
+/// Determinant of the coupling matrix [1, γ; γ, 1]: 1 + γ²
+///
+/// Subtraction is addition in GF(2^8), so this is also 1 - γ². The PFT
+/// divides by it; it is non-zero for every valid γ.
+#[inline]
+pub fn coupling_det(gamma: u8) -> u8 {
+    add(1, mul(gamma, gamma))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_ops() {
+        for a in 1..=255u8 {
+            assert_eq!(mul(a, inv(a)), 1);
+            assert_eq!(div(mul(a, 0x53), 0x53), a);
+            assert_eq!(add(a, a), 0);
+        }
+    }
+
+    #[test]
+    fn test_coupling_det() {
+        assert_eq!(coupling_det(2), 5);
+        // γ = 1 is the degenerate coupling: 1 + 1 = 0
+        assert_eq!(coupling_det(1), 0);
+        assert!((2..=255).all(|g| coupling_det(g) != 0));
+    }
+}
@@ -0,0 +1,67 @@
+//! Byte layout of the sub-chunks inside a stored chunk
+//!
+//! Every algorithm in this crate works on node-major chunks, where sub-chunk
+//! z occupies bytes z·S..(z+1)·S for sub-chunk size S. Some storage formats
+//! instead interleave the layers, storing byte b of every layer together so
+//! that a read of any byte range touches all α layers equally. The
+//! `*_with_layout` methods of `ClayCode` convert at the API boundary.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// How the α sub-chunks of a chunk are packed into its bytes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SubChunkLayout {
+    /// Sub-chunks one after another: byte b of layer z at z·S + b
+    #[default]
+    NodeMajor,
+    /// Layers interleaved byte by byte: byte b of layer z at b·α + z
+    LayerMajor,
+}
+
+impl SubChunkLayout {
+    /// Rearrange a node-major chunk of α sub-chunks into this layout
+    pub(crate) fn arrange(self, chunk: Vec<u8>, sub_chunk_no: usize) -> Vec<u8> {
+        match self {
+            Self::NodeMajor => chunk,
+            Self::LayerMajor => transpose(&chunk, sub_chunk_no, chunk.len() / sub_chunk_no),
+        }
+    }
+
+    /// Rearrange a chunk in this layout into node-major order
+    pub(crate) fn to_node_major(self, chunk: &[u8], sub_chunk_no: usize) -> Vec<u8> {
+        match self {
+            Self::NodeMajor => chunk.to_vec(),
+            Self::LayerMajor => transpose(chunk, chunk.len() / sub_chunk_no, sub_chunk_no),
+        }
+    }
+}
+
+/// Transpose a rows × cols byte matrix stored row by row
+fn transpose(data: &[u8], rows: usize, cols: usize) -> Vec<u8> {
+    let mut out = vec![0u8; data.len()];
+    for (r, row) in data.chunks_exact(cols).enumerate().take(rows) {
+        for (c, &byte) in row.iter().enumerate() {
+            out[c * rows + r] = byte;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_layer_major_interleaves_layers() {
+        // α = 4 sub-chunks of 3 bytes: layer z holds 10z, 10z+1, 10z+2
+        let node_major: Vec<u8> = (0..4).flat_map(|z| (0..3).map(move |b| 10 * z + b)).collect();
+        let layer_major = SubChunkLayout::LayerMajor.arrange(node_major.clone(), 4);
+        assert_eq!(layer_major, [0, 10, 20, 30, 1, 11, 21, 31, 2, 12, 22, 32]);
+        assert_eq!(SubChunkLayout::LayerMajor.to_node_major(&layer_major, 4), node_major);
+
+        let unchanged = SubChunkLayout::NodeMajor.arrange(node_major.clone(), 4);
+        assert_eq!(unchanged, node_major);
+        assert_eq!(SubChunkLayout::default(), SubChunkLayout::NodeMajor);
+    }
+}
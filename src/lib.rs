@@ -11,48 +11,201 @@
 //!
 //! ```
 //! use clay_codes::ClayCode;
-//! use std::collections::HashMap;
 //!
 //! // Create a (6, 4, 5) Clay code: 4 data + 2 parity, repair with 5 helpers
 //! let clay = ClayCode::new(4, 2, 5).unwrap();
 //!
 //! // Encode data
 //! let data = b"Hello, Clay codes!";
-//! let chunks = clay.encode(data);
+//! let chunks = clay.encode(data).unwrap();
 //!
 //! // Decode with all chunks
-//! let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
-//! for (i, chunk) in chunks.iter().enumerate() {
-//!     available.insert(i, chunk.clone());
-//! }
-//! let decoded = clay.decode(&available, &[]).unwrap();
+//! let decoded = clay.decode_all(&chunks).unwrap();
 //! assert_eq!(&decoded[..data.len()], &data[..]);
 //! ```
 //!
 //! # Modules
 //!
+//! - `builder`: Named-parameter construction of ClayCode
+//! - `checksum`: Per-sub-chunk CRC32C trailers for corruption-aware repair
 //! - `error`: Error types for Clay code operations
+//! - `params`: Persistable (k, m, d) parameters
+//! - `warning`: Non-fatal warnings about inadvisable parameters
 //! - `transforms`: Pairwise coupling transforms (PRT/PFT)
+//! - `simd`: 16-byte-at-a-time PRT/PFT kernels (`simd` feature)
+//! - `galois`: Public GF(2^8) arithmetic and the coupling determinant
+//! - `codec`: Reed-Solomon codec for the uncoupled layers (GF(2^8) or GF(2^16))
+//!   behind the `RsBackend` trait, replaceable with `ClayCode::with_backend`
 //! - `encode`: Encoding implementation
+//! - `layout`: Node-major or layer-interleaved packing of sub-chunks
 //! - `decode`: Decoding and erasure recovery
 //! - `repair`: Single-node optimal repair
+//! - `session`: Decoding from chunks fed one at a time as they arrive
+//! - `stream`: Stripe-by-stripe encoding of readers too large for memory
+//! - `verify`: Stripe consistency checks and error correction
+//! - `profile`: Transform and RS timings of encode and decode (`profile` feature)
+//! - `testing`: Round-trip and repair checks (`testing` feature)
+//!
+//! # Features
+//!
+//! - `rayon`: Decode the independent layers of each intersection score level
+//!   in parallel. Encoding computes parity through the same layered decode,
+//!   so it runs the per-layer RS encodes of a level in parallel too. Both
+//!   are serial without it and produce identical output either way.
+//! - `serde`: Serialize and deserialize [`ClayParams`] and [`ObjectManifest`].
+//! - `tracing`: Emit `tracing` spans and events from layered decoding (the
+//!   intersection-score order and the RS recoveries of each layer) and from
+//!   the phases of repair (aloof nodes, per-layer U recovery, C recovery).
+//!   Compiled out entirely without the feature.
+//! - `testing`: Expose `testing::roundtrip_check`,
+//!   `testing::repair_check` and `testing::partial_helper_data` for fuzzing
+//!   arbitrary codes and erasure patterns in downstream test suites.
+//! - `simd`: Run the full pairwise transforms (both vertices of a coupled
+//!   pair) 16 bytes at a time with SSSE3 byte shuffles on x86 and x86_64.
+//!   Detected at run time with `std`, at compile time without it; results
+//!   are identical to the scalar tables, which other targets keep using.
+//! - `profile`: Add [`ClayCode::encode_profiled`] and
+//!   [`ClayCode::decode_profiled`], which time the pairwise transforms and
+//!   the RS decodes of every layer with `std::time::Instant` and return
+//!   the totals as a [`PhaseTimings`]. No timing code is compiled without
+//!   it. Implies `std`.
+//! - `std` (default): Standard library support, see below. Enabled by
+//!   `rayon` and `profile`.
+//!
+//! # no_std
+//!
+//! With default features off the crate is `no_std` and needs only `alloc`.
+//! Encoding, decoding, repair and verification are all available, with
+//! chunk maps taking `alloc::collections::BTreeMap` in place of
+//! `std::collections::HashMap`. These need `std`:
+//!
+//! - [`ClayCode::decode_to_writer`] and the [`Encoder`] stream API
+//!   ([`ClayCode::encoder`]), which use `std::io`
+//! - the `std::error::Error` impl of [`ClayError`]
+//! - the `rayon` and `profile` features
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+extern crate alloc;
+
+use alloc::collections::BTreeSet;
+#[cfg(not(feature = "std"))]
+use alloc::collections::BTreeMap as HashMap;
+use alloc::format;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::ops::Range;
+#[cfg(feature = "std")]
 use std::collections::HashMap;
+#[cfg(feature = "std")]
+use std::io::Write;
 
+mod builder;
+mod checksum;
+mod codec;
 mod coords;
 mod decode;
 mod encode;
 mod error;
+mod layout;
+pub mod galois;
+mod params;
+#[cfg(feature = "profile")]
+mod profile;
 mod repair;
+mod session;
+#[cfg(feature = "simd")]
+mod simd;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(test)]
+mod test_vectors;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 mod transforms;
+mod verify;
+mod warning;
 
+pub use builder::ClayCodeBuilder;
+pub use checksum::{crc32c, CHECKSUM_SIZE};
+pub use codec::{RsBackend, RsCodec};
+pub use decode::HealedStripe;
+pub use encode::{
+    EncodeWorkspace, EncodedObject, ObjectManifest, PaddingScheme, Shard, ShardKind, StripeGeometry,
+};
 pub use error::ClayError;
+pub use layout::SubChunkLayout;
+/// Error type of the Reed-Solomon codec, carried by [`ClayError::ReedSolomon`]
+pub use reed_solomon_erasure::Error as RsError;
+pub use params::ClayParams;
+#[cfg(feature = "profile")]
+pub use profile::PhaseTimings;
+pub use repair::{HelperByteRanges, HelperRole, RepairStats, RepairWorkspace};
+pub use session::DecodeSession;
+#[cfg(feature = "std")]
+pub use stream::Encoder;
+pub use verify::MAX_CORRECTION_CANDIDATES;
+pub use warning::{ClayWarning, LARGE_SUB_CHUNK_NO};
 
 const MAX_RS_SHARDS: usize = 32768;
 
-use decode::decode as decode_chunks;
-use encode::encode as encode_chunks;
-use repair::{minimum_to_repair as min_repair, repair as repair_chunk};
+/// Largest sub-packetization α the constructors accept by default
+///
+/// Work and memory per stripe grow with α, which is exponential in the
+/// number of y-sections: (q = 2, n = 32) already gives α = 65536. Use
+/// [`ClayCode::new_unchecked_alpha`] or
+/// [`ClayCodeBuilder::max_sub_chunk_no`] to go past it.
+pub const DEFAULT_MAX_SUB_CHUNK_NO: usize = 1 << 16;
+
+use decode::{
+    decode as decode_chunks, decode_all as decode_all_chunks, decode_exact as decode_exact_chunks,
+    decode_unpad as decode_unpadded_chunks, decode_lenient as decode_shard_survivors,
+    decode_object as decode_striped_object,
+    decode_refs as decode_chunk_refs, decode_from_pairs as decode_chunk_pairs,
+    decode_and_heal as decode_and_heal_chunks, decode_with_report as decode_chunks_with_report,
+    decode_strict as decode_chunks_strict, reconstruct_chunks as reconstruct_erased,
+    decode_nodes as decode_chunk_subset, decode_owned as decode_owned_chunks,
+    decode_shards as decode_shard_array, decoding_order as layer_decoding_order,
+    layer_scores as intersection_scores, read_range as read_data_range,
+};
+#[cfg(feature = "std")]
+use decode::decode_to_writer as decode_chunks_to_writer;
+#[cfg(feature = "profile")]
+use decode::decode_profiled as decode_chunks_profiled;
+#[cfg(feature = "profile")]
+use encode::encode_profiled as encode_chunks_profiled;
+#[cfg(feature = "std")]
+use repair::repair_from_readers as repair_chunk_from_readers;
+use encode::{
+    encode as encode_chunks, encode_chunks as encode_split_chunks, encode_parity,
+    encode_into as encode_into_buffers, encode_with as encode_into_workspace,
+    encode_into_with as encode_into_buffers_with,
+    encode_with_chunk_size as encode_fixed_chunks, regenerate_parity as regenerate_parity_chunks,
+    encode_with_length as encode_chunks_with_length, geometry as stripe_geometry,
+    encode_with_padding as encode_chunks_with_padding, encode_tagged as encode_tagged_chunks,
+    encode_object as encode_striped_object,
+};
+use repair::{
+    aloof_nodes as repair_aloof_nodes, minimum_to_repair as min_repair,
+    helper_layer_roles as repair_helper_roles,
+    minimum_to_repair_multi as min_repair_multi,
+    repair as repair_chunk, repair_byte_ranges as repair_ranges,
+    repair_from_full_chunks as repair_chunk_from_full,
+    repair_from_subchunk_map as repair_chunk_from_subchunk_map,
+    repair_multi as repair_chunks_multi, repair_plan as repair_index_plan,
+    repair_subchunk_indices as repair_indices,
+    repair_verified as repair_chunk_verified, repair_with as repair_into_workspace,
+    repair_with_stats as repair_chunk_with_stats, repair_to_buffer as repair_chunk_into,
+    minimum_to_repair_with_d as min_repair_with_d, repair_with_d as repair_chunk_with_d,
+    minimum_to_repair_with_topology as min_repair_with_topology,
+    minimum_to_repair_min_cpu as min_repair_min_cpu,
+    minimum_to_repair_prefer as min_repair_prefer,
+};
+use checksum::{
+    checksummed_helper_data as checksummed_repair_data,
+    encode_with_checksums as encode_checksummed, repair_with_checksums as repair_checksummed,
+};
+use verify::{decode_with_correction as decode_corrected, verify as verify_stripe};
 
 /// Clay (Coupled-Layer) erasure code
 #[derive(Clone, Debug)]
@@ -75,33 +228,107 @@ pub struct ClayCode {
     pub sub_chunk_no: usize,
     /// Sub-chunks needed from each helper during repair: β = α / q
     pub beta: usize,
-    /// Number of original shards for RS (k + nu)
-    original_count: usize,
-    /// Number of recovery shards for RS (m)
-    recovery_count: usize,
+    /// Coupling coefficient γ of the pairwise transforms (γ ≠ 0, γ² ≠ 1)
+    pub gamma: u8,
+    /// RS codec for the uncoupled layers, built once and shared across clones
+    rs: Arc<dyn RsBackend>,
+    /// Multiply-by-γ tables for the pairwise transforms, shared like `rs`
+    mul: Arc<transforms::GfMulTable>,
+    /// Plane vector of every layer (α × t, flattened), shared like `rs`
+    planes: Arc<[usize]>,
+    /// Companion layer of every vertex (t × q × α, flattened), shared like `rs`
+    companions: Arc<[usize]>,
 }
 
+// One code is shared by worker threads repairing different objects, so
+// it must stay Send + Sync (no interior mutability without locking)
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ClayCode>();
+};
+
 impl ClayCode {
     /// Create a new Clay code with parameters (k, m, d)
     ///
+    /// With m = 1 the only valid d is k, which gives q = 1 and α = 1: a
+    /// plain single-parity RS code with no coupling, repaired by reading k
+    /// full chunks.
+    ///
+    /// k = 1 is allowed: each chunk is then a coded copy of the data, like
+    /// replication that repairs from d partial copies.
+    ///
     /// # Parameters
     /// - `k`: Number of data chunks (systematic nodes)
     /// - `m`: Number of parity chunks
-    /// - `d`: Number of helper nodes for repair
+    /// - `d`: Number of helper nodes for repair, k < d < k + m (d = k if m = 1)
     ///
     /// # Returns
     /// Result with ClayCode or error if parameters are invalid
     pub fn new(k: usize, m: usize, d: usize) -> Result<Self, ClayError> {
+        Self::with_gamma(k, m, d, transforms::DEFAULT_GAMMA)
+    }
+
+    /// Create a new Clay code with a custom coupling coefficient γ
+    ///
+    /// Chunks encoded with one γ can only be decoded and repaired with the
+    /// same γ.
+    ///
+    /// # Parameters
+    /// - `k`: Number of data chunks (systematic nodes)
+    /// - `m`: Number of parity chunks
+    /// - `d`: Number of helper nodes for repair
+    /// - `gamma`: Coupling coefficient in GF(2^8); must satisfy γ ≠ 0, γ² ≠ 1
+    ///
+    /// # Returns
+    /// Result with ClayCode or error if parameters are invalid or α exceeds
+    /// DEFAULT_MAX_SUB_CHUNK_NO
+    pub fn with_gamma(k: usize, m: usize, d: usize, gamma: u8) -> Result<Self, ClayError> {
+        Self::with_alpha_limit(k, m, d, gamma, DEFAULT_MAX_SUB_CHUNK_NO)
+    }
+
+    /// Create a new Clay code without the default cap on α
+    ///
+    /// For experiments with very wide codes. α = q^t must still fit in a
+    /// usize, and every stripe is split into α sub-chunks, so expect
+    /// encoding, decoding and repair to be slow and memory hungry.
+    ///
+    /// # Parameters
+    /// - `k`: Number of data chunks (systematic nodes)
+    /// - `m`: Number of parity chunks
+    /// - `d`: Number of helper nodes for repair
+    ///
+    /// # Returns
+    /// Result with ClayCode or error if parameters are invalid
+    pub fn new_unchecked_alpha(k: usize, m: usize, d: usize) -> Result<Self, ClayError> {
+        Self::with_alpha_limit(k, m, d, transforms::DEFAULT_GAMMA, usize::MAX)
+    }
+
+    /// Create a code, rejecting any α above `max_sub_chunk_no`
+    pub(crate) fn with_alpha_limit(
+        k: usize,
+        m: usize,
+        d: usize,
+        gamma: u8,
+        max_sub_chunk_no: usize,
+    ) -> Result<Self, ClayError> {
+        if !transforms::is_valid_gamma(gamma) {
+            return Err(ClayError::InvalidParameters(format!(
+                "gamma must satisfy gamma != 0 and gamma^2 != 1, got {}",
+                gamma
+            )));
+        }
         if k < 1 {
             return Err(ClayError::InvalidParameters("k must be at least 1".into()));
         }
         if m < 1 {
             return Err(ClayError::InvalidParameters("m must be at least 1".into()));
         }
-        if d < k + 1 || d > k + m - 1 {
+        // A single parity has no coupling: d = k, q = 1 and α = 1
+        let min_d = if m == 1 { k } else { k + 1 };
+        if d < min_d || d > k + m - 1 {
             return Err(ClayError::InvalidParameters(format!(
                 "d must be in range [{}, {}], got {}",
-                k + 1,
+                min_d,
                 k + m - 1,
                 d
             )));
@@ -120,9 +347,17 @@ impl ClayCode {
             ClayError::Overflow(format!("q^t = {}^{} overflows", q, t))
         })?;
 
+        if sub_chunk_no > max_sub_chunk_no {
+            return Err(ClayError::SubPacketizationTooLarge {
+                alpha: sub_chunk_no,
+                limit: max_sub_chunk_no,
+            });
+        }
+
         let beta = sub_chunk_no / q; // β = α / q
 
-        // Validate that k+nu+m fits in reed-solomon limits (up to 32768 shards)
+        // Validate that k+nu+m fits in reed-solomon limits (up to 32768 shards).
+        // Codes wider than 256 shards use GF(2^16) for the uncoupled layers.
         let original_count = k + nu;
         let recovery_count = m;
         if original_count > MAX_RS_SHARDS || recovery_count > MAX_RS_SHARDS {
@@ -131,6 +366,10 @@ impl ClayCode {
             ));
         }
 
+        let rs = codec::RsCodec::new(original_count, recovery_count).map_err(|e| {
+            ClayError::InvalidParameters(format!("RS codec init failed: {:?}", e))
+        })?;
+
         Ok(ClayCode {
             k,
             m,
@@ -141,18 +380,312 @@ impl ClayCode {
             nu,
             sub_chunk_no,
             beta,
-            original_count,
-            recovery_count,
+            gamma,
+            rs: Arc::new(rs),
+            mul: Arc::new(transforms::GfMulTable::new(gamma)),
+            planes: coords::plane_vector_table(sub_chunk_no, t, q).into(),
+            companions: coords::companion_layer_table(sub_chunk_no, t, q).into(),
         })
     }
 
+    /// Create a new Clay code whose uncoupled layers use a custom backend
+    ///
+    /// The pairwise transforms are unchanged; only the per-layer MDS code is
+    /// swapped. Chunks are only compatible with codes using the same backend.
+    ///
+    /// # Parameters
+    /// - `k`: Number of data chunks (systematic nodes)
+    /// - `m`: Number of parity chunks
+    /// - `d`: Number of helper nodes for repair
+    /// - `backend`: Layer code with k + nu data shards and m parity shards
+    ///
+    /// # Returns
+    /// Result with ClayCode or error if the parameters are invalid or the
+    /// backend's shard counts do not match the code
+    pub fn with_backend<B: RsBackend + 'static>(
+        k: usize,
+        m: usize,
+        d: usize,
+        backend: B,
+    ) -> Result<Self, ClayError> {
+        Self::builder().k(k).m(m).d(d).backend(backend).build()
+    }
+
+    /// Swap the layer code for `backend`, checking that it fits this code
+    pub(crate) fn replace_backend(
+        mut self,
+        backend: Arc<dyn RsBackend>,
+    ) -> Result<Self, ClayError> {
+        if backend.data_shard_count() != self.k + self.nu
+            || backend.parity_shard_count() != self.m
+        {
+            return Err(ClayError::InvalidParameters(format!(
+                "backend has {} data and {} parity shards, expected {} and {}",
+                backend.data_shard_count(),
+                backend.parity_shard_count(),
+                self.k + self.nu,
+                self.m
+            )));
+        }
+        if backend.symbol_size() == 0 {
+            return Err(ClayError::InvalidParameters(
+                "backend symbol size must be at least 1".into(),
+            ));
+        }
+        self.rs = backend;
+        Ok(self)
+    }
+
+    /// Create a new Clay code and list any inadvisable parameter choices
+    ///
+    /// Accepts and rejects exactly what new() does. The warnings point out
+    /// codes that work but cost more than necessary, such as d < k + m - 1.
+    ///
+    /// # Parameters
+    /// - `k`: Number of data chunks (systematic nodes)
+    /// - `m`: Number of parity chunks
+    /// - `d`: Number of helper nodes for repair
+    ///
+    /// # Returns
+    /// Result with the ClayCode and its warnings (empty if there are none),
+    /// or error if parameters are invalid
+    pub fn new_checked(
+        k: usize,
+        m: usize,
+        d: usize,
+    ) -> Result<(Self, Vec<ClayWarning>), ClayError> {
+        let code = Self::new(k, m, d)?;
+        let warnings = warning::warnings_for(&code);
+        Ok((code, warnings))
+    }
+
     /// Create with default d = k + m - 1 (maximum helpers)
     pub fn new_default(k: usize, m: usize) -> Result<Self, ClayError> {
         Self::new(k, m, k + m - 1)
     }
 
+    /// Create a Clay code from the total node count n and data count k
+    ///
+    /// For configs written in the (n, k) convention used by Ceph and ISA-L;
+    /// m = n - k.
+    ///
+    /// # Parameters
+    /// - `n`: Total number of chunks (data + parity)
+    /// - `k`: Number of data chunks (systematic nodes)
+    /// - `d`: Number of helper nodes for repair
+    ///
+    /// # Returns
+    /// Result with ClayCode or error if n <= k or the parameters are invalid
+    pub fn from_n_k(n: usize, k: usize, d: usize) -> Result<Self, ClayError> {
+        if n <= k {
+            return Err(ClayError::InvalidParameters(format!(
+                "n must be greater than k, got n = {}, k = {}",
+                n, k
+            )));
+        }
+        Self::new(k, n - k, d)
+    }
+
+    /// Create from (n, k) with default d = n - 1 (maximum helpers)
+    pub fn from_n_k_default(n: usize, k: usize) -> Result<Self, ClayError> {
+        Self::from_n_k(n, k, n.saturating_sub(1))
+    }
+
+    /// Rebuild a code from stored parameters
+    ///
+    /// # Parameters
+    /// - `params`: The (k, m, d) of the code, e.g. from [`ClayCode::params`]
+    ///
+    /// # Returns
+    /// Result with ClayCode or error if parameters are invalid
+    pub fn from_params(params: ClayParams) -> Result<Self, ClayError> {
+        Self::new(params.k, params.m, params.d)
+    }
+
+    /// Get the free parameters of this code for storage
+    pub fn params(&self) -> ClayParams {
+        ClayParams {
+            k: self.k,
+            m: self.m,
+            d: self.d,
+        }
+    }
+
+    /// Check that the derived parameters agree with (k, m, d)
+    ///
+    /// Every constructor produces a consistent code, but the parameter
+    /// fields are public and can be changed afterwards. This recomputes
+    /// n, q, nu, t, α and β and checks them, the layer code's shard counts
+    /// and γ.
+    ///
+    /// # Returns
+    /// Ok, or error naming the first field that disagrees
+    pub fn validate(&self) -> Result<(), ClayError> {
+        let mismatch = |field: &str, actual: usize, expected: usize| {
+            Err(ClayError::InvalidParameters(format!(
+                "{} is {} but should be {}",
+                field, actual, expected
+            )))
+        };
+        if self.k < 1 || self.m < 1 {
+            return Err(ClayError::InvalidParameters(format!(
+                "k and m must be at least 1, got k = {}, m = {}",
+                self.k, self.m
+            )));
+        }
+        let n = self.k.checked_add(self.m).ok_or_else(|| {
+            ClayError::Overflow(format!("k + m ({} + {})", self.k, self.m))
+        })?;
+        if self.n != n {
+            return mismatch("n", self.n, n);
+        }
+        let min_d = if self.m == 1 { self.k } else { self.k + 1 };
+        if self.d < min_d || self.d > n - 1 {
+            return Err(ClayError::InvalidParameters(format!(
+                "d must be in range [{}, {}], got {}",
+                min_d,
+                n - 1,
+                self.d
+            )));
+        }
+        let q = self.d - self.k + 1;
+        if self.q != q {
+            return mismatch("q", self.q, q);
+        }
+        let nu = (q - n % q) % q;
+        if self.nu != nu {
+            return mismatch("nu", self.nu, nu);
+        }
+        let t = (n + nu) / q;
+        if self.t != t {
+            return mismatch("t", self.t, t);
+        }
+        let sub_chunk_no = checked_pow(q, t)
+            .ok_or_else(|| ClayError::Overflow(format!("q^t = {}^{} overflows", q, t)))?;
+        if self.sub_chunk_no != sub_chunk_no {
+            return mismatch("sub_chunk_no", self.sub_chunk_no, sub_chunk_no);
+        }
+        if self.beta != sub_chunk_no / q {
+            return mismatch("beta", self.beta, sub_chunk_no / q);
+        }
+        if self.rs.data_shard_count() != self.k + nu {
+            return mismatch("RS data shard count", self.rs.data_shard_count(), self.k + nu);
+        }
+        if self.rs.parity_shard_count() != self.m {
+            return mismatch("RS parity shard count", self.rs.parity_shard_count(), self.m);
+        }
+        if self.planes.len() != sub_chunk_no * t {
+            return mismatch("plane vector table length", self.planes.len(), sub_chunk_no * t);
+        }
+        if self.companions.len() != t * q * sub_chunk_no {
+            let expected = t * q * sub_chunk_no;
+            return mismatch("companion layer table length", self.companions.len(), expected);
+        }
+        if !transforms::is_valid_gamma(self.gamma) {
+            return Err(ClayError::InvalidParameters(format!(
+                "gamma must satisfy gamma != 0 and gamma^2 != 1, got {}",
+                self.gamma
+            )));
+        }
+        if self.mul.gamma() != self.gamma {
+            return mismatch("multiply table gamma", self.mul.gamma().into(), self.gamma.into());
+        }
+        Ok(())
+    }
+
+    /// List the codes that meet storage, sub-packetization and durability targets
+    ///
+    /// Enumerates every valid (k, m, d) and keeps those with
+    /// n/k <= `target_overhead`, m >= `min_durability` and
+    /// α <= `max_sub_chunk_no`. Candidates are walked by q = d - k + 1 and
+    /// t = ceil(n / q), so α = q^t is known before a code is built and the
+    /// walk stops once it exceeds the cap. Caps above
+    /// DEFAULT_MAX_SUB_CHUNK_NO are clamped to it, as `new` enforces it.
+    /// With `min_durability` <= 1 the single-parity codes (d = k, α = 1)
+    /// are included too, up to the 256 chunks a GF(2^8) stripe can hold.
+    ///
+    /// # Parameters
+    /// - `target_overhead`: Largest acceptable storage overhead n/k
+    /// - `max_sub_chunk_no`: Largest acceptable sub-packetization α
+    /// - `min_durability`: Smallest acceptable number of parity chunks m
+    ///
+    /// # Returns
+    /// Matching codes sorted by normalized repair bandwidth, lowest first
+    pub fn recommend(
+        target_overhead: f64,
+        max_sub_chunk_no: usize,
+        min_durability: usize,
+    ) -> Vec<ClayCode> {
+        let max_alpha = max_sub_chunk_no.min(DEFAULT_MAX_SUB_CHUNK_NO);
+        let mut codes = Vec::new();
+
+        if min_durability <= 1 && max_alpha >= 1 {
+            for k in 1..codec::GF8_MAX_SHARDS {
+                if (k + 1) as f64 > target_overhead * k as f64 {
+                    continue;
+                }
+                if let Ok(code) = ClayCode::new(k, 1, k) {
+                    codes.push(code);
+                }
+            }
+        }
+
+        // m >= q and k >= 1 give n > q, so t >= 2 and q^2 <= α
+        let mut q = 2;
+        while q * q <= max_alpha {
+            let mut t = 2;
+            while checked_pow(q, t).map_or(false, |alpha| alpha <= max_alpha) {
+                for nu in 0..q {
+                    let n = q * t - nu;
+                    for m in min_durability.max(q)..n {
+                        let k = n - m;
+                        if n as f64 > target_overhead * k as f64 {
+                            continue;
+                        }
+                        if let Ok(code) = ClayCode::new(k, m, k + q - 1) {
+                            codes.push(code);
+                        }
+                    }
+                }
+                t += 1;
+            }
+            q += 1;
+        }
+
+        codes.sort_by(|a, b| {
+            a.normalized_repair_bandwidth()
+                .total_cmp(&b.normalized_repair_bandwidth())
+        });
+        codes
+    }
+
+    /// Start building a code with named parameters
+    ///
+    /// ```
+    /// use clay_codes::ClayCode;
+    ///
+    /// let clay = ClayCode::builder().k(4).m(2).build().unwrap();
+    /// assert_eq!(clay.d, 5);
+    /// ```
+    pub fn builder() -> ClayCodeBuilder {
+        ClayCodeBuilder::new()
+    }
+
+    /// Create a streaming encoder for stripes of `stripe_size` data bytes
+    ///
+    /// # Parameters
+    /// - `stripe_size`: Data bytes per stripe; k times a chunk size that
+    ///   encode_with_chunk_size() accepts (a multiple of α of at least 2 * α)
+    ///
+    /// # Returns
+    /// Result with Encoder or error if the stripe size is misaligned
+    #[cfg(feature = "std")]
+    pub fn encoder(&self, stripe_size: usize) -> Result<Encoder, ClayError> {
+        Encoder::new(self, stripe_size)
+    }
+
     /// Get encoding parameters for internal use
-    fn encode_params(&self) -> encode::EncodeParams {
+    fn encode_params(&self) -> encode::EncodeParams<'_> {
         encode::EncodeParams {
             k: self.k,
             m: self.m,
@@ -161,130 +694,1743 @@ impl ClayCode {
             t: self.t,
             nu: self.nu,
             sub_chunk_no: self.sub_chunk_no,
-            original_count: self.original_count,
-            recovery_count: self.recovery_count,
+            mul: &self.mul,
+            rs: &*self.rs,
+            planes: &self.planes,
+            companions: &self.companions,
         }
     }
 
     /// Encode data into n chunks
     ///
+    /// Chunks are ceil(data.len() / k) bytes rounded up to whole sub-chunks,
+    /// with at least 2 bytes per sub-chunk; the data is zero-padded to fill
+    /// the k data chunks.
+    ///
     /// # Parameters
     /// - `data`: Raw data bytes to encode
     ///
     /// # Returns
-    /// Vector of n chunks, each containing α sub-chunks
-    pub fn encode(&self, data: &[u8]) -> Vec<Vec<u8>> {
+    /// Vector of n chunks, each containing α sub-chunks, or error if the
+    /// parity computation fails
+    pub fn encode(&self, data: &[u8]) -> Result<Vec<Vec<u8>>, ClayError> {
         encode_chunks(&self.encode_params(), data)
     }
 
-    /// Decode data from available chunks
+    /// Encode data into n chunks, timing the transform and RS phases
+    ///
+    /// Same as [`ClayCode::encode`]. Parity is computed by the layered
+    /// decode, so the timings cover its layers.
     ///
     /// # Parameters
-    /// - `available`: Map from chunk index to chunk data
-    /// - `erasures`: Set of erased chunk indices
+    /// - `data`: Raw data bytes to encode
     ///
     /// # Returns
-    /// Recovered original data, or error if decoding fails
-    pub fn decode(
+    /// The n chunks and the phase timings, or error if the parity
+    /// computation fails
+    #[cfg(feature = "profile")]
+    pub fn encode_profiled(
         &self,
-        available: &HashMap<usize, Vec<u8>>,
-        erasures: &[usize],
-    ) -> Result<Vec<u8>, ClayError> {
-        decode_chunks(&self.encode_params(), available, erasures)
+        data: &[u8],
+    ) -> Result<(Vec<Vec<u8>>, PhaseTimings), ClayError> {
+        encode_chunks_profiled(&self.encode_params(), data)
     }
 
-    /// Determine minimum sub-chunks needed to repair a lost node
+    /// Encode data into n chunks tagged with their node index and kind
+    ///
+    /// Same chunks as encode(), each wrapped in a [`Shard`] that records
+    /// its index and whether it is data or parity, so the chunks stay
+    /// self-describing once filtered, reordered or sent elsewhere.
     ///
     /// # Parameters
-    /// - `lost_node`: Index of the lost node (0 to n-1)
-    /// - `available`: Available node indices
+    /// - `data`: Raw data bytes to encode
     ///
     /// # Returns
-    /// Vector of (helper_node_idx, sub_chunk_indices) where sub_chunk_indices
-    /// is a vector of the specific sub-chunk indices needed from that helper.
-    /// The repair() function expects helper data to contain these sub-chunks
-    /// concatenated in the ORDER they appear in sub_chunk_indices.
-    pub fn minimum_to_repair(
-        &self,
-        lost_node: usize,
-        available: &[usize],
-    ) -> Result<Vec<(usize, Vec<usize>)>, ClayError> {
-        min_repair(&self.encode_params(), lost_node, available)
+    /// The n shards in node order, or error if the parity computation fails
+    pub fn encode_tagged(&self, data: &[u8]) -> Result<Vec<Shard>, ClayError> {
+        encode_tagged_chunks(&self.encode_params(), data)
     }
 
-    /// Repair a lost chunk using partial data from helper nodes
+    /// Chunk layout encode() would produce for `data_len` bytes
+    ///
+    /// Pure sizing math, so buffers can be planned without encoding.
     ///
     /// # Parameters
-    /// - `lost_node`: Index of the lost node (0 to n-1)
-    /// - `helper_data`: Map from helper node index to partial chunk data.
-    ///   Each helper's data must be the concatenation of sub-chunks at the
-    ///   indices returned by minimum_to_repair(), in that exact order.
-    /// - `chunk_size`: Full chunk size
+    /// - `data_len`: Length of the data to encode (may be 0)
     ///
     /// # Returns
-    /// The recovered full chunk, or error if repair fails
-    pub fn repair(
-        &self,
-        lost_node: usize,
-        helper_data: &HashMap<usize, Vec<u8>>,
-        chunk_size: usize,
-    ) -> Result<Vec<u8>, ClayError> {
-        repair_chunk(&self.encode_params(), lost_node, helper_data, chunk_size)
+    /// Chunk and sub-chunk sizes, padded length and padding
+    pub fn geometry(&self, data_len: usize) -> StripeGeometry {
+        stripe_geometry(&self.encode_params(), data_len)
     }
 
-    /// Calculate normalized repair bandwidth
+    /// Split a chunk into its α sub-chunks
     ///
-    /// This is the ratio of data downloaded for repair to the size of the
-    /// repaired chunk. For Clay codes, this is d / (k * q).
-    pub fn normalized_repair_bandwidth(&self) -> f64 {
-        (self.d as f64) / ((self.k as f64) * (self.d - self.k + 1) as f64)
+    /// # Parameters
+    /// - `chunk`: A full chunk; its length must be a non-zero multiple of α
+    ///
+    /// # Returns
+    /// Iterator over exactly α equal slices in layer order, or error if the
+    /// chunk length is misaligned
+    pub fn sub_chunks<'a>(
+        &self,
+        chunk: &'a [u8],
+    ) -> Result<impl Iterator<Item = &'a [u8]>, ClayError> {
+        let sub_chunk_size = self.sub_chunk_size_of(chunk)?;
+        Ok(chunk.chunks_exact(sub_chunk_size))
     }
-}
 
-/// Integer power function with overflow checking
-fn checked_pow(base: usize, exp: usize) -> Option<usize> {
-    let mut result: usize = 1;
-    let mut b = base;
-    let mut e = exp;
-    while e > 0 {
-        if e & 1 == 1 {
-            result = result.checked_mul(b)?;
-        }
-        e >>= 1;
-        if e > 0 {
-            b = b.checked_mul(b)?;
+    /// Get one sub-chunk of a chunk
+    ///
+    /// # Parameters
+    /// - `chunk`: A full chunk; its length must be a non-zero multiple of α
+    /// - `index`: Sub-chunk (layer) index, 0 to α-1
+    ///
+    /// # Returns
+    /// The sub-chunk, or error if the chunk length is misaligned or the
+    /// index is out of range
+    pub fn sub_chunk<'a>(&self, chunk: &'a [u8], index: usize) -> Result<&'a [u8], ClayError> {
+        let sub_chunk_size = self.sub_chunk_size_of(chunk)?;
+        if index >= self.sub_chunk_no {
+            return Err(ClayError::InvalidParameters(format!(
+                "Sub-chunk index {} out of range [0, {})",
+                index, self.sub_chunk_no
+            )));
         }
+        Ok(&chunk[index * sub_chunk_size..(index + 1) * sub_chunk_size])
     }
-    Some(result)
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn test_basic_encode_decode() {
-        let clay = ClayCode::new(4, 2, 5).unwrap();
-        let data = b"Test data for Clay codes - not empty!";
-        let chunks = clay.encode(data);
-        assert_eq!(chunks.len(), 6); // k + m = 6
 
-        // Decode with all chunks
-        let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
-        for (i, chunk) in chunks.iter().enumerate() {
-            available.insert(i, chunk.clone());
+    /// Copy one sub-chunk out of a chunk stored in `layout`
+    ///
+    /// In [`SubChunkLayout::LayerMajor`] a sub-chunk is spread across the
+    /// chunk, so it is gathered into a new buffer; what a helper sends for
+    /// repair_with_layout() is these sub-chunks concatenated.
+    ///
+    /// # Parameters
+    /// - `chunk`: A full chunk in `layout`
+    /// - `index`: Sub-chunk (layer) index, 0 to α-1
+    /// - `layout`: Layout the chunk is stored in
+    ///
+    /// # Returns
+    /// The sub-chunk bytes, or error if the chunk length is misaligned or
+    /// the index is out of range
+    pub fn sub_chunk_with_layout(
+        &self,
+        chunk: &[u8],
+        index: usize,
+        layout: SubChunkLayout,
+    ) -> Result<Vec<u8>, ClayError> {
+        match layout {
+            SubChunkLayout::NodeMajor => self.sub_chunk(chunk, index).map(<[u8]>::to_vec),
+            SubChunkLayout::LayerMajor => {
+                self.sub_chunk_size_of(chunk)?;
+                if index >= self.sub_chunk_no {
+                    return Err(ClayError::InvalidParameters(format!(
+                        "Sub-chunk index {} out of range [0, {})",
+                        index, self.sub_chunk_no
+                    )));
+                }
+                Ok(chunk.iter().skip(index).step_by(self.sub_chunk_no).copied().collect())
+            }
         }
-        let decoded = clay.decode(&available, &[]).unwrap();
+    }
 
-        // Check prefix matches (may have padding)
-        assert_eq!(&decoded[..data.len()], &data[..]);
+    fn sub_chunk_size_of(&self, chunk: &[u8]) -> Result<usize, ClayError> {
+        if chunk.is_empty() || chunk.len() % self.sub_chunk_no != 0 {
+            return Err(ClayError::InvalidChunkSize {
+                expected: self.sub_chunk_no,
+                actual: chunk.len(),
+            });
+        }
+        Ok(chunk.len() / self.sub_chunk_no)
     }
 
-    #[test]
-    fn test_decode_with_erasures() {
-        let clay = ClayCode::new(4, 2, 5).unwrap();
+    /// Encode data into n chunks of a fixed size
+    ///
+    /// The data is zero-padded to k * chunk_size, so every object encodes
+    /// to the same on-disk layout regardless of its length.
+    ///
+    /// # Parameters
+    /// - `data`: Raw data bytes to encode, at most k * chunk_size
+    /// - `chunk_size`: Bytes per chunk; a multiple of α of at least 2 * α
+    ///
+    /// # Returns
+    /// Vector of n chunks of exactly `chunk_size` bytes, or error if the
+    /// chunk size is misaligned or the data does not fit
+    pub fn encode_with_chunk_size(
+        &self,
+        data: &[u8],
+        chunk_size: usize,
+    ) -> Result<Vec<Vec<u8>>, ClayError> {
+        encode_fixed_chunks(&self.encode_params(), data, chunk_size)
+    }
+
+    /// Encode data into n chunks packed in the given sub-chunk layout
+    ///
+    /// [`SubChunkLayout::LayerMajor`] is for storage formats that interleave
+    /// the layers, so every byte range of a stored chunk covers all α
+    /// layers. Such chunks must be read back with decode_with_layout() and
+    /// repaired with repair_with_layout(), using the same layout.
+    ///
+    /// # Parameters
+    /// - `data`: Raw data bytes to encode
+    /// - `layout`: How to pack the sub-chunks of each chunk
+    ///
+    /// # Returns
+    /// Vector of n chunks in `layout`, or error if encoding fails
+    pub fn encode_with_layout(
+        &self,
+        data: &[u8],
+        layout: SubChunkLayout,
+    ) -> Result<Vec<Vec<u8>>, ClayError> {
+        let chunks = self.encode(data)?;
+        Ok(chunks.into_iter().map(|c| layout.arrange(c, self.sub_chunk_no)).collect())
+    }
+
+    /// Encode data into preallocated output buffers
+    ///
+    /// Writes the same n chunks as encode() straight into the caller's
+    /// buffers; size them with geometry(). Only the working buffers of the
+    /// parity computation are allocated; encode_into_with() takes those
+    /// from an [`EncodeWorkspace`] instead.
+    ///
+    /// # Parameters
+    /// - `data`: Raw data bytes to encode
+    /// - `out`: Exactly n buffers of `geometry(data.len()).chunk_size` bytes
+    ///
+    /// # Returns
+    /// Ok once every buffer is written, or InvalidParameters if the buffer
+    /// count or a buffer length is wrong
+    pub fn encode_into(&self, data: &[u8], out: &mut [&mut [u8]]) -> Result<(), ClayError> {
+        encode_into_buffers(&self.encode_params(), data, out)
+    }
+
+    /// Encode data into preallocated output buffers with a reusable workspace
+    ///
+    /// Same chunks as encode_with(), written straight into the caller's
+    /// buffers. Together with the workspace this makes a fixed-size stripe
+    /// encode that allocates nothing once the workspace has been used.
+    ///
+    /// # Parameters
+    /// - `ws`: Workspace from `EncodeWorkspace::new` for this code
+    /// - `data`: At most `ws.stripe_size()` bytes
+    /// - `out`: Exactly n buffers of `ws.chunk_size()` bytes
+    ///
+    /// # Returns
+    /// Ok once every buffer is written, or InvalidParameters if the
+    /// workspace belongs to another code, the data does not fit or the
+    /// buffer count or a buffer length is wrong
+    pub fn encode_into_with(
+        &self,
+        ws: &mut EncodeWorkspace,
+        data: &[u8],
+        out: &mut [&mut [u8]],
+    ) -> Result<(), ClayError> {
+        encode_into_buffers_with(&self.encode_params(), ws, data, out)
+    }
+
+    /// Encode data into the buffers of a reusable workspace
+    ///
+    /// Same chunks as encode() for data of the workspace's stripe size;
+    /// shorter data is zero-padded to it. Nothing is allocated once the
+    /// workspace has been used.
+    ///
+    /// # Parameters
+    /// - `ws`: Workspace from `EncodeWorkspace::new` for this code
+    /// - `data`: At most `ws.stripe_size()` bytes
+    ///
+    /// # Returns
+    /// The n chunks, borrowed from the workspace, or error if the workspace
+    /// belongs to another code or the data does not fit
+    pub fn encode_with<'w>(
+        &self,
+        ws: &'w mut EncodeWorkspace,
+        data: &[u8],
+    ) -> Result<&'w [Vec<u8>], ClayError> {
+        encode_into_workspace(&self.encode_params(), ws, data)
+    }
+
+    /// Encode many blocks with one chunk size, sharing a single workspace
+    ///
+    /// Every block is encoded with encode_with() into the same
+    /// [`EncodeWorkspace`], so sizing and working buffers are set up once
+    /// for the whole batch. All blocks share `chunk_size`: each is
+    /// zero-padded to k * chunk_size and must not exceed it.
+    ///
+    /// # Parameters
+    /// - `blocks`: Data blocks, each at most k * chunk_size bytes
+    /// - `chunk_size`: Bytes per chunk; a multiple of α of at least 2 * α
+    ///
+    /// # Returns
+    /// The n chunks of each block, in block order, or error if the chunk
+    /// size is misaligned or a block does not fit
+    pub fn encode_batch(
+        &self,
+        blocks: &[&[u8]],
+        chunk_size: usize,
+    ) -> Result<Vec<Vec<Vec<u8>>>, ClayError> {
+        let params = self.encode_params();
+        let mut ws = EncodeWorkspace::new(self, chunk_size)?;
+        blocks
+            .iter()
+            .map(|block| encode_into_workspace(&params, &mut ws, block).map(<[Vec<u8>]>::to_vec))
+            .collect()
+    }
+
+    /// Encode data into n chunks, each followed by per-sub-chunk checksums
+    ///
+    /// Each chunk gets a trailer of α little-endian CRC32C values, one per
+    /// sub-chunk, so that repair_with_checksums() can check every helper
+    /// sub-chunk before using it.
+    ///
+    /// # Parameters
+    /// - `data`: Raw data bytes to encode
+    ///
+    /// # Returns
+    /// Vector of n chunks of chunk_size + α * CHECKSUM_SIZE bytes
+    pub fn encode_with_checksums(&self, data: &[u8]) -> Result<Vec<Vec<u8>>, ClayError> {
+        encode_checksummed(&self.encode_params(), data)
+    }
+
+    /// Encode data into n chunks, recording its exact length
+    ///
+    /// Prepends an 8-byte little-endian length header to the first data
+    /// chunk so that `decode_exact` can return the original bytes without
+    /// the caller tracking the data length separately. The header counts
+    /// towards chunk sizing, so chunks may be larger than with `encode`.
+    ///
+    /// # Parameters
+    /// - `data`: Raw data bytes to encode
+    ///
+    /// # Returns
+    /// Vector of n chunks, each containing α sub-chunks
+    pub fn encode_with_length(&self, data: &[u8]) -> Result<Vec<Vec<u8>>, ClayError> {
+        encode_chunks_with_length(&self.encode_params(), data)
+    }
+
+    /// Split an object of any size into independently coded stripes
+    ///
+    /// Each stripe holds k * chunk_size bytes of the object and the last is
+    /// zero-padded, so there are ceil(data.len() / (k * chunk_size))
+    /// stripes. The returned manifest records the exact length for
+    /// decode_object().
+    ///
+    /// # Parameters
+    /// - `data`: The whole object
+    /// - `chunk_size`: Bytes per chunk; a multiple of α of at least 2 * α
+    ///
+    /// # Returns
+    /// The manifest and the stripes in order, each n chunks of exactly
+    /// `chunk_size` bytes, or error if the chunk size is misaligned
+    pub fn encode_object(
+        &self,
+        data: &[u8],
+        chunk_size: usize,
+    ) -> Result<EncodedObject, ClayError> {
+        encode_striped_object(&self.encode_params(), data, chunk_size)
+    }
+
+    /// Encode data into n chunks, filling the padding as `pad` says
+    ///
+    /// `PaddingScheme::Pkcs7` lets `decode_unpad` return the exact data
+    /// without a length header, but only when the stripe needs at most 255
+    /// bytes of padding; see `encode_with_length` for the general case.
+    ///
+    /// # Parameters
+    /// - `data`: Raw data bytes to encode
+    /// - `pad`: What to fill the stripe with past the end of the data
+    ///
+    /// # Returns
+    /// Vector of n chunks, each containing α sub-chunks, or error if
+    /// PKCS#7 padding would exceed 255 bytes
+    pub fn encode_with_padding(
+        &self,
+        data: &[u8],
+        pad: PaddingScheme,
+    ) -> Result<Vec<Vec<u8>>, ClayError> {
+        encode_chunks_with_padding(&self.encode_params(), data, pad)
+    }
+
+    /// Encode k pre-split data chunks into n chunks
+    ///
+    /// For callers that already hold the data as one buffer per data node.
+    /// Unlike encode(), the data is not padded or re-split.
+    ///
+    /// # Parameters
+    /// - `data_chunks`: Exactly k chunks of equal length, divisible by α
+    ///
+    /// # Returns
+    /// All n chunks: the data chunks unchanged, then the m parity chunks
+    pub fn encode_chunks(&self, data_chunks: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, ClayError> {
+        encode_split_chunks(&self.encode_params(), data_chunks)
+    }
+
+    /// Compute the m parity chunks for k pre-split data chunks
+    ///
+    /// Same as encode_chunks(), for stores that have already written the
+    /// data chunks and only need the parities.
+    ///
+    /// # Parameters
+    /// - `data_chunks`: Exactly k chunks of equal length, divisible by α
+    ///
+    /// # Returns
+    /// The m parity chunks in node order (nodes k to n-1)
+    pub fn compute_parity(&self, data_chunks: &[Vec<u8>]) -> Result<Vec<Vec<u8>>, ClayError> {
+        encode_parity(&self.encode_params(), data_chunks)
+    }
+
+    /// Rebuild lost parity chunks from the intact data chunks
+    ///
+    /// Restores durability after parity loss without a decode and full
+    /// re-encode; the data chunks are only read.
+    ///
+    /// # Parameters
+    /// - `data_chunks`: Map from data node index to chunk, all k present
+    /// - `lost_parity`: Parity node indices to rebuild (each k to n-1)
+    ///
+    /// # Returns
+    /// Map from each requested parity index to its chunk, or error if a
+    /// data chunk is missing or an index is not a parity node
+    pub fn regenerate_parity(
+        &self,
+        data_chunks: &HashMap<usize, Vec<u8>>,
+        lost_parity: &[usize],
+    ) -> Result<HashMap<usize, Vec<u8>>, ClayError> {
+        regenerate_parity_chunks(&self.encode_params(), data_chunks, lost_parity)
+    }
+
+    /// Decode data from available chunks
+    ///
+    /// Nodes missing from `available` count as erased, so passing whatever
+    /// chunks survived with empty `erasures` just works as long as at least
+    /// k are present.
+    ///
+    /// # Parameters
+    /// - `available`: Map from chunk index to chunk data
+    /// - `erasures`: Erased chunk indices, in addition to those not provided
+    ///
+    /// # Returns
+    /// Recovered original data, or error if decoding fails
+    pub fn decode(
+        &self,
+        available: &HashMap<usize, Vec<u8>>,
+        erasures: &[usize],
+    ) -> Result<Vec<u8>, ClayError> {
+        decode_chunks(&self.encode_params(), available, erasures)
+    }
+
+    /// Decode data from available chunks, timing the transform and RS phases
+    ///
+    /// Same as [`ClayCode::decode`]. With only parity erased no layer is
+    /// decoded and every counter is zero.
+    ///
+    /// # Parameters
+    /// - `available`: Map from chunk index to chunk data
+    /// - `erasures`: Erased chunk indices, in addition to those not provided
+    ///
+    /// # Returns
+    /// Recovered original data and the phase timings, or error if decoding
+    /// fails
+    #[cfg(feature = "profile")]
+    pub fn decode_profiled(
+        &self,
+        available: &HashMap<usize, Vec<u8>>,
+        erasures: &[usize],
+    ) -> Result<(Vec<u8>, PhaseTimings), ClayError> {
+        decode_chunks_profiled(&self.encode_params(), available, erasures)
+    }
+
+    /// Decode data and report which chunks were read
+    ///
+    /// For checking that a fetch brought in only what the decode needed:
+    /// with only parity lost just the k data chunks are read, otherwise
+    /// every available chunk is.
+    ///
+    /// # Parameters
+    /// - `available`: Map from chunk index to chunk data
+    /// - `erasures`: Erased chunk indices, in addition to those not provided
+    ///
+    /// # Returns
+    /// Recovered original data and the ascending indices of the chunks
+    /// read, or error if decoding fails
+    pub fn decode_with_report(
+        &self,
+        available: &HashMap<usize, Vec<u8>>,
+        erasures: &[usize],
+    ) -> Result<(Vec<u8>, Vec<usize>), ClayError> {
+        decode_chunks_with_report(&self.encode_params(), available, erasures)
+    }
+
+    /// Decode data from chunks stored in the given sub-chunk layout
+    ///
+    /// # Parameters
+    /// - `available`: Map from chunk index to chunk data in `layout`
+    /// - `erasures`: Erased chunk indices, in addition to those not provided
+    /// - `layout`: Layout the chunks were encoded with
+    ///
+    /// # Returns
+    /// Recovered original data, or error if decoding fails
+    pub fn decode_with_layout(
+        &self,
+        available: &HashMap<usize, Vec<u8>>,
+        erasures: &[usize],
+        layout: SubChunkLayout,
+    ) -> Result<Vec<u8>, ClayError> {
+        if layout == SubChunkLayout::NodeMajor {
+            return self.decode(available, erasures);
+        }
+        let node_major: HashMap<usize, Vec<u8>> = available
+            .iter()
+            .map(|(&idx, chunk)| {
+                self.sub_chunk_size_of(chunk)?;
+                Ok((idx, layout.to_node_major(chunk, self.sub_chunk_no)))
+            })
+            .collect::<Result<_, ClayError>>()?;
+        decode_chunks(&self.encode_params(), &node_major, erasures)
+    }
+
+    /// Start a decode that accepts chunks one at a time, in any order
+    ///
+    /// # Returns
+    /// An empty [`DecodeSession`] for this code
+    pub fn decode_session(&self) -> DecodeSession {
+        DecodeSession::new(self)
+    }
+
+    /// Decode data when all n chunks are present
+    ///
+    /// Shorthand for decode() with every chunk available and no erasures,
+    /// without building a map; the data chunks are simply concatenated.
+    ///
+    /// # Parameters
+    /// - `chunks`: Exactly n chunks in node order, e.g. as encode() returns
+    ///
+    /// # Returns
+    /// Decoded data (may include padding) or error if the chunks are malformed
+    pub fn decode_all(&self, chunks: &[Vec<u8>]) -> Result<Vec<u8>, ClayError> {
+        decode_all_chunks(&self.encode_params(), chunks)
+    }
+
+    /// Decode data, requiring every node to be either available or erased
+    ///
+    /// Same as decode(), but errors unless `available` holds exactly the
+    /// n - erasures.len() nodes not listed in `erasures`.
+    ///
+    /// # Parameters
+    /// - `available`: Map from chunk index to chunk data
+    /// - `erasures`: Set of erased chunk indices
+    ///
+    /// # Returns
+    /// Recovered original data, or error if decoding fails
+    pub fn decode_strict(
+        &self,
+        available: &HashMap<usize, Vec<u8>>,
+        erasures: &[usize],
+    ) -> Result<Vec<u8>, ClayError> {
+        decode_chunks_strict(&self.encode_params(), available, erasures)
+    }
+
+    /// Decode data from available chunks, streaming it to a writer
+    ///
+    /// Same as decode(), but each recovered data chunk is written to `out`
+    /// instead of being collected into one vector.
+    ///
+    /// # Parameters
+    /// - `available`: Map from chunk index to chunk data
+    /// - `erasures`: Set of erased chunk indices
+    /// - `out`: Sink for the decoded data (may include padding)
+    ///
+    /// # Returns
+    /// Number of bytes written, or error if decoding or writing fails
+    #[cfg(feature = "std")]
+    pub fn decode_to_writer(
+        &self,
+        available: &HashMap<usize, Vec<u8>>,
+        erasures: &[usize],
+        out: &mut impl Write,
+    ) -> Result<usize, ClayError> {
+        decode_chunks_to_writer(&self.encode_params(), available, erasures, out)
+    }
+
+    /// Recover only some chunks instead of all k data chunks
+    ///
+    /// Useful for partial reads. When exactly one wanted chunk is erased it
+    /// is rebuilt with the low-bandwidth repair if enough helpers are
+    /// available, falling back to a full decode otherwise. Nodes missing
+    /// from `available` count as erased.
+    ///
+    /// # Parameters
+    /// - `available`: Map from chunk index to chunk data
+    /// - `erasures`: Erased chunk indices, in addition to those not provided
+    /// - `wanted`: Chunk indices to return (each 0 to n-1)
+    ///
+    /// # Returns
+    /// Map from each wanted index to its full chunk, or error if decoding fails
+    pub fn decode_nodes(
+        &self,
+        available: &HashMap<usize, Vec<u8>>,
+        erasures: &[usize],
+        wanted: &[usize],
+    ) -> Result<HashMap<usize, Vec<u8>>, ClayError> {
+        decode_chunk_subset(&self.encode_params(), available, erasures, wanted)
+    }
+
+    /// Rebuild the chunks of the erased nodes to heal a stripe
+    ///
+    /// Unlike decode(), which returns the original data, this returns the
+    /// chunk bytes of each erased node (data or parity) so they can be
+    /// written back to fresh storage. Nodes missing from `available` count
+    /// as erased.
+    ///
+    /// # Parameters
+    /// - `available`: Map from chunk index to chunk data
+    /// - `erasures`: Erased chunk indices, in addition to those not provided
+    ///
+    /// # Returns
+    /// Map from each erased index (0 to n-1) to its full chunk, or error if
+    /// decoding fails
+    pub fn reconstruct_chunks(
+        &self,
+        available: &HashMap<usize, Vec<u8>>,
+        erasures: &[usize],
+    ) -> Result<HashMap<usize, Vec<u8>>, ClayError> {
+        reconstruct_erased(&self.encode_params(), available, erasures)
+    }
+
+    /// Decode the data and rebuild the erased chunks with one layered pass
+    ///
+    /// Same result as calling decode() and reconstruct_chunks(), which
+    /// would each run the layered decode, at the cost of one.
+    ///
+    /// # Parameters
+    /// - `available`: Map from chunk index to chunk data
+    /// - `erasures`: Erased chunk indices, in addition to those not provided
+    ///
+    /// # Returns
+    /// The decoded data (may include padding) and a map from each erased
+    /// index to its full chunk, or error if decoding fails
+    pub fn decode_and_heal(
+        &self,
+        available: &HashMap<usize, Vec<u8>>,
+        erasures: &[usize],
+    ) -> Result<HealedStripe, ClayError> {
+        decode_and_heal_chunks(&self.encode_params(), available, erasures)
+    }
+
+    /// Read a byte range of the data without decoding the whole stripe
+    ///
+    /// Only the layers holding the range in erased data chunks, and the
+    /// layers those depend on, are decoded. A range inside available data
+    /// chunks is copied directly. Nodes missing from `available` count as
+    /// erased.
+    ///
+    /// # Parameters
+    /// - `available`: Map from chunk index to chunk data
+    /// - `erasures`: Erased chunk indices, in addition to those not provided
+    /// - `byte_range`: Range of the decoded data (as decode() returns it)
+    ///
+    /// # Returns
+    /// The requested bytes, or error if the range is out of bounds or
+    /// decoding fails
+    pub fn read_range(
+        &self,
+        available: &HashMap<usize, Vec<u8>>,
+        erasures: &[usize],
+        byte_range: Range<usize>,
+    ) -> Result<Vec<u8>, ClayError> {
+        read_data_range(&self.encode_params(), available, erasures, byte_range)
+    }
+
+    /// Decode chunks lent as borrowed slices
+    ///
+    /// Same as decode(), for callers whose chunks live in a larger buffer
+    /// and can only be lent as `&[u8]`. Nothing is cloned up front; only the
+    /// chunks the decoder works on are copied.
+    ///
+    /// # Parameters
+    /// - `available`: Map from chunk index to borrowed chunk data
+    /// - `erasures`: Set of erased chunk indices
+    ///
+    /// # Returns
+    /// Decoded data (may include padding) or error if decoding fails
+    pub fn decode_refs(
+        &self,
+        available: &HashMap<usize, &[u8]>,
+        erasures: &[usize],
+    ) -> Result<Vec<u8>, ClayError> {
+        decode_chunk_refs(&self.encode_params(), available, erasures)
+    }
+
+    /// Decode chunks given as a list of (index, chunk) pairs
+    ///
+    /// Same as decode(), for ingest pipelines that assemble chunk lists
+    /// from several sources. Collecting such a list into a map would keep
+    /// whichever copy of a repeated index came last; here a repeated index
+    /// is an error.
+    ///
+    /// # Parameters
+    /// - `pairs`: (chunk index, chunk data) pairs
+    /// - `erasures`: Erased chunk indices, in addition to those not provided
+    ///
+    /// # Returns
+    /// Decoded data (may include padding), or error naming the first
+    /// repeated index, or if decoding fails
+    pub fn decode_from_pairs(
+        &self,
+        pairs: &[(usize, Vec<u8>)],
+        erasures: &[usize],
+    ) -> Result<Vec<u8>, ClayError> {
+        decode_chunk_pairs(&self.encode_params(), pairs, erasures)
+    }
+
+    /// Decode chunks, taking ownership of them to avoid copying
+    ///
+    /// Same as decode(), but the available chunks are moved into the
+    /// decoder instead of cloned, halving peak memory for large stripes.
+    ///
+    /// # Parameters
+    /// - `available`: Map from chunk index to chunk data (consumed)
+    /// - `erasures`: Set of erased chunk indices
+    ///
+    /// # Returns
+    /// Decoded data (may include padding) or error if decoding fails
+    pub fn decode_owned(
+        &self,
+        available: HashMap<usize, Vec<u8>>,
+        erasures: &[usize],
+    ) -> Result<Vec<u8>, ClayError> {
+        decode_owned_chunks(&self.encode_params(), available, erasures)
+    }
+
+    /// Decode chunks produced by `encode_with_length` back to the exact original data
+    ///
+    /// # Parameters
+    /// - `available`: Map from chunk index to chunk data
+    /// - `erasures`: Set of erased chunk indices
+    ///
+    /// # Returns
+    /// The original data with padding and length header removed, or error if
+    /// decoding fails or the header is inconsistent with the decoded size
+    pub fn decode_exact(
+        &self,
+        available: &HashMap<usize, Vec<u8>>,
+        erasures: &[usize],
+    ) -> Result<Vec<u8>, ClayError> {
+        decode_exact_chunks(&self.encode_params(), available, erasures)
+    }
+
+    /// Reassemble an object encoded with encode_object()
+    ///
+    /// Every stripe is decoded from its own survivors, so different stripes
+    /// may have lost different nodes.
+    ///
+    /// # Parameters
+    /// - `manifest`: The manifest encode_object() returned
+    /// - `stripes`: For each stripe in order, a map from chunk index to
+    ///   chunk data holding at least k of its chunks
+    ///
+    /// # Returns
+    /// The original object, exactly as long as it was, or error if a stripe
+    /// cannot be decoded or the stripes do not match the manifest
+    pub fn decode_object(
+        &self,
+        manifest: &ObjectManifest,
+        stripes: &[HashMap<usize, Vec<u8>>],
+    ) -> Result<Vec<u8>, ClayError> {
+        decode_striped_object(&self.encode_params(), manifest, stripes)
+    }
+
+    /// Decode chunks encoded with `PaddingScheme::Pkcs7` back to the exact data
+    ///
+    /// # Parameters
+    /// - `available`: Map from chunk index to chunk data
+    /// - `erasures`: Erased chunk indices, in addition to those not provided
+    ///
+    /// # Returns
+    /// The original data with the padding removed, or error if decoding
+    /// fails or the data does not end in valid PKCS#7 padding
+    pub fn decode_unpad(
+        &self,
+        available: &HashMap<usize, Vec<u8>>,
+        erasures: &[usize],
+    ) -> Result<Vec<u8>, ClayError> {
+        decode_unpadded_chunks(&self.encode_params(), available, erasures)
+    }
+
+    /// Decode data from whichever shards of an n-slot array survived
+    ///
+    /// The most forgiving entry point for a caller that just collected
+    /// what it could: `None` marks a missing node, and any k or more
+    /// present shards will do. The array is only read; see decode_shards()
+    /// to fill in the missing chunks instead.
+    ///
+    /// # Parameters
+    /// - `shards`: Exactly n shards in node order (k data then m parity)
+    ///
+    /// # Returns
+    /// Recovered original data, or error if fewer than k shards are
+    /// present or decoding fails
+    pub fn decode_lenient(&self, shards: &[Option<Vec<u8>>]) -> Result<Vec<u8>, ClayError> {
+        decode_shard_survivors(&self.encode_params(), shards)
+    }
+
+    /// Reconstruct erased chunks in place from an n-slot shard array
+    ///
+    /// Mirrors `ReedSolomon::reconstruct`: `None` marks an erased node, and
+    /// on success every erased slot is filled with its recovered chunk.
+    ///
+    /// # Parameters
+    /// - `shards`: Exactly n shards in node order (k data then m parity)
+    ///
+    /// # Returns
+    /// Ok once all erased slots are filled, or error if decoding fails
+    pub fn decode_shards(&self, shards: &mut [Option<Vec<u8>>]) -> Result<(), ClayError> {
+        decode_shard_array(&self.encode_params(), shards)
+    }
+
+    /// Check that a full stripe's parity matches its data
+    ///
+    /// Re-derives the uncoupled layers from the chunks and re-encodes each
+    /// layer's parity, without reconstructing anything. Detects silent
+    /// corruption that erasure decoding alone would miss.
+    ///
+    /// # Parameters
+    /// - `chunks`: All n chunks in node order
+    ///
+    /// # Returns
+    /// Ok(true) if consistent, Ok(false) on mismatch, or error if the number
+    /// or sizes of the chunks are wrong
+    pub fn verify(&self, chunks: &[Vec<u8>]) -> Result<bool, ClayError> {
+        verify_stripe(&self.encode_params(), chunks)
+    }
+
+    /// Recover data from a full stripe, locating corrupted chunks
+    ///
+    /// Unlike decode(), the corrupted chunks need not be known: up to
+    /// floor(m/2) of them are located and corrected. Needs m >= 2.
+    ///
+    /// Costly: every candidate set of corrupted chunks is tried with a full
+    /// decode, up to C(n, 1) + ... + C(n, e) of them for e corrupted
+    /// chunks. The search stops at [`MAX_CORRECTION_CANDIDATES`], which
+    /// lowers the correctable count on wide codes.
+    ///
+    /// # Parameters
+    /// - `chunks`: All n chunks in node order
+    ///
+    /// # Returns
+    /// The recovered data (padded, as from decode()) and the sorted indices
+    /// of the corrupted chunks
+    pub fn decode_with_correction(
+        &self,
+        chunks: &[Vec<u8>],
+    ) -> Result<(Vec<u8>, Vec<usize>), ClayError> {
+        decode_corrected(&self.encode_params(), chunks)
+    }
+
+    /// Intersection score of every layer for an erasure pattern
+    ///
+    /// A layer's score counts the erased nodes that are unpaired in it;
+    /// decoding handles layers in order of increasing score.
+    ///
+    /// # Parameters
+    /// - `erasures`: Erased chunk indices (each 0 to n-1)
+    ///
+    /// # Returns
+    /// One score per layer, indexed by layer (α entries), or
+    /// InvalidParameters if an erasure index is out of range or repeated
+    pub fn layer_scores(&self, erasures: &[usize]) -> Result<Vec<usize>, ClayError> {
+        Ok(intersection_scores(&self.encode_params(), &self.internal_erasures(erasures)?))
+    }
+
+    /// Order in which decoding processes the layers for an erasure pattern
+    ///
+    /// # Parameters
+    /// - `erasures`: Erased chunk indices (each 0 to n-1)
+    ///
+    /// # Returns
+    /// All α layer indices, by increasing intersection score and then
+    /// index, or InvalidParameters if an erasure index is out of range or
+    /// repeated
+    pub fn decoding_order(&self, erasures: &[usize]) -> Result<Vec<usize>, ClayError> {
+        Ok(layer_decoding_order(&self.encode_params(), &self.internal_erasures(erasures)?))
+    }
+
+    /// Map a node to its (x, y) position in the q × t grid
+    ///
+    /// Data nodes keep their index as grid position and parity nodes are
+    /// shifted past the nu shortened positions, so x = i mod q and
+    /// y = i / q for the internal index i.
+    ///
+    /// # Parameters
+    /// - `node`: Chunk index (0 to n-1)
+    ///
+    /// # Returns
+    /// (x, y) with x < q and y < t, or error if the node is out of range
+    pub fn node_to_xy(&self, node: usize) -> Result<(usize, usize), ClayError> {
+        if node >= self.n {
+            return Err(ClayError::InvalidParameters(format!(
+                "Node index {} out of range [0, {})",
+                node, self.n
+            )));
+        }
+        let internal = coords::to_internal(node, self.k, self.nu);
+        Ok((internal % self.q, internal / self.q))
+    }
+
+    /// Map an (x, y) grid position back to its node
+    ///
+    /// # Parameters
+    /// - `x`: Position within the y-section (0 to q-1)
+    /// - `y`: y-section (0 to t-1)
+    ///
+    /// # Returns
+    /// The chunk index at (x, y), or error if a coordinate is out of range
+    /// or the position belongs to a shortened node, which is never stored
+    pub fn xy_to_node(&self, x: usize, y: usize) -> Result<usize, ClayError> {
+        if x >= self.q || y >= self.t {
+            return Err(ClayError::InvalidParameters(format!(
+                "Position ({}, {}) out of range [0, {}) x [0, {})",
+                x, y, self.q, self.t
+            )));
+        }
+        coords::to_external(y * self.q + x, self.k, self.nu).ok_or_else(|| {
+            ClayError::InvalidParameters(format!("Position ({}, {}) holds a shortened node", x, y))
+        })
+    }
+
+    /// Digits of a layer index in base q, one per y-section
+    ///
+    /// Entry y is the x of the unpaired vertex of y-section y in layer z;
+    /// entry 0 is the most significant digit.
+    ///
+    /// # Parameters
+    /// - `z`: Layer index (0 to α-1)
+    ///
+    /// # Returns
+    /// The t digits of z, or error if z is out of range
+    pub fn plane_vector(&self, z: usize) -> Result<&[usize], ClayError> {
+        if z >= self.sub_chunk_no {
+            return Err(ClayError::InvalidParameters(format!(
+                "Layer index {} out of range [0, {})",
+                z, self.sub_chunk_no
+            )));
+        }
+        Ok(&self.planes[z * self.t..(z + 1) * self.t])
+    }
+
+    /// Companion layer of every vertex, flattened
+    ///
+    /// Entry `(y * q + x) * α + z` is the layer z_sw holding the companion
+    /// of vertex (x, y, z): z with its digit y set to x. Unpaired vertices
+    /// (x = z_y) are their own companion, so the entry is z. The run of α
+    /// entries starting at `(y * q + x) * α` belongs to internal node
+    /// y * q + x. Computed once when the code is built; decode and repair
+    /// look companions up here.
+    ///
+    /// # Returns
+    /// The t * q * α companion layers
+    pub fn companion_table(&self) -> &[usize] {
+        &self.companions
+    }
+
+    /// Check whether vertex (x, y, z) is unpaired (red)
+    ///
+    /// An unpaired vertex has x equal to digit y of z, so its coupled and
+    /// uncoupled values coincide and it has no companion.
+    ///
+    /// # Parameters
+    /// - `x`: Position within the y-section (0 to q-1)
+    /// - `y`: y-section (0 to t-1)
+    /// - `z`: Layer index (0 to α-1)
+    ///
+    /// # Returns
+    /// Whether the vertex is unpaired, or error if a coordinate is out of
+    /// range
+    pub fn is_unpaired(&self, x: usize, y: usize, z: usize) -> Result<bool, ClayError> {
+        if x >= self.q || y >= self.t {
+            return Err(ClayError::InvalidParameters(format!(
+                "Position ({}, {}) out of range [0, {}) x [0, {})",
+                x, y, self.q, self.t
+            )));
+        }
+        Ok(self.plane_vector(z)?[y] == x)
+    }
+
+    /// Check whether decode can succeed from a set of surviving nodes
+    ///
+    /// The code is MDS, so any k distinct survivors suffice. Duplicates
+    /// count once.
+    ///
+    /// # Parameters
+    /// - `available`: Surviving chunk indices (each 0 to n-1)
+    ///
+    /// # Returns
+    /// true if `available` holds at least k distinct indices, false if it
+    /// holds fewer or any index is n or greater
+    pub fn is_recoverable(&self, available: &[usize]) -> bool {
+        if available.iter().any(|&i| i >= self.n) {
+            return false;
+        }
+        let distinct: BTreeSet<usize> = available.iter().copied().collect();
+        distinct.len() >= self.k
+    }
+
+    /// Map external erasure indices to internal node indices
+    ///
+    /// # Returns
+    /// The internal indices, or InvalidParameters if an index is out of
+    /// range or appears more than once
+    fn internal_erasures(&self, erasures: &[usize]) -> Result<BTreeSet<usize>, ClayError> {
+        let mut internal = BTreeSet::new();
+        for &i in erasures {
+            if i >= self.n {
+                return Err(ClayError::InvalidParameters(format!(
+                    "Erasure index {} out of range [0, {})",
+                    i, self.n
+                )));
+            }
+            if !internal.insert(coords::to_internal(i, self.k, self.nu)) {
+                return Err(ClayError::InvalidParameters(format!(
+                    "Erasure index {} appears more than once",
+                    i
+                )));
+            }
+        }
+        Ok(internal)
+    }
+
+    /// Determine minimum sub-chunks needed to repair a lost node
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `available`: Available node indices
+    ///
+    /// # Returns
+    /// Vector of (helper_node_idx, sub_chunk_indices) where sub_chunk_indices
+    /// is a vector of the specific sub-chunk indices needed from that helper.
+    /// The repair() function expects helper data to contain these sub-chunks
+    /// concatenated in the ORDER they appear in sub_chunk_indices. Repeated
+    /// available indices are ignored; an index out of range or equal to
+    /// `lost_node` is an error.
+    pub fn minimum_to_repair(
+        &self,
+        lost_node: usize,
+        available: &[usize],
+    ) -> Result<Vec<(usize, Vec<usize>)>, ClayError> {
+        min_repair(&self.encode_params(), lost_node, available)
+    }
+
+    /// Determine minimum sub-chunks needed to repair a lost node, preferring
+    /// cheap helpers
+    ///
+    /// The lost node's y-section partners are required and always chosen;
+    /// the other helpers are the available nodes with the lowest `cost`,
+    /// ties broken by node index.
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `available`: Available node indices
+    /// - `cost`: Cost of reading from a node (e.g. 0 in the same rack)
+    ///
+    /// # Returns
+    /// Vector of (helper_node_idx, sub_chunk_indices) as minimum_to_repair()
+    /// returns it, y-section partners first
+    pub fn minimum_to_repair_with_topology(
+        &self,
+        lost_node: usize,
+        available: &[usize],
+        cost: &dyn Fn(usize) -> u32,
+    ) -> Result<Vec<(usize, Vec<usize>)>, ClayError> {
+        min_repair_with_topology(&self.encode_params(), lost_node, available, cost)
+    }
+
+    /// Determine minimum sub-chunks needed to repair a lost node, reading
+    /// from preferred nodes first
+    ///
+    /// The lost node's y-section partners are required and always chosen;
+    /// the other helpers come from the available nodes in `prefer`, in
+    /// order, then from the rest by index. Useful when some nodes, often
+    /// the parity ones, sit on cheaper or faster storage.
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `available`: Available node indices
+    /// - `prefer`: Nodes to read from first; unavailable ones are ignored
+    ///
+    /// # Returns
+    /// Vector of (helper_node_idx, sub_chunk_indices) as minimum_to_repair()
+    /// returns it, y-section partners first
+    pub fn minimum_to_repair_prefer(
+        &self,
+        lost_node: usize,
+        available: &[usize],
+        prefer: &[usize],
+    ) -> Result<Vec<(usize, Vec<usize>)>, ClayError> {
+        min_repair_prefer(&self.encode_params(), lost_node, available, prefer)
+    }
+
+    /// Determine minimum sub-chunks needed to repair a lost node, choosing
+    /// the helpers that leave the least decoding work
+    ///
+    /// Bandwidth is the same for any d helpers, and so is the RS work per
+    /// layer. The coupling work is not: it grows with the number of helpers
+    /// sharing a y-section with an aloof node, so this leaves whole
+    /// y-sections out where the survivors allow it.
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `available`: Available node indices
+    ///
+    /// # Returns
+    /// Vector of (helper_node_idx, sub_chunk_indices) as minimum_to_repair()
+    /// returns it, y-section partners first
+    pub fn minimum_to_repair_min_cpu(
+        &self,
+        lost_node: usize,
+        available: &[usize],
+    ) -> Result<Vec<(usize, Vec<usize>)>, ClayError> {
+        min_repair_min_cpu(&self.encode_params(), lost_node, available)
+    }
+
+    /// Determine the sub-chunks needed to repair a lost node with exactly d helpers
+    ///
+    /// β = α/q with q = d - k + 1: a larger d reads from more helpers but
+    /// less from each, for a normalized bandwidth of d / (k·q). The chunks'
+    /// layer structure is fixed by q, so d must be the d this code was
+    /// built with; other values are rejected.
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `available`: Available node indices
+    /// - `d`: Helper count; must satisfy k < d <= available.len() and match q
+    ///
+    /// # Returns
+    /// Same as minimum_to_repair(), or error if d is invalid
+    pub fn minimum_to_repair_with_d(
+        &self,
+        lost_node: usize,
+        available: &[usize],
+        d: usize,
+    ) -> Result<Vec<(usize, Vec<usize>)>, ClayError> {
+        min_repair_with_d(&self.encode_params(), lost_node, available, d)
+    }
+
+    /// Repair a lost chunk with exactly d helpers
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `helper_data`: Map from helper node index to partial chunk data
+    /// - `chunk_size`: Full chunk size
+    /// - `d`: Helper count; must satisfy k < d <= helper_data.len() and match q
+    ///
+    /// # Returns
+    /// The recovered full chunk, or error if d is invalid or repair fails
+    pub fn repair_with_d(
+        &self,
+        lost_node: usize,
+        helper_data: &HashMap<usize, Vec<u8>>,
+        chunk_size: usize,
+        d: usize,
+    ) -> Result<Vec<u8>, ClayError> {
+        repair_chunk_with_d(&self.encode_params(), lost_node, helper_data, chunk_size, d)
+    }
+
+    /// Get the sub-chunk indices each helper supplies to repair a lost node
+    ///
+    /// These are the layers where the lost node is unpaired; there are
+    /// `beta` of them, in ascending order.
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    ///
+    /// # Returns
+    /// Sub-chunk indices, the same for every helper
+    pub fn get_repair_subchunk_indices(&self, lost_node: usize) -> Result<Vec<usize>, ClayError> {
+        repair_indices(&self.encode_params(), lost_node)
+    }
+
+    /// Determine the aloof nodes of a repair with the given helpers
+    ///
+    /// Aloof nodes are neither lost nor helpers; each one adds MDS recovery
+    /// work to the repair, so helper selections with fewer of them repair
+    /// more cheaply.
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `helpers`: Helper node indices
+    ///
+    /// # Returns
+    /// Aloof node indices in ascending order, or error if an index is out
+    /// of range or the lost node is among the helpers
+    pub fn aloof_nodes(
+        &self,
+        lost_node: usize,
+        helpers: &[usize],
+    ) -> Result<Vec<usize>, ClayError> {
+        repair_aloof_nodes(&self.encode_params(), lost_node, helpers)
+    }
+
+    /// Classify how a helper's sub-chunks are used in each repair layer
+    ///
+    /// Every helper sends the same β sub-chunks, but what each one feeds
+    /// differs: an unpaired sub-chunk goes straight into the layer's MDS
+    /// decode, a paired one is first transformed with its partner's, and
+    /// one paired with an aloof node has to wait for the layer that
+    /// recovers the aloof node. Helpers are those minimum_to_repair()
+    /// picks with every other node available.
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `helper`: Index of one of its helpers
+    ///
+    /// # Returns
+    /// (layer, role) for each repair layer in ascending order, or error if
+    /// an index is out of range or `helper` is not one of the helpers
+    pub fn helper_layer_roles(
+        &self,
+        lost_node: usize,
+        helper: usize,
+    ) -> Result<Vec<(usize, HelperRole)>, ClayError> {
+        repair_helper_roles(&self.encode_params(), lost_node, helper)
+    }
+
+    /// Get the repair sub-chunk indices of every node at once
+    ///
+    /// Lets a scheduler build its fetch lookup table once per code instead
+    /// of asking per repair.
+    ///
+    /// # Returns
+    /// For each external node 0..n, the sub-chunk indices its helpers supply,
+    /// as get_repair_subchunk_indices() returns them
+    pub fn repair_plan(&self) -> Vec<Vec<usize>> {
+        repair_index_plan(&self.encode_params())
+    }
+
+    /// Determine the byte ranges to read from each helper to repair a lost node
+    ///
+    /// Helpers are chosen as by minimum_to_repair() with every other node
+    /// available, and adjacent sub-chunks are merged into one range so a
+    /// caller can read them with as few seeks as possible.
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `chunk_size`: Full chunk size (a multiple of sub_chunk_no)
+    ///
+    /// # Returns
+    /// Vector of (helper_node_idx, byte_ranges). The bytes of each helper's
+    /// ranges, concatenated in order, form its data for repair().
+    pub fn repair_byte_ranges(
+        &self,
+        lost_node: usize,
+        chunk_size: usize,
+    ) -> Result<HelperByteRanges, ClayError> {
+        repair_ranges(&self.encode_params(), lost_node, chunk_size)
+    }
+
+    /// Repair a lost chunk using partial data from helper nodes
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `helper_data`: Map from helper node index to partial chunk data.
+    ///   Each helper's data must be the concatenation of sub-chunks at the
+    ///   indices returned by minimum_to_repair(), in that exact order.
+    /// - `chunk_size`: Full chunk size
+    ///
+    /// # Returns
+    /// The recovered full chunk, or error if repair fails
+    pub fn repair(
+        &self,
+        lost_node: usize,
+        helper_data: &HashMap<usize, Vec<u8>>,
+        chunk_size: usize,
+    ) -> Result<Vec<u8>, ClayError> {
+        repair_chunk(&self.encode_params(), lost_node, helper_data, chunk_size)
+    }
+
+    /// Repair a lost chunk stored in the given sub-chunk layout
+    ///
+    /// Helper data is the same β sub-chunks as for repair(), each taken out
+    /// of the helper's stored chunk with sub_chunk_with_layout().
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `helper_data`: Map from helper node index to its repair sub-chunks,
+    ///   concatenated in minimum_to_repair() order
+    /// - `chunk_size`: Full chunk size
+    /// - `layout`: Layout the chunks were encoded with
+    ///
+    /// # Returns
+    /// The recovered full chunk in `layout`, or error if repair fails
+    pub fn repair_with_layout(
+        &self,
+        lost_node: usize,
+        helper_data: &HashMap<usize, Vec<u8>>,
+        chunk_size: usize,
+        layout: SubChunkLayout,
+    ) -> Result<Vec<u8>, ClayError> {
+        let recovered = self.repair(lost_node, helper_data, chunk_size)?;
+        Ok(layout.arrange(recovered, self.sub_chunk_no))
+    }
+
+    /// Repair a lost chunk into a caller-provided buffer
+    ///
+    /// Same result as repair() with `out.len()` as the chunk size, written
+    /// in place instead of returned. Working memory is still allocated per
+    /// call; repair_with() and a [`RepairWorkspace`] avoid that.
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `helper_data`: Map from helper node index to partial chunk data
+    /// - `out`: Buffer of exactly one chunk for the recovered chunk
+    ///
+    /// # Returns
+    /// Ok once `out` holds the recovered chunk, or error if the buffer
+    /// length does not match the helper data or repair fails; `out` is left
+    /// untouched on error
+    pub fn repair_into(
+        &self,
+        lost_node: usize,
+        helper_data: &HashMap<usize, Vec<u8>>,
+        out: &mut [u8],
+    ) -> Result<(), ClayError> {
+        repair_chunk_into(&self.encode_params(), lost_node, helper_data, out)
+    }
+
+    /// Repair a lost chunk into the buffers of a reusable workspace
+    ///
+    /// Same result as repair() for the workspace's chunk size. Nothing is
+    /// allocated once the workspace has repaired a node at each position
+    /// within a y-section, so a node doing continuous background repair can
+    /// keep one workspace per chunk size.
+    ///
+    /// # Parameters
+    /// - `ws`: Workspace from `RepairWorkspace::new` for this code
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `helper_data`: Map from helper node index to partial chunk data
+    ///
+    /// # Returns
+    /// The recovered chunk, borrowed from the workspace, or error if the
+    /// workspace belongs to another code or repair fails
+    pub fn repair_with<'w>(
+        &self,
+        ws: &'w mut RepairWorkspace,
+        lost_node: usize,
+        helper_data: &HashMap<usize, Vec<u8>>,
+    ) -> Result<&'w [u8], ClayError> {
+        repair_into_workspace(&self.encode_params(), ws, lost_node, helper_data)
+    }
+
+    /// Repair a lost chunk and report the bandwidth used
+    ///
+    /// Same as repair(), for callers that log the real repair traffic
+    /// against a full k-chunk decode.
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `helper_data`: Map from helper node index to partial chunk data
+    /// - `chunk_size`: Full chunk size
+    ///
+    /// # Returns
+    /// The recovered full chunk and its RepairStats, or error if repair fails
+    pub fn repair_with_stats(
+        &self,
+        lost_node: usize,
+        helper_data: &HashMap<usize, Vec<u8>>,
+        chunk_size: usize,
+    ) -> Result<(Vec<u8>, RepairStats), ClayError> {
+        repair_chunk_with_stats(&self.encode_params(), lost_node, helper_data, chunk_size)
+    }
+
+    /// Extract what a helper sends to repair a lost node from its checksummed chunk
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `stored`: The helper's chunk from encode_with_checksums()
+    ///
+    /// # Returns
+    /// The β repair sub-chunks followed by their β checksums
+    pub fn checksummed_helper_data(
+        &self,
+        lost_node: usize,
+        stored: &[u8],
+    ) -> Result<Vec<u8>, ClayError> {
+        checksummed_repair_data(&self.encode_params(), lost_node, stored)
+    }
+
+    /// Repair a lost chunk, checking every helper sub-chunk against its checksum
+    ///
+    /// A silently corrupted helper sub-chunk would otherwise be mixed into
+    /// the recovered chunk; here it is reported before repair starts.
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `helper_data`: Map from helper node index to its data from
+    ///   checksummed_helper_data()
+    /// - `chunk_size`: Full chunk size, without the checksum trailer
+    ///
+    /// # Returns
+    /// The recovered chunk with its checksum trailer, or ReconstructionFailed
+    /// naming the helper whose sub-chunk is corrupted
+    pub fn repair_with_checksums(
+        &self,
+        lost_node: usize,
+        helper_data: &HashMap<usize, Vec<u8>>,
+        chunk_size: usize,
+    ) -> Result<Vec<u8>, ClayError> {
+        repair_checksummed(&self.encode_params(), lost_node, helper_data, chunk_size)
+    }
+
+    /// Repair a lost chunk and cross-check it against extra surviving chunks
+    ///
+    /// The recovered chunk, the extra chunks and the helper data must
+    /// satisfy the RS relation of every uncoupled layer they determine
+    /// with redundancy, which includes all the repair layers. This catches
+    /// a corrupted helper that a plain repair would silently propagate;
+    /// passing a helper's full chunk as an extra checks its sub-chunks.
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `helper_data`: Map from helper node index to partial chunk data
+    /// - `chunk_size`: Full chunk size
+    /// - `extra_parity_check`: Map from surviving node index, helper or
+    ///   not, to its full chunk
+    ///
+    /// # Returns
+    /// The recovered full chunk, or ReconstructionFailed on a mismatch
+    pub fn repair_verified(
+        &self,
+        lost_node: usize,
+        helper_data: &HashMap<usize, Vec<u8>>,
+        chunk_size: usize,
+        extra_parity_check: &HashMap<usize, Vec<u8>>,
+    ) -> Result<Vec<u8>, ClayError> {
+        repair_chunk_verified(
+            &self.encode_params(),
+            lost_node,
+            helper_data,
+            chunk_size,
+            extra_parity_check,
+        )
+    }
+
+    /// Repair a lost chunk from full helper chunks
+    ///
+    /// Like repair(), but each helper supplies its whole chunk and the
+    /// needed sub-chunks are extracted internally.
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `helpers`: Map from helper node index to its full chunk
+    /// - `chunk_size`: Full chunk size; every helper chunk must be this long
+    ///
+    /// # Returns
+    /// The recovered full chunk, or error if repair fails
+    pub fn repair_from_full_chunks(
+        &self,
+        lost_node: usize,
+        helpers: &HashMap<usize, Vec<u8>>,
+        chunk_size: usize,
+    ) -> Result<Vec<u8>, ClayError> {
+        repair_chunk_from_full(&self.encode_params(), lost_node, helpers, chunk_size)
+    }
+
+    /// Repair a lost chunk by seeking into each helper's stored chunk
+    ///
+    /// Only the β repair sub-chunks of each helper are read, at the byte
+    /// ranges repair_byte_ranges() reports, so whole chunks are never
+    /// buffered.
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `helpers`: Map from helper node index to a reader over its chunk,
+    ///   such as an open file or `&mut File`
+    /// - `chunk_size`: Full chunk size
+    ///
+    /// # Returns
+    /// The recovered full chunk, or error if a read or the repair fails
+    #[cfg(feature = "std")]
+    pub fn repair_from_readers<R: std::io::Read + std::io::Seek>(
+        &self,
+        lost_node: usize,
+        helpers: &mut HashMap<usize, R>,
+        chunk_size: usize,
+    ) -> Result<Vec<u8>, ClayError> {
+        repair_chunk_from_readers(&self.encode_params(), lost_node, helpers, chunk_size)
+    }
+
+    /// Repair a lost chunk from sub-chunks keyed by their index
+    ///
+    /// Like repair(), but helper sub-chunks are looked up by index instead
+    /// of being concatenated in minimum_to_repair() order.
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `helpers`: Map from helper node index to a map of sub-chunk index
+    ///   to sub-chunk bytes
+    /// - `chunk_size`: Full chunk size
+    ///
+    /// # Returns
+    /// The recovered full chunk, or MissingSubChunk if a helper lacks a
+    /// needed sub-chunk
+    pub fn repair_from_subchunk_map(
+        &self,
+        lost_node: usize,
+        helpers: &HashMap<usize, HashMap<usize, Vec<u8>>>,
+        chunk_size: usize,
+    ) -> Result<Vec<u8>, ClayError> {
+        repair_chunk_from_subchunk_map(&self.encode_params(), lost_node, helpers, chunk_size)
+    }
+
+    /// Determine the sub-chunks needed to repair several lost nodes at once
+    ///
+    /// Lost nodes sharing a y-section are repaired together at reduced
+    /// bandwidth; lost nodes in different y-sections are repaired
+    /// independently, each treating the others as aloof. When the survivors
+    /// cannot support that, or it would read at least as much as a full
+    /// decode, the plan falls back to k helpers supplying whole chunks.
+    ///
+    /// # Parameters
+    /// - `lost_nodes`: Indices of the lost nodes (at most m, each 0 to n-1)
+    /// - `available`: Available node indices
+    ///
+    /// # Returns
+    /// Vector of (helper_node_idx, sub_chunk_indices). Every helper is asked
+    /// for the same ascending sub-chunk indices, and repair_multi() expects
+    /// each helper's data concatenated in that order.
+    pub fn minimum_to_repair_multi(
+        &self,
+        lost_nodes: &[usize],
+        available: &[usize],
+    ) -> Result<Vec<(usize, Vec<usize>)>, ClayError> {
+        min_repair_multi(&self.encode_params(), lost_nodes, available)
+    }
+
+    /// Repair several lost chunks using data from helper nodes
+    ///
+    /// # Parameters
+    /// - `lost_nodes`: Indices of the lost nodes (at most m, each 0 to n-1)
+    /// - `helper_data`: Map from helper node index to the data requested by
+    ///   minimum_to_repair_multi(), concatenated in that exact order
+    /// - `chunk_size`: Full chunk size
+    ///
+    /// # Returns
+    /// Map from lost node index to its recovered full chunk
+    pub fn repair_multi(
+        &self,
+        lost_nodes: &[usize],
+        helper_data: &HashMap<usize, Vec<u8>>,
+        chunk_size: usize,
+    ) -> Result<HashMap<usize, Vec<u8>>, ClayError> {
+        repair_chunks_multi(&self.encode_params(), lost_nodes, helper_data, chunk_size)
+    }
+
+    /// Calculate normalized repair bandwidth
+    ///
+    /// This is the ratio of data downloaded for repair to the size of the
+    /// repaired chunk. For Clay codes, this is d / (k * q).
+    pub fn normalized_repair_bandwidth(&self) -> f64 {
+        (self.d as f64) / ((self.k as f64) * (self.d - self.k + 1) as f64)
+    }
+
+    /// Normalized repair bandwidth a code with the same k and m would have
+    /// if it were built with `d` helpers
+    ///
+    /// For comparing choices of d without constructing a code for each.
+    /// Such a code has q = d - k + 1 and helpers each send β = α / q
+    /// sub-chunks, so the ratio is d / (k * q) whatever α comes to.
+    ///
+    /// # Parameters
+    /// - `d`: Number of helpers, in [k+1, n-1], or exactly k when m = 1
+    ///
+    /// # Returns
+    /// The ratio of bytes downloaded to the size of the repaired chunk, or
+    /// error if d is out of range
+    pub fn repair_bandwidth_for_d(&self, d: usize) -> Result<f64, ClayError> {
+        let min_d = if self.m == 1 { self.k } else { self.k + 1 };
+        if d < min_d || d > self.n - 1 {
+            return Err(ClayError::InvalidParameters(format!(
+                "d must be in range [{}, {}], got {}",
+                min_d,
+                self.n - 1,
+                d
+            )));
+        }
+        let q = d - self.k + 1;
+        Ok((d as f64) / ((self.k as f64) * q as f64))
+    }
+
+    /// Bytes read from helpers to repair one node, without any data
+    ///
+    /// d helpers each send β sub-chunks, matching the `bytes_read` that
+    /// repair_with_stats() reports.
+    ///
+    /// # Parameters
+    /// - `lost_node`: Index of the lost node (0 to n-1)
+    /// - `chunk_size`: Full chunk size
+    ///
+    /// # Returns
+    /// d * β * (chunk_size / α), or error if the node index or chunk size
+    /// is invalid
+    pub fn repair_cost(&self, lost_node: usize, chunk_size: usize) -> Result<usize, ClayError> {
+        if lost_node >= self.n {
+            return Err(ClayError::InvalidParameters(format!(
+                "Invalid lost node index: {} >= {}",
+                lost_node, self.n
+            )));
+        }
+        self.d
+            .checked_mul(self.helper_bytes(chunk_size)?)
+            .ok_or_else(|| {
+                ClayError::Overflow(format!("repair cost of {}-byte chunks", chunk_size))
+            })
+    }
+
+    /// Bytes each helper supplies to repair one node
+    ///
+    /// Every helper of a single-node repair sends the same β sub-chunks'
+    /// worth of data, whichever node is lost, so one buffer of this size
+    /// fits the data of any helper listed by minimum_to_repair().
+    ///
+    /// # Parameters
+    /// - `chunk_size`: Full chunk size
+    ///
+    /// # Returns
+    /// β * (chunk_size / α), or error if the chunk size is not a non-zero
+    /// multiple of α
+    pub fn helper_bytes(&self, chunk_size: usize) -> Result<usize, ClayError> {
+        if chunk_size == 0 || chunk_size % self.sub_chunk_no != 0 {
+            return Err(ClayError::InvalidChunkSize {
+                expected: self.sub_chunk_no,
+                actual: chunk_size,
+            });
+        }
+        Ok(self.beta * (chunk_size / self.sub_chunk_no))
+    }
+
+    /// Bytes read to rebuild a node by full decode (k whole chunks)
+    ///
+    /// Divide repair_cost() by this for the bandwidth ratio of a repair.
+    /// Saturates at `usize::MAX` rather than wrapping.
+    pub fn full_decode_cost(&self, chunk_size: usize) -> usize {
+        self.k.saturating_mul(chunk_size)
+    }
+
+    /// Approximate peak transient memory of one decode or repair
+    ///
+    /// Counts the working stripe and U buffers (a chunk each per internal
+    /// node, k + m + nu), the per-layer bookkeeping and the returned data,
+    /// and takes the larger of decode and repair. Input chunks are not
+    /// counted. With the `rayon` feature each layer decoded in parallel
+    /// adds one more set of sub-chunk shards on top.
+    ///
+    /// # Parameters
+    /// - `chunk_size`: Full chunk size
+    ///
+    /// # Returns
+    /// Estimated bytes, saturating at `usize::MAX`
+    pub fn working_set_bytes(&self, chunk_size: usize) -> usize {
+        let total_nodes = self.q * self.t;
+        let sub_chunk_size = chunk_size / self.sub_chunk_no;
+        // U values, computed flags and one layer of RS shards per node
+        let u_bytes = chunk_size
+            .saturating_add(self.sub_chunk_no)
+            .saturating_add(sub_chunk_size)
+            .saturating_mul(total_nodes)
+            .saturating_add(sub_chunk_size.saturating_mul(2));
+        // Decode also holds the whole stripe and returns k data chunks
+        let decode = total_nodes
+            .saturating_mul(chunk_size)
+            .saturating_add(self.k.saturating_mul(chunk_size))
+            .saturating_add(u_bytes);
+        // Repair returns one chunk and may rebuild the rest of its y-section
+        let repair = self.q.saturating_mul(chunk_size).saturating_add(u_bytes);
+        decode.max(repair)
+    }
+}
+
+/// One-line summary for logs, e.g.
+/// `Clay(n=6,k=4,m=2,d=5,q=2,α=8,β=4,overhead=1.50x,repair_bw=62.5%)`
+///
+/// `repair_bw` is normalized_repair_bandwidth() as a percentage of the k
+/// chunks a plain RS repair reads.
+impl core::fmt::Display for ClayCode {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "Clay(n={},k={},m={},d={},q={},α={},β={},overhead={:.2}x,repair_bw={:.1}%)",
+            self.n,
+            self.k,
+            self.m,
+            self.d,
+            self.q,
+            self.sub_chunk_no,
+            self.beta,
+            self.n as f64 / self.k as f64,
+            self.normalized_repair_bandwidth() * 100.0
+        )
+    }
+}
+
+/// Total bytes of `nodes` buffers of `chunk_size` bytes each
+///
+/// Errors with `Overflow` if they could not be allocated at all (more than
+/// `isize::MAX` bytes), so sizes from the caller fail cleanly instead of
+/// wrapping. Sub-chunk offsets into such buffers, `z * sub_chunk_size` with
+/// z < α, stay below `chunk_size` and need no further checks.
+fn checked_buffer_bytes(nodes: usize, chunk_size: usize) -> Result<usize, ClayError> {
+    nodes
+        .checked_mul(chunk_size)
+        .filter(|&bytes| bytes <= isize::MAX as usize)
+        .ok_or_else(|| {
+            ClayError::Overflow(format!("{} buffers of {} bytes", nodes, chunk_size))
+        })
+}
+
+/// Integer power function with overflow checking
+fn checked_pow(base: usize, exp: usize) -> Option<usize> {
+    let mut result: usize = 1;
+    let mut b = base;
+    let mut e = exp;
+    while e > 0 {
+        if e & 1 == 1 {
+            result = result.checked_mul(b)?;
+        }
+        e >>= 1;
+        if e > 0 {
+            b = b.checked_mul(b)?;
+        }
+    }
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::partial_helper_data;
+
+    #[test]
+    fn test_basic_encode_decode() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        let data = b"Test data for Clay codes - not empty!";
+        let chunks = clay.encode(data).unwrap();
+        assert_eq!(chunks.len(), 6); // k + m = 6
+
+        // Decode with all chunks
+        let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
+        for (i, chunk) in chunks.iter().enumerate() {
+            available.insert(i, chunk.clone());
+        }
+        let decoded = clay.decode(&available, &[]).unwrap();
+
+        // Check prefix matches (may have padding)
+        assert_eq!(&decoded[..data.len()], &data[..]);
+    }
+
+    #[test]
+    fn test_decode_with_erasures() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
         let data = b"Test data for Clay codes - testing erasure recovery!";
-        let chunks = clay.encode(data);
+        let chunks = clay.encode(data).unwrap();
 
         // Lose node 0
         let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
@@ -350,77 +2496,614 @@ mod tests {
     }
 
     #[test]
-    fn test_repair_bandwidth_verification() {
-        // This test verifies we're actually using Clay's repair advantage
-        let clay = ClayCode::new(4, 2, 5).unwrap();
-        let data = b"Test data for bandwidth verification of Clay codes repair!";
-        let chunks = clay.encode(data);
-        let chunk_size = chunks[0].len();
+    fn test_repair_bandwidth_verification() {
+        // This test verifies we're actually using Clay's repair advantage
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        let data = b"Test data for bandwidth verification of Clay codes repair!";
+        let chunks = clay.encode(data).unwrap();
+        let chunk_size = chunks[0].len();
+
+        // Get minimum data needed to repair node 0
+        let available: Vec<usize> = vec![1, 2, 3, 4, 5];
+        let helper_info = clay.minimum_to_repair(0, &available).unwrap();
+
+        // Calculate total sub-chunks requested
+        let sub_chunk_size = chunk_size / clay.sub_chunk_no;
+        let total_repair_subchunks: usize = helper_info
+            .iter()
+            .map(|(_, indices)| indices.len())
+            .sum();
+        let total_repair_bytes = total_repair_subchunks * sub_chunk_size;
+
+        let full_decode_bytes = clay.k * chunk_size;
+
+        // Clay repair should use significantly less data
+        let ratio = total_repair_bytes as f64 / full_decode_bytes as f64;
+        println!(
+            "Repair bandwidth: {} bytes, Full decode: {} bytes, Ratio: {:.3}",
+            total_repair_bytes, full_decode_bytes, ratio
+        );
+
+        assert!(
+            total_repair_bytes < full_decode_bytes * 7 / 10,
+            "Repair bandwidth {} should be < 70% of full decode {}",
+            total_repair_bytes,
+            full_decode_bytes
+        );
+    }
+
+    #[test]
+    fn test_repair_correctness() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        let data = b"Test data for repair correctness verification!!!!";
+        let chunks = clay.encode(data).unwrap();
+        let chunk_size = chunks[0].len();
+        let sub_chunk_size = chunk_size / clay.sub_chunk_no;
+
+        // Test repairing each node
+        for lost_node in 0..clay.n {
+            let available: Vec<usize> = (0..clay.n).filter(|&i| i != lost_node).collect();
+            let helper_info = clay.minimum_to_repair(lost_node, &available).unwrap();
+
+            // Extract only the required sub-chunks from each helper
+            let partial_data = partial_helper_data(&chunks, &helper_info, sub_chunk_size);
+
+            // Repair using ONLY partial data
+            let recovered = clay.repair(lost_node, &partial_data, chunk_size).unwrap();
+
+            // Verify recovered chunk matches original
+            assert_eq!(
+                recovered, chunks[lost_node],
+                "Repair failed for node {}",
+                lost_node
+            );
+        }
+    }
+
+    #[test]
+    fn test_single_data_chunk_codes() {
+        // k = 1: every chunk is a coded copy of the data. (1, 2, 2) needs a
+        // shortened node to fill node 0's y-section, (1, 3, 2) does not.
+        for (m, d, nu) in [(2, 2, 1), (3, 2, 0)] {
+            let clay = ClayCode::new(1, m, d).unwrap();
+            assert_eq!((clay.q, clay.t, clay.nu, clay.sub_chunk_no), (2, 2, nu, 4));
+
+            let data: Vec<u8> = (0..100).map(|i| (i * 23 + 5) as u8).collect();
+            let chunks = clay.encode(&data).unwrap();
+            assert_eq!(chunks.len(), 1 + m);
+            let chunk_size = chunks[0].len();
+            assert_eq!(&chunks[0][..data.len()], &data[..]);
+            assert!(chunks[0][data.len()..].iter().all(|&b| b == 0));
+
+            // No erasures, then every single and double erasure
+            let mut patterns: Vec<Vec<usize>> = vec![vec![]];
+            patterns.extend((0..clay.n).map(|a| vec![a]));
+            patterns.extend((0..clay.n).flat_map(|a| (a + 1..clay.n).map(move |b| vec![a, b])));
+            for erasures in patterns {
+                let available: HashMap<usize, Vec<u8>> = (0..clay.n)
+                    .filter(|i| !erasures.contains(i))
+                    .map(|i| (i, chunks[i].clone()))
+                    .collect();
+                let decoded = clay.decode(&available, &erasures).unwrap();
+                assert_eq!(&decoded[..data.len()], &data[..], "(1, {}, {}) {:?}", m, d, erasures);
+                assert_eq!(decoded.len(), chunk_size);
+            }
+
+            for lost_node in 0..clay.n {
+                let available: Vec<usize> = (0..clay.n).filter(|&i| i != lost_node).collect();
+                let plan = clay.minimum_to_repair(lost_node, &available).unwrap();
+                assert_eq!(plan.len(), d);
+                let sub_chunk_size = chunk_size / clay.sub_chunk_no;
+                let helper_data = partial_helper_data(&chunks, &plan, sub_chunk_size);
+                let recovered = clay.repair(lost_node, &helper_data, chunk_size).unwrap();
+                assert_eq!(recovered, chunks[lost_node], "(1, {}, {}) node {}", m, d, lost_node);
+            }
+        }
+    }
+
+    #[test]
+    fn test_repair_byte_ranges() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        let data: Vec<u8> = (0..256).map(|i| (i * 3 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+        let chunk_size = chunks[0].len();
+        let sub_chunk_size = chunk_size / clay.sub_chunk_no;
+
+        for lost_node in 0..clay.n {
+            let indices = clay.get_repair_subchunk_indices(lost_node).unwrap();
+            assert_eq!(indices.len(), clay.beta);
+
+            let ranges = clay.repair_byte_ranges(lost_node, chunk_size).unwrap();
+            assert_eq!(ranges.len(), clay.d);
+
+            let mut helper_data: HashMap<usize, Vec<u8>> = HashMap::new();
+            for (helper, helper_ranges) in &ranges {
+                let total: usize = helper_ranges.iter().map(|r| r.len()).sum();
+                assert_eq!(total, clay.beta * sub_chunk_size);
+                // Merged ranges never touch each other
+                for pair in helper_ranges.windows(2) {
+                    assert!(pair[0].end < pair[1].start);
+                }
+                let bytes: Vec<u8> = helper_ranges
+                    .iter()
+                    .flat_map(|r| chunks[*helper][r.clone()].iter().copied())
+                    .collect();
+                helper_data.insert(*helper, bytes);
+            }
+
+            let (recovered, stats) =
+                clay.repair_with_stats(lost_node, &helper_data, chunk_size).unwrap();
+            assert_eq!(recovered, chunks[lost_node], "Repair failed for node {}", lost_node);
+            assert_eq!(
+                stats,
+                RepairStats {
+                    helpers_used: clay.d,
+                    subchunks_per_helper: clay.beta,
+                    bytes_read: clay.d * clay.beta * sub_chunk_size,
+                    bytes_written: chunk_size,
+                }
+            );
+            assert_eq!(clay.repair_cost(lost_node, chunk_size).unwrap(), stats.bytes_read);
+            let helper_bytes = clay.helper_bytes(chunk_size).unwrap();
+            assert!(helper_data.values().all(|h| h.len() == helper_bytes));
+        }
+        assert_eq!(clay.full_decode_cost(chunk_size), clay.k * chunk_size);
+        assert!(matches!(
+            clay.repair_cost(clay.n, chunk_size),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            clay.repair_cost(0, chunk_size + 1),
+            Err(ClayError::InvalidChunkSize { .. })
+        ));
+        assert!(matches!(
+            clay.helper_bytes(0),
+            Err(ClayError::InvalidChunkSize { .. })
+        ));
+
+        // Node 0 is unpaired in the first beta layers: one contiguous range
+        let ranges = clay.repair_byte_ranges(0, chunk_size).unwrap();
+        for (_, helper_ranges) in &ranges {
+            assert_eq!(helper_ranges.len(), 1);
+            assert_eq!(helper_ranges[0], 0..clay.beta * sub_chunk_size);
+        }
+
+        assert!(clay.get_repair_subchunk_indices(clay.n).is_err());
+        assert!(matches!(
+            clay.repair_byte_ranges(0, chunk_size + 1),
+            Err(ClayError::InvalidChunkSize { .. })
+        ));
+    }
+
+    #[test]
+    fn test_layer_scores_and_decoding_order() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        let max_score = |erasures: &[usize]| {
+            clay.layer_scores(erasures).unwrap().into_iter().max().unwrap()
+        };
+
+        // Same expectations as get_max_iscore, through the public surface
+        assert_eq!(max_score(&[]), 0);
+        assert_eq!(max_score(&[0]), 1);
+        assert_eq!(max_score(&[0, 1]), 1);
+        assert_eq!(max_score(&[0, 2]), 2);
+
+        // Parity indices are shifted past the shortened nodes
+        let shortened = ClayCode::new(5, 2, 6).unwrap();
+        assert_eq!(shortened.nu, 1);
+        let scores = shortened.layer_scores(&[5]).unwrap();
+        assert_eq!(scores.len(), shortened.sub_chunk_no);
+        assert_eq!(scores.iter().sum::<usize>(), shortened.beta);
+
+        let scores = clay.layer_scores(&[0, 2]).unwrap();
+        let order = clay.decoding_order(&[0, 2]).unwrap();
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (0..clay.sub_chunk_no).collect::<Vec<_>>());
+        assert!(order.windows(2).all(|w| scores[w[0]] <= scores[w[1]]));
+
+        // Out-of-range and repeated erasures are errors, not panics
+        for erasures in [&[6][..], &[0, 9], &[2, 2]] {
+            assert!(matches!(clay.layer_scores(erasures), Err(ClayError::InvalidParameters(_))));
+            assert!(matches!(clay.decoding_order(erasures), Err(ClayError::InvalidParameters(_))));
+        }
+    }
+
+    #[test]
+    fn test_params_roundtrip() {
+        let clay = ClayCode::new(10, 4, 13).unwrap();
+        let params = clay.params();
+        assert_eq!(params, ClayParams { k: 10, m: 4, d: 13 });
+
+        let rebuilt = ClayCode::from_params(params).unwrap();
+        assert_eq!(
+            (rebuilt.n, rebuilt.q, rebuilt.t, rebuilt.nu, rebuilt.sub_chunk_no, rebuilt.beta),
+            (clay.n, clay.q, clay.t, clay.nu, clay.sub_chunk_no, clay.beta)
+        );
+
+        assert!(ClayCode::from_params(ClayParams { k: 4, m: 2, d: 6 }).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        for (k, m, d) in [(4, 2, 5), (5, 3, 7), (10, 4, 12), (4, 1, 4)] {
+            ClayCode::new(k, m, d).unwrap().validate().unwrap();
+        }
+        ClayCode::with_gamma(4, 2, 5, 7).unwrap().validate().unwrap();
+
+        let clay = ClayCode::new(5, 3, 7).unwrap();
+        let tampered: [fn(&mut ClayCode); 8] = [
+            |c| c.n += 1,
+            |c| c.d = 8,
+            |c| c.q = 2,
+            |c| c.nu = 0,
+            |c| c.t += 1,
+            |c| c.sub_chunk_no *= 3,
+            |c| c.beta = c.sub_chunk_no,
+            |c| c.gamma = 3,
+        ];
+        for (i, tamper) in tampered.iter().enumerate() {
+            let mut bad = clay.clone();
+            tamper(&mut bad);
+            assert!(
+                matches!(bad.validate(), Err(ClayError::InvalidParameters(_))),
+                "tampering {} went unnoticed",
+                i
+            );
+        }
+
+        // Parameters changed consistently, but the layer code still has k = 5
+        let mut bad = clay.clone();
+        bad.k = 4;
+        bad.d = 6;
+        match bad.validate() {
+            Err(ClayError::InvalidParameters(msg)) => assert!(msg.contains("n is 8"), "{}", msg),
+            other => panic!("expected InvalidParameters, got {:?}", other),
+        }
+        bad.m = 4;
+        match bad.validate() {
+            Err(ClayError::InvalidParameters(msg)) => assert!(msg.contains("RS"), "{}", msg),
+            other => panic!("expected InvalidParameters, got {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_params_json_roundtrip() {
+        let clay = ClayCode::new(5, 3, 7).unwrap();
+        let json = serde_json::to_string(&clay.params()).unwrap();
+        assert_eq!(json, r#"{"k":5,"m":3,"d":7}"#);
+
+        let rebuilt = ClayCode::from_params(serde_json::from_str(&json).unwrap()).unwrap();
+        assert_eq!(
+            (rebuilt.k, rebuilt.m, rebuilt.d, rebuilt.q, rebuilt.t, rebuilt.nu),
+            (clay.k, clay.m, clay.d, clay.q, clay.t, clay.nu)
+        );
+        assert_eq!((rebuilt.sub_chunk_no, rebuilt.beta), (clay.sub_chunk_no, clay.beta));
+
+        let manifest = clay.encode_object(&[1u8; 100], 2 * clay.sub_chunk_no).unwrap().manifest;
+        let json = serde_json::to_string(&manifest).unwrap();
+        assert_eq!(json, r#"{"len":100,"chunk_size":54,"stripe_count":1}"#);
+        assert_eq!(serde_json::from_str::<ObjectManifest>(&json).unwrap(), manifest);
+    }
+
+    #[test]
+    fn test_recommend() {
+        let codes = ClayCode::recommend(1.5, 256, 2);
+        assert!(!codes.is_empty());
+        for code in &codes {
+            assert!(code.n as f64 <= 1.5 * code.k as f64);
+            assert!(code.m >= 2);
+            assert!(code.sub_chunk_no <= 256);
+        }
+        assert!(codes
+            .windows(2)
+            .all(|w| w[0].normalized_repair_bandwidth() <= w[1].normalized_repair_bandwidth()));
+        assert!(codes.iter().any(|c| (c.k, c.m, c.d) == (10, 4, 13)));
+
+        // Tighter targets only remove candidates
+        let durable = ClayCode::recommend(1.5, 256, 4);
+        assert!(durable.len() < codes.len());
+        assert!(durable.iter().all(|c| c.m >= 4));
+        assert!(ClayCode::recommend(1.0, 256, 2).is_empty());
+        assert!(ClayCode::recommend(3.0, 1, 2).is_empty());
+        assert!(ClayCode::recommend(3.0, 0, 1).is_empty());
+        let uncoupled = ClayCode::recommend(3.0, 1, 1);
+        assert!(!uncoupled.is_empty());
+        assert!(uncoupled.iter().all(|c| c.m == 1 && c.sub_chunk_no == 1));
+
+        // Wide repair groups keep α small: q = 10, t = 2 gives α = 100
+        let wide = ClayCode::recommend(2.0, 100, 10);
+        assert!(wide.iter().any(|c| (c.k, c.m, c.d, c.sub_chunk_no) == (10, 10, 19, 100)));
+        assert!(wide.iter().all(|c| c.sub_chunk_no <= 100 && c.m >= 10));
+
+        // Single-parity codes only show up when one parity is enough
+        let single = ClayCode::recommend(1.5, 256, 1);
+        assert!(single.iter().any(|c| (c.k, c.m, c.d, c.sub_chunk_no) == (4, 1, 4, 1)));
+        assert!(single.iter().all(|c| c.n as f64 <= 1.5 * c.k as f64));
+        assert_eq!(single.len(), codes.len() + single.iter().filter(|c| c.m == 1).count());
+        assert!(codes.iter().all(|c| c.m >= 2));
+    }
+
+    #[test]
+    fn test_repair_with_d() {
+        let clay = ClayCode::new(6, 3, 8).unwrap();
+        let data: Vec<u8> = (0..2000).map(|i| (i * 7 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+        let chunk_size = chunks[0].len();
+        let sub_chunk_size = chunk_size / clay.sub_chunk_no;
+
+        let lost_node = 2;
+        let available: Vec<usize> = (0..clay.n).filter(|&i| i != lost_node).collect();
+        let helper_info = clay.minimum_to_repair_with_d(lost_node, &available, 8).unwrap();
+        assert_eq!(helper_info, clay.minimum_to_repair(lost_node, &available).unwrap());
+
+        let helper_data = partial_helper_data(&chunks, &helper_info, sub_chunk_size);
+        let recovered = clay.repair_with_d(lost_node, &helper_data, chunk_size, 8).unwrap();
+        assert_eq!(recovered, chunks[lost_node]);
+
+        // d must exceed k, be covered by the helpers and match q
+        assert!(matches!(
+            clay.minimum_to_repair_with_d(lost_node, &available, 6),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            clay.minimum_to_repair_with_d(lost_node, &available[..6], 7),
+            Err(ClayError::InsufficientHelpers { needed: 7, provided: 6, .. })
+        ));
+        assert!(matches!(
+            clay.minimum_to_repair_with_d(lost_node, &available, 7),
+            Err(ClayError::InvalidParameters(_))
+        ));
+
+        // With one parity d = k is the only choice
+        let single = ClayCode::new(4, 1, 4).unwrap();
+        let chunks = single.encode(&data).unwrap();
+        let chunk_size = chunks[0].len();
+        let available: Vec<usize> = (0..single.n).filter(|&i| i != 1).collect();
+        let helper_info = single.minimum_to_repair_with_d(1, &available, 4).unwrap();
+        let helper_data = partial_helper_data(&chunks, &helper_info, chunk_size);
+        assert_eq!(single.repair_with_d(1, &helper_data, chunk_size, 4).unwrap(), chunks[1]);
+        assert!(matches!(
+            single.minimum_to_repair_with_d(1, &available, 3),
+            Err(ClayError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn test_minimum_to_repair_with_topology() {
+        // q = 3, n = 12, d = 10: one of the 11 survivors is left out
+        let clay = ClayCode::new(8, 4, 10).unwrap();
+        let data: Vec<u8> = (0..3000).map(|i| (i * 11 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+        let chunk_size = chunks[0].len();
+        let sub_chunk_size = chunk_size / clay.sub_chunk_no;
+
+        // Node 4 sits in the lost node's y-section, so it is required
+        // despite being expensive; node 9 is the costliest optional helper
+        let lost_node = 5;
+        let cost = |node: usize| match node {
+            4 => 50,
+            9 => 20,
+            10 | 11 => 1,
+            _ => 0,
+        };
+        let available: Vec<usize> = (0..clay.n).rev().filter(|&i| i != lost_node).collect();
+        let plan = clay.minimum_to_repair_with_topology(lost_node, &available, &cost).unwrap();
+        let helpers: Vec<usize> = plan.iter().map(|(h, _)| *h).collect();
+        assert_eq!(helpers, vec![3, 4, 0, 1, 2, 6, 7, 8, 10, 11]);
+
+        let helper_data = partial_helper_data(&chunks, &plan, sub_chunk_size);
+        assert_eq!(clay.repair(lost_node, &helper_data, chunk_size).unwrap(), chunks[lost_node]);
+    }
+
+    #[test]
+    fn test_repair_from_full_chunks() {
+        let clay = ClayCode::new(9, 3, 11).unwrap();
+        let data: Vec<u8> = (0..5000).map(|i| (i * 13 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+        let chunk_size = chunks[0].len();
+
+        for lost_node in 0..clay.n {
+            let available: Vec<usize> = (0..clay.n).filter(|&i| i != lost_node).collect();
+            let plan = clay.minimum_to_repair(lost_node, &available).unwrap();
+            let helpers: HashMap<usize, Vec<u8>> = plan
+                .iter()
+                .map(|(idx, _)| (*idx, chunks[*idx].clone()))
+                .collect();
+
+            let recovered = clay
+                .repair_from_full_chunks(lost_node, &helpers, chunk_size)
+                .unwrap();
+            assert_eq!(recovered, chunks[lost_node], "Repair failed for node {}", lost_node);
+        }
+
+        // A helper chunk of the wrong size is rejected
+        let mut helpers: HashMap<usize, Vec<u8>> =
+            (1..clay.n).map(|i| (i, chunks[i].clone())).collect();
+        helpers.get_mut(&1).unwrap().pop();
+        assert!(matches!(
+            clay.repair_from_full_chunks(0, &helpers, chunk_size),
+            Err(ClayError::InvalidChunkSize { .. })
+        ));
+    }
+
+    #[test]
+    fn test_repair_from_subchunk_map() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        let data: Vec<u8> = (0..1600).map(|i| (i * 7 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+        let chunk_size = chunks[0].len();
+
+        for lost_node in 0..clay.n {
+            let available: Vec<usize> = (0..clay.n).filter(|&i| i != lost_node).collect();
+            let plan = clay.minimum_to_repair(lost_node, &available).unwrap();
+            // Inserted in reverse, so nothing depends on fetch order
+            let mut helpers: HashMap<usize, HashMap<usize, Vec<u8>>> = plan
+                .iter()
+                .map(|(h, indices)| {
+                    let sub_chunks = indices
+                        .iter()
+                        .rev()
+                        .map(|&sc| (sc, clay.sub_chunk(&chunks[*h], sc).unwrap().to_vec()))
+                        .collect();
+                    (*h, sub_chunks)
+                })
+                .collect();
+            let recovered = clay.repair_from_subchunk_map(lost_node, &helpers, chunk_size).unwrap();
+            assert_eq!(recovered, chunks[lost_node], "Repair failed for node {}", lost_node);
+
+            let (helper, indices) = &plan[0];
+            helpers.get_mut(helper).unwrap().remove(&indices[1]);
+            assert_eq!(
+                clay.repair_from_subchunk_map(lost_node, &helpers, chunk_size),
+                Err(ClayError::MissingSubChunk { helper: *helper, sub_chunk: indices[1] })
+            );
+        }
+    }
+
+    #[test]
+    fn test_repair_verified() {
+        // Default d = n - 1, unshortened and shortened (nu = 1)
+        for clay in [ClayCode::new(4, 2, 5).unwrap(), ClayCode::new(5, 3, 7).unwrap()] {
+            let data: Vec<u8> = (0..3000).map(|i| (i * 17 % 253) as u8).collect();
+            let chunks = clay.encode(&data).unwrap();
+            let chunk_size = chunks[0].len();
+            let sub_chunk_size = chunk_size / clay.sub_chunk_no;
 
-        // Get minimum data needed to repair node 0
-        let available: Vec<usize> = vec![1, 2, 3, 4, 5];
-        let helper_info = clay.minimum_to_repair(0, &available).unwrap();
+            for lost_node in 0..clay.n {
+                let available: Vec<usize> = (0..clay.n).filter(|&i| i != lost_node).collect();
+                let plan = clay.minimum_to_repair(lost_node, &available).unwrap();
+                let helper_data = partial_helper_data(&chunks, &plan, sub_chunk_size);
+                let extra_of = |node: usize| -> HashMap<usize, Vec<u8>> {
+                    core::iter::once((node, chunks[node].clone())).collect()
+                };
 
-        // Calculate total sub-chunks requested
-        let sub_chunk_size = chunk_size / clay.sub_chunk_no;
-        let total_repair_subchunks: usize = helper_info
-            .iter()
-            .map(|(_, indices)| indices.len())
-            .sum();
-        let total_repair_bytes = total_repair_subchunks * sub_chunk_size;
+                // Any survivor can supply the extra chunk
+                for &node in &available {
+                    let recovered = clay
+                        .repair_verified(lost_node, &helper_data, chunk_size, &extra_of(node))
+                        .unwrap();
+                    assert_eq!(recovered, chunks[lost_node], "Repair of node {} failed", lost_node);
+                }
 
-        let full_decode_bytes = clay.k * chunk_size;
+                for (helper, indices) in &plan {
+                    // Corrupted in transit: the helper's own full chunk catches it,
+                    // in the lost y-section or not
+                    let mut bad = helper_data.clone();
+                    bad.get_mut(helper).unwrap()[0] ^= 0x5a;
+                    assert!(
+                        matches!(
+                            clay.repair_verified(lost_node, &bad, chunk_size, &extra_of(*helper)),
+                            Err(ClayError::ReconstructionFailed(_))
+                        ),
+                        "Corrupted helper {} not caught repairing node {}",
+                        helper,
+                        lost_node
+                    );
 
-        // Clay repair should use significantly less data
-        let ratio = total_repair_bytes as f64 / full_decode_bytes as f64;
-        println!(
-            "Repair bandwidth: {} bytes, Full decode: {} bytes, Ratio: {:.3}",
-            total_repair_bytes, full_decode_bytes, ratio
-        );
+                    // Corrupted at rest: the other survivors' full chunks catch it
+                    let mut rotten = chunks.clone();
+                    rotten[*helper][indices[0] * sub_chunk_size] ^= 0x5a;
+                    let extra: HashMap<usize, Vec<u8>> =
+                        available.iter().map(|&i| (i, rotten[i].clone())).collect();
+                    assert!(
+                        matches!(
+                            clay.repair_verified(lost_node, &bad, chunk_size, &extra),
+                            Err(ClayError::ReconstructionFailed(_))
+                        ),
+                        "Rotten helper {} not caught repairing node {}",
+                        helper,
+                        lost_node
+                    );
+                }
+            }
+        }
 
-        assert!(
-            total_repair_bytes < full_decode_bytes * 7 / 10,
-            "Repair bandwidth {} should be < 70% of full decode {}",
-            total_repair_bytes,
-            full_decode_bytes
-        );
-    }
+        // d < n - 1: a survivor outside the helper set works as well
+        let clay = ClayCode::new(6, 3, 7).unwrap();
+        let chunks = clay.encode(&[7u8; 1000]).unwrap();
+        let chunk_size = chunks[0].len();
+        let plan = clay.minimum_to_repair(0, &(1..clay.n - 1).collect::<Vec<_>>()).unwrap();
+        let helper_data = partial_helper_data(&chunks, &plan, chunk_size / clay.sub_chunk_no);
+        let spare: HashMap<usize, Vec<u8>> =
+            core::iter::once((clay.n - 1, chunks[clay.n - 1].clone())).collect();
+        assert_eq!(clay.repair_verified(0, &helper_data, chunk_size, &spare).unwrap(), chunks[0]);
 
-    #[test]
-    fn test_repair_correctness() {
         let clay = ClayCode::new(4, 2, 5).unwrap();
-        let data = b"Test data for repair correctness verification!!!!";
-        let chunks = clay.encode(data);
+        let chunks = clay.encode(&[7u8; 100]).unwrap();
         let chunk_size = chunks[0].len();
-        let sub_chunk_size = chunk_size / clay.sub_chunk_no;
+        let plan = clay.minimum_to_repair(0, &(1..clay.n).collect::<Vec<_>>()).unwrap();
+        let helper_data = partial_helper_data(&chunks, &plan, chunk_size / clay.sub_chunk_no);
+        assert!(matches!(
+            clay.repair_verified(0, &helper_data, chunk_size, &HashMap::new()),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        let lost: HashMap<usize, Vec<u8>> = core::iter::once((0, chunks[0].clone())).collect();
+        assert!(matches!(
+            clay.repair_verified(0, &helper_data, chunk_size, &lost),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        let short: HashMap<usize, Vec<u8>> = core::iter::once((1, vec![0u8; 8])).collect();
+        assert!(matches!(
+            clay.repair_verified(0, &helper_data, chunk_size, &short),
+            Err(ClayError::InvalidChunkSize { .. })
+        ));
+    }
 
-        // Test repairing each node
-        for lost_node in 0..clay.n {
-            let available: Vec<usize> = (0..clay.n).filter(|&i| i != lost_node).collect();
-            let helper_info = clay.minimum_to_repair(lost_node, &available).unwrap();
+    #[test]
+    fn test_repair_multi_all_pairs() {
+        for (k, m, d) in [(4, 2, 5), (9, 3, 11), (5, 3, 7)] {
+            let clay = ClayCode::new(k, m, d).unwrap();
+            let data: Vec<u8> = (0..k * clay.sub_chunk_no * 2).map(|i| (i * 29 % 251) as u8).collect();
+            let chunks = clay.encode(&data).unwrap();
+            let chunk_size = chunks[0].len();
+            let sub_chunk_size = chunk_size / clay.sub_chunk_no;
 
-            // Extract only the required sub-chunks from each helper
-            let mut partial_data: HashMap<usize, Vec<u8>> = HashMap::new();
-            for (helper_idx, indices) in &helper_info {
-                let mut helper_partial = Vec::new();
-                for &sc_idx in indices {
-                    let start_byte = sc_idx * sub_chunk_size;
-                    let end_byte = (sc_idx + 1) * sub_chunk_size;
-                    helper_partial.extend_from_slice(&chunks[*helper_idx][start_byte..end_byte]);
+            for a in 0..clay.n {
+                for b in (a + 1)..clay.n {
+                    let lost = [a, b];
+                    let available: Vec<usize> = (0..clay.n).filter(|i| !lost.contains(i)).collect();
+                    let plan = clay.minimum_to_repair_multi(&lost, &available).unwrap();
+                    let partial = partial_helper_data(&chunks, &plan, sub_chunk_size);
+                    let recovered = clay.repair_multi(&lost, &partial, chunk_size).unwrap();
+                    assert_eq!(recovered.len(), 2);
+                    for node in lost {
+                        assert_eq!(
+                            recovered[&node], chunks[node],
+                            "Multi-repair of {:?} failed for ({}, {}, {})",
+                            lost, k, m, d
+                        );
+                    }
                 }
-                partial_data.insert(*helper_idx, helper_partial);
             }
+        }
+    }
 
-            // Repair using ONLY partial data
-            let recovered = clay.repair(lost_node, &partial_data, chunk_size).unwrap();
+    #[test]
+    fn test_repair_multi_same_y_section_saves_bandwidth() {
+        // (12, 9, 11): q = 3, nodes 0..3 share y-section 0
+        let clay = ClayCode::new(9, 3, 11).unwrap();
+        let available: Vec<usize> = (2..clay.n).collect();
+        let plan = clay.minimum_to_repair_multi(&[0, 1], &available).unwrap();
 
-            // Verify recovered chunk matches original
-            assert_eq!(
-                recovered, chunks[lost_node],
-                "Repair failed for node {}",
-                lost_node
-            );
-        }
+        let total_subchunks: usize = plan.iter().map(|(_, indices)| indices.len()).sum();
+        assert_eq!(plan[0].1.len(), 2 * clay.beta);
+        assert!(total_subchunks < clay.k * clay.sub_chunk_no);
+
+        // A single lost node plans exactly like minimum_to_repair
+        let single = clay.minimum_to_repair_multi(&[4], &[0, 1, 2, 3, 5, 6, 7, 8, 9, 10, 11]).unwrap();
+        assert_eq!(single.len(), clay.d);
+        assert!(single.iter().all(|(_, indices)| indices.len() == clay.beta));
+    }
+
+    #[test]
+    fn test_repair_multi_rejects_too_many() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        let result = clay.minimum_to_repair_multi(&[0, 1, 2], &[3, 4, 5]);
+        assert!(matches!(result, Err(ClayError::TooManyErasures { max: 2, actual: 3 })));
+        let result = clay.repair_multi(&[0, 1, 2], &HashMap::new(), 16);
+        assert!(matches!(result, Err(ClayError::TooManyErasures { max: 2, actual: 3 })));
     }
 
     #[test]
@@ -436,7 +3119,7 @@ mod tests {
             let clay = ClayCode::new(k, m, d).unwrap();
             let data_size = k * clay.sub_chunk_no * 2;
             let data: Vec<u8> = (0..data_size).map(|i| (i % 256) as u8).collect();
-            let chunks = clay.encode(&data);
+            let chunks = clay.encode(&data).unwrap();
 
             // Test decode with one erasure
             let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
@@ -465,7 +3148,7 @@ mod tests {
             let clay = ClayCode::new(k, m, d).unwrap();
             let data_size = k * clay.sub_chunk_no;
             let data: Vec<u8> = (0..data_size).map(|i| ((i * 7 + 13) % 256) as u8).collect();
-            let chunks = clay.encode(&data);
+            let chunks = clay.encode(&data).unwrap();
             let chunk_size = chunks[0].len();
             let sub_chunk_size = chunk_size / clay.sub_chunk_no;
 
@@ -473,16 +3156,7 @@ mod tests {
                 let available: Vec<usize> = (0..clay.n).filter(|&i| i != lost_node).collect();
                 let helper_info = clay.minimum_to_repair(lost_node, &available).unwrap();
 
-                let mut partial_data: HashMap<usize, Vec<u8>> = HashMap::new();
-                for (helper_idx, indices) in &helper_info {
-                    let mut helper_partial = Vec::new();
-                    for &sc_idx in indices {
-                        let start_byte = sc_idx * sub_chunk_size;
-                        let end_byte = (sc_idx + 1) * sub_chunk_size;
-                        helper_partial.extend_from_slice(&chunks[*helper_idx][start_byte..end_byte]);
-                    }
-                    partial_data.insert(*helper_idx, helper_partial);
-                }
+                let partial_data = partial_helper_data(&chunks, &helper_info, sub_chunk_size);
 
                 let recovered = clay.repair(lost_node, &partial_data, chunk_size).unwrap();
                 assert_eq!(
@@ -498,7 +3172,7 @@ mod tests {
     fn test_decode_max_erasures() {
         let clay = ClayCode::new(4, 2, 5).unwrap();
         let data: Vec<u8> = (0..256).map(|i| (i % 256) as u8).collect();
-        let chunks = clay.encode(&data);
+        let chunks = clay.encode(&data).unwrap();
 
         // Lose exactly m = 2 nodes in different patterns
         let patterns = vec![vec![0, 5], vec![0, 1], vec![4, 5], vec![1, 3]];
@@ -521,76 +3195,535 @@ mod tests {
     }
 
     #[test]
-    fn test_normalized_repair_bandwidth() {
-        let test_cases = vec![
-            ((4, 2, 5), 0.625),
-            ((9, 3, 11), 0.407),
-            ((10, 4, 13), 0.325),
-        ];
+    fn test_decode_max_erasures_three_y_sections() {
+        // q = 3: y-sections {0,1,2}, {3,4,5}, {6,7,8}, {9,10,11}. Three
+        // erasures in three sections give layers of every score 0 to 3
+        let clay = ClayCode::new(9, 3, 11).unwrap();
+        let data: Vec<u8> = (0..9000).map(|i| (i * 29 % 253) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+
+        for erasures in [vec![0, 3, 6], vec![1, 5, 9], vec![2, 7, 11], vec![4, 8, 10]] {
+            let scores = clay.layer_scores(&erasures).unwrap();
+            for score in 0..=3 {
+                assert!(scores.contains(&score), "no layer of score {} for {:?}", score, erasures);
+            }
+            assert_eq!(scores.iter().max(), Some(&3));
+
+            let available: HashMap<usize, Vec<u8>> = (0..clay.n)
+                .filter(|i| !erasures.contains(i))
+                .map(|i| (i, chunks[i].clone()))
+                .collect();
+            let decoded = clay.decode(&available, &erasures).unwrap();
+            assert_eq!(&decoded[..data.len()], &data[..], "Failed for erasures {:?}", erasures);
+
+            let rebuilt = clay.reconstruct_chunks(&available, &erasures).unwrap();
+            for &node in &erasures {
+                assert_eq!(rebuilt[&node], chunks[node], "node {} of {:?}", node, erasures);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_max_erasures_many_layers() {
+        // Erasures spread over several y-sections give several intersection
+        // score levels, each holding many layers (decoded in parallel with `rayon`)
+        let clay = ClayCode::new(10, 4, 13).unwrap();
+        let data: Vec<u8> = (0..20000).map(|i| (i * 7 % 251) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+
+        let patterns = vec![vec![0, 4, 9, 13], vec![0, 1, 2, 3], vec![2, 6, 10, 11]];
+
+        for erasures in patterns {
+            let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
+            for (i, chunk) in chunks.iter().enumerate() {
+                if !erasures.contains(&i) {
+                    available.insert(i, chunk.clone());
+                }
+            }
+            let decoded = clay.decode(&available, &erasures).unwrap();
+            assert_eq!(
+                &decoded[..data.len()],
+                &data[..],
+                "Failed for erasures {:?}",
+                erasures
+            );
+        }
+    }
+
+    #[test]
+    fn test_repair_bandwidth_for_d() {
+        let clay = ClayCode::new(10, 4, 13).unwrap();
+        assert_eq!(clay.repair_bandwidth_for_d(13).unwrap(), clay.normalized_repair_bandwidth());
+
+        // Each smaller d costs more bandwidth and matches the code built with it
+        let mut previous = 0.0;
+        for d in (11..=13).rev() {
+            let ratio = clay.repair_bandwidth_for_d(d).unwrap();
+            let built = ClayCode::new(10, 4, d).unwrap();
+            assert_eq!(ratio, built.normalized_repair_bandwidth());
+            assert_eq!(ratio, (d * built.beta) as f64 / (10 * built.sub_chunk_no) as f64);
+            assert!(ratio > previous);
+            previous = ratio;
+        }
+
+        for d in [0, 10, 14] {
+            assert!(matches!(
+                clay.repair_bandwidth_for_d(d),
+                Err(ClayError::InvalidParameters(_))
+            ));
+        }
+
+        // A single parity repairs from exactly k helpers at full cost
+        let single = ClayCode::new(4, 1, 4).unwrap();
+        assert_eq!(single.repair_bandwidth_for_d(4).unwrap(), 1.0);
+        assert!(single.repair_bandwidth_for_d(3).is_err());
+    }
+
+    #[test]
+    fn test_normalized_repair_bandwidth() {
+        let test_cases = vec![
+            ((4, 2, 5), 0.625),
+            ((9, 3, 11), 0.407),
+            ((10, 4, 13), 0.325),
+        ];
+
+        for ((k, m, d), expected) in test_cases {
+            let clay = ClayCode::new(k, m, d).unwrap();
+            let actual = clay.normalized_repair_bandwidth();
+            assert!(
+                (actual - expected).abs() < 0.01,
+                "Expected {}, got {} for ({}, {}, {})",
+                expected,
+                actual,
+                k,
+                m,
+                d
+            );
+        }
+    }
+
+    #[test]
+    fn test_random_data() {
+        use rand::Rng;
+        let mut rng = rand::thread_rng();
+
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        let data_size = clay.k * clay.sub_chunk_no * 4;
+        let data: Vec<u8> = (0..data_size).map(|_| rng.gen()).collect();
+        let chunks = clay.encode(&data).unwrap();
+
+        // Test full decode
+        let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
+        for (i, chunk) in chunks.iter().enumerate() {
+            available.insert(i, chunk.clone());
+        }
+        let decoded = clay.decode(&available, &[]).unwrap();
+        assert_eq!(&decoded[..data.len()], &data[..]);
+
+        // Test decode with erasure
+        let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
+        for (i, chunk) in chunks.iter().enumerate() {
+            if i != 2 {
+                available.insert(i, chunk.clone());
+            }
+        }
+        let decoded = clay.decode(&available, &[2]).unwrap();
+        assert_eq!(&decoded[..data.len()], &data[..]);
+    }
+
+    #[test]
+    fn test_size_overflow_is_an_error() {
+        let clay = ClayCode::new(10, 4, 13).unwrap();
+        let huge = usize::MAX / clay.sub_chunk_no * clay.sub_chunk_no;
+
+        // d * β * (chunk_size / α) = 3.25 * chunk_size
+        assert!(matches!(clay.repair_cost(0, huge), Err(ClayError::Overflow(_))));
+        assert_eq!(clay.full_decode_cost(huge), usize::MAX);
+
+        let mut helpers: HashMap<usize, Vec<u8>> =
+            (1..14).map(|i| (i, vec![0u8; 64])).collect();
+        assert!(matches!(clay.repair(0, &helpers, huge), Err(ClayError::Overflow(_))));
+        helpers.remove(&1);
+        assert!(matches!(
+            clay.repair_multi(&[0, 1], &helpers, huge),
+            Err(ClayError::Overflow(_))
+        ));
+        assert!(matches!(
+            EncodeWorkspace::new(&clay, huge / 512 * 512),
+            Err(ClayError::Overflow(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "32")]
+    fn test_size_overflow_32bit() {
+        // Sizes that are fine on 64-bit targets but exceed a 32-bit usize
+        let clay = ClayCode::new(10, 4, 13).unwrap();
+        let chunk_size = 1 << 30;
+        assert!(matches!(clay.repair_cost(0, chunk_size), Err(ClayError::Overflow(_))));
+        assert!(matches!(
+            EncodeWorkspace::new(&clay, 512 << 20),
+            Err(ClayError::Overflow(_))
+        ));
+        let helpers: HashMap<usize, Vec<u8>> = (1..14).map(|i| (i, vec![0u8; 64])).collect();
+        assert!(matches!(clay.repair(0, &helpers, chunk_size), Err(ClayError::Overflow(_))));
+    }
+
+    #[test]
+    fn test_checked_pow_overflow() {
+        // Test that checked_pow handles overflow gracefully
+        assert!(checked_pow(2, 63).is_some());
+        assert!(checked_pow(2, 64).is_none()); // Would overflow
+        assert!(checked_pow(10, 20).is_none()); // Would overflow
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        // k must be >= 1
+        assert!(ClayCode::new(0, 2, 1).is_err());
+
+        // m must be >= 1
+        assert!(ClayCode::new(4, 0, 3).is_err());
+
+        // d must be in range
+        assert!(ClayCode::new(4, 2, 4).is_err()); // d < k+1
+        assert!(ClayCode::new(4, 2, 6).is_err()); // d > k+m-1
+    }
+
+    #[test]
+    fn test_sub_packetization_limit() {
+        // q = 2: n = 32 gives t = 16 and α = 2^16, exactly the limit
+        let at_limit = ClayCode::new(30, 2, 31).unwrap();
+        assert_eq!(at_limit.sub_chunk_no, DEFAULT_MAX_SUB_CHUNK_NO);
+
+        // n = 34 gives t = 17
+        assert_eq!(
+            ClayCode::new(32, 2, 33).unwrap_err(),
+            ClayError::SubPacketizationTooLarge { alpha: 1 << 17, limit: DEFAULT_MAX_SUB_CHUNK_NO }
+        );
+        let unchecked = ClayCode::new_unchecked_alpha(32, 2, 33).unwrap();
+        assert_eq!(unchecked.sub_chunk_no, 1 << 17);
+
+        // Still an overflow when q^t does not fit at all
+        assert!(matches!(
+            ClayCode::new_unchecked_alpha(200, 2, 201),
+            Err(ClayError::Overflow(_))
+        ));
+    }
+
+    #[test]
+    fn test_single_parity() {
+        // m = 1 only allows d = k
+        assert!(ClayCode::new(4, 1, 5).is_err());
+        let clay = ClayCode::new(4, 1, 4).unwrap();
+        assert_eq!((clay.q, clay.sub_chunk_no, clay.beta), (1, 1, 1));
+
+        let data: Vec<u8> = (0..1000).map(|i| (i * 7 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+        let chunk_size = chunks[0].len();
+
+        for lost_node in 0..clay.n {
+            let available: HashMap<usize, Vec<u8>> = (0..clay.n)
+                .filter(|&i| i != lost_node)
+                .map(|i| (i, chunks[i].clone()))
+                .collect();
+            let decoded = clay.decode(&available, &[lost_node]).unwrap();
+            assert_eq!(&decoded[..data.len()], &data[..]);
+
+            let helpers: Vec<usize> = available.keys().copied().collect();
+            let plan = clay.minimum_to_repair(lost_node, &helpers).unwrap();
+            assert_eq!(plan.len(), clay.k);
+            let helper_data: HashMap<usize, Vec<u8>> =
+                plan.iter().map(|(h, _)| (*h, chunks[*h].clone())).collect();
+            assert_eq!(
+                clay.repair(lost_node, &helper_data, chunk_size).unwrap(),
+                chunks[lost_node],
+                "Repair failed for node {}",
+                lost_node
+            );
+        }
+    }
+
+    #[test]
+    fn test_wide_code_uses_gf16() {
+        // 258 shards do not fit GF(2^8); the smallest such Clay code has t = 2
+        let clay = ClayCode::new(129, 129, 257).unwrap();
+        assert_eq!(clay.sub_chunk_no, 129 * 129);
+        assert_eq!(clay.rs.symbol_size(), 2);
+
+        // k = 250, m = 10 needs at least q^t = 2^130 sub-chunks in any field
+        assert!(matches!(ClayCode::new(250, 10, 259), Err(ClayError::Overflow(_))));
+    }
+
+    #[test]
+    fn test_gf16_encode_decode_repair() {
+        let clay = ClayCode::with_backend(4, 2, 5, RsCodec::new_gf16(4, 2).unwrap()).unwrap();
+
+        let data: Vec<u8> = (0..500).map(|i| (i * 11 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+        assert!(clay.verify(&chunks).unwrap());
+
+        let available: HashMap<usize, Vec<u8>> =
+            (2..clay.n).map(|i| (i, chunks[i].clone())).collect();
+        let decoded = clay.decode(&available, &[0, 1]).unwrap();
+        assert_eq!(&decoded[..data.len()], &data[..]);
+
+        let helpers: HashMap<usize, Vec<u8>> =
+            (1..clay.n).map(|i| (i, chunks[i].clone())).collect();
+        let repaired = clay.repair_from_full_chunks(0, &helpers, chunks[0].len()).unwrap();
+        assert_eq!(repaired, chunks[0]);
+    }
+
+    #[test]
+    #[ignore = "encodes a 4 MiB stripe over 16641 layers; run with --release"]
+    fn test_wide_code_roundtrip() {
+        let clay = ClayCode::new(129, 129, 257).unwrap();
+        let data: Vec<u8> = (0..100_000).map(|i| (i % 251) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+
+        let erasures: Vec<usize> = (0..clay.m).map(|i| i * 2).collect();
+        let available: HashMap<usize, Vec<u8>> = (0..clay.n)
+            .filter(|i| !erasures.contains(i))
+            .map(|i| (i, chunks[i].clone()))
+            .collect();
+        let decoded = clay.decode(&available, &erasures).unwrap();
+        assert_eq!(&decoded[..data.len()], &data[..]);
+    }
+
+    #[test]
+    fn test_layer_major_roundtrip() {
+        let layout = SubChunkLayout::LayerMajor;
+        let clay = ClayCode::new(5, 2, 6).unwrap();
+        let data: Vec<u8> = (0..1500).map(|i| (i * 19 % 256) as u8).collect();
+        let node_major = clay.encode(&data).unwrap();
+        let chunks = clay.encode_with_layout(&data, layout).unwrap();
+        let chunk_size = chunks[0].len();
+        assert_ne!(chunks, node_major);
+        assert_eq!(
+            clay.sub_chunk_with_layout(&chunks[3], 5, layout).unwrap(),
+            clay.sub_chunk(&node_major[3], 5).unwrap()
+        );
+
+        let available: HashMap<usize, Vec<u8>> =
+            (2..clay.n).map(|i| (i, chunks[i].clone())).collect();
+        let decoded = clay.decode_with_layout(&available, &[0, 1], layout).unwrap();
+        assert_eq!(&decoded[..data.len()], &data[..]);
+
+        for lost_node in 0..clay.n {
+            let available: Vec<usize> = (0..clay.n).filter(|&i| i != lost_node).collect();
+            let helper_data: HashMap<usize, Vec<u8>> = clay
+                .minimum_to_repair(lost_node, &available)
+                .unwrap()
+                .into_iter()
+                .map(|(h, indices)| {
+                    let buf = indices
+                        .iter()
+                        .flat_map(|&sc| clay.sub_chunk_with_layout(&chunks[h], sc, layout).unwrap())
+                        .collect();
+                    (h, buf)
+                })
+                .collect();
+            let recovered =
+                clay.repair_with_layout(lost_node, &helper_data, chunk_size, layout).unwrap();
+            assert_eq!(recovered, chunks[lost_node], "Repair failed for node {}", lost_node);
+        }
+
+        // The default layout is what encode() produces
+        let default = SubChunkLayout::default();
+        assert_eq!(clay.encode_with_layout(&data, default).unwrap(), node_major);
+    }
+
+    #[test]
+    fn test_coordinates() {
+        // (5, 2, 6): q = 2, t = 4, nu = 1, so parity nodes 5 and 6 sit at
+        // internal positions 6 and 7
+        let clay = ClayCode::new(5, 2, 6).unwrap();
+        assert_eq!(clay.node_to_xy(0).unwrap(), (0, 0));
+        assert_eq!(clay.node_to_xy(4).unwrap(), (0, 2));
+        assert_eq!(clay.node_to_xy(5).unwrap(), (0, 3));
+        assert_eq!(clay.node_to_xy(6).unwrap(), (1, 3));
+        for node in 0..clay.n {
+            let (x, y) = clay.node_to_xy(node).unwrap();
+            assert_eq!(clay.xy_to_node(x, y).unwrap(), node);
+        }
+        assert!(matches!(clay.node_to_xy(7), Err(ClayError::InvalidParameters(_))));
+        assert!(matches!(clay.xy_to_node(1, 2), Err(ClayError::InvalidParameters(_))));
+        assert!(matches!(clay.xy_to_node(2, 0), Err(ClayError::InvalidParameters(_))));
+        assert!(matches!(clay.xy_to_node(0, 4), Err(ClayError::InvalidParameters(_))));
+
+        // z = 5 = 0101 in base 2
+        assert_eq!(clay.plane_vector(5).unwrap(), [0, 1, 0, 1]);
+        assert!(clay.plane_vector(clay.sub_chunk_no).is_err());
+        assert!(clay.is_unpaired(0, 0, 5).unwrap());
+        assert!(!clay.is_unpaired(1, 0, 5).unwrap());
+        assert!(clay.is_unpaired(1, 3, 5).unwrap());
+        assert!(clay.is_unpaired(2, 0, 5).is_err());
+        assert!(clay.is_unpaired(0, 0, 16).is_err());
+
+        // Each layer has exactly one unpaired vertex per y-section
+        for z in 0..clay.sub_chunk_no {
+            for y in 0..clay.t {
+                let red = (0..clay.q).filter(|&x| clay.is_unpaired(x, y, z).unwrap()).count();
+                assert_eq!(red, 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_working_set_bytes() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        // 6 internal nodes, α = 8: stripe + U buffers + k output chunks,
+        // plus 8 flags and one 2-byte shard per node and 2 scratch shards
+        assert_eq!(clay.working_set_bytes(16), 6 * 16 * 2 + 4 * 16 + 6 * (8 + 2) + 2 * 2);
+        assert!(clay.working_set_bytes(1 << 20) > clay.working_set_bytes(1 << 19));
+        assert_eq!(clay.working_set_bytes(usize::MAX), usize::MAX);
+
+        // Shortened nodes count: (5, 2, 6) works on 8 internal nodes
+        let shortened = ClayCode::new(5, 2, 6).unwrap();
+        assert!(shortened.working_set_bytes(1024) >= 8 * 1024 * 2 + 5 * 1024);
+    }
+
+    #[test]
+    fn test_display_summary() {
+        let clay = ClayCode::new(10, 4, 13).unwrap();
+        assert_eq!(
+            format!("{}", clay),
+            "Clay(n=14,k=10,m=4,d=13,q=4,α=256,β=64,overhead=1.40x,repair_bw=32.5%)"
+        );
+        let summary = ClayCode::new(4, 2, 5).unwrap().to_string();
+        for field in ["n=6", "k=4", "m=2", "d=5", "q=2", "α=8", "β=4", "overhead=1.50x"] {
+            assert!(summary.contains(field), "{} missing from {}", field, summary);
+        }
+    }
+
+    #[test]
+    fn test_is_recoverable() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        assert!(clay.is_recoverable(&[0, 1, 2, 3, 4, 5]));
+        assert!(clay.is_recoverable(&[5, 1, 4, 2]));
+        assert!(!clay.is_recoverable(&[0, 1, 2]));
+        assert!(!clay.is_recoverable(&[]));
+        // Duplicates count once
+        assert!(!clay.is_recoverable(&[0, 0, 1, 1, 2]));
+        assert!(clay.is_recoverable(&[0, 0, 1, 2, 3]));
+        // Out-of-range indices are never recoverable
+        assert!(!clay.is_recoverable(&[0, 1, 2, 3, 6]));
 
-        for ((k, m, d), expected) in test_cases {
-            let clay = ClayCode::new(k, m, d).unwrap();
-            let actual = clay.normalized_repair_bandwidth();
-            assert!(
-                (actual - expected).abs() < 0.01,
-                "Expected {}, got {} for ({}, {}, {})",
-                expected,
-                actual,
-                k,
-                m,
-                d
+        // Agrees with decode for every subset
+        let data: Vec<u8> = (0..300).map(|i| (i * 3 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+        for mask in 0u32..(1 << clay.n) {
+            let available: Vec<usize> = (0..clay.n).filter(|&i| mask & (1 << i) != 0).collect();
+            let erasures: Vec<usize> = (0..clay.n).filter(|&i| mask & (1 << i) == 0).collect();
+            let map: HashMap<usize, Vec<u8>> =
+                available.iter().map(|&i| (i, chunks[i].clone())).collect();
+            assert_eq!(
+                clay.is_recoverable(&available),
+                clay.decode(&map, &erasures).is_ok(),
+                "available {:?}",
+                available
             );
         }
     }
 
     #[test]
-    fn test_random_data() {
-        use rand::Rng;
-        let mut rng = rand::thread_rng();
-
-        let clay = ClayCode::new(4, 2, 5).unwrap();
-        let data_size = clay.k * clay.sub_chunk_no * 4;
-        let data: Vec<u8> = (0..data_size).map(|_| rng.gen()).collect();
-        let chunks = clay.encode(&data);
+    fn test_with_backend() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
 
-        // Test full decode
-        let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
-        for (i, chunk) in chunks.iter().enumerate() {
-            available.insert(i, chunk.clone());
+        #[derive(Debug)]
+        struct Counting {
+            inner: RsCodec,
+            reconstructs: Arc<AtomicUsize>,
         }
-        let decoded = clay.decode(&available, &[]).unwrap();
-        assert_eq!(&decoded[..data.len()], &data[..]);
 
-        // Test decode with erasure
-        let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
-        for (i, chunk) in chunks.iter().enumerate() {
-            if i != 2 {
-                available.insert(i, chunk.clone());
+        impl RsBackend for Counting {
+            fn data_shard_count(&self) -> usize {
+                self.inner.data_shard_count()
+            }
+            fn parity_shard_count(&self) -> usize {
+                self.inner.parity_shard_count()
+            }
+            fn encode(&self, shards: &mut [Vec<u8>]) -> Result<(), ClayError> {
+                self.inner.encode(shards)
+            }
+            fn reconstruct(&self, shards: &mut [(Vec<u8>, bool)]) -> Result<(), ClayError> {
+                self.reconstructs.fetch_add(1, Ordering::Relaxed);
+                self.inner.reconstruct(shards)
             }
         }
-        let decoded = clay.decode(&available, &[2]).unwrap();
+
+        let reconstructs = Arc::new(AtomicUsize::new(0));
+        let backend = Counting {
+            inner: RsCodec::new(4, 2).unwrap(),
+            reconstructs: Arc::clone(&reconstructs),
+        };
+        let clay = ClayCode::with_backend(4, 2, 5, backend).unwrap();
+        let default = ClayCode::new(4, 2, 5).unwrap();
+
+        let data: Vec<u8> = (0..700).map(|i| (i * 7 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+        assert_eq!(chunks, default.encode(&data).unwrap());
+        // The default verify re-encodes through the backend
+        assert!(clay.verify(&chunks).unwrap());
+        let available: HashMap<usize, Vec<u8>> =
+            (2..clay.n).map(|i| (i, chunks[i].clone())).collect();
+        let decoded = clay.decode(&available, &[0, 1]).unwrap();
         assert_eq!(&decoded[..data.len()], &data[..]);
-    }
+        let after_decode = reconstructs.load(Ordering::Relaxed);
+        assert!(after_decode > 0);
 
-    #[test]
-    fn test_checked_pow_overflow() {
-        // Test that checked_pow handles overflow gracefully
-        assert!(checked_pow(2, 63).is_some());
-        assert!(checked_pow(2, 64).is_none()); // Would overflow
-        assert!(checked_pow(10, 20).is_none()); // Would overflow
+        let chunk_size = chunks[0].len();
+        let helpers: HashMap<usize, Vec<u8>> = clay
+            .minimum_to_repair(3, &[0, 1, 2, 4, 5])
+            .unwrap()
+            .into_iter()
+            .map(|(h, sc)| {
+                let buf = sc.iter().flat_map(|&s| clay.sub_chunk(&chunks[h], s).unwrap());
+                (h, buf.copied().collect())
+            })
+            .collect();
+        assert_eq!(clay.repair(3, &helpers, chunk_size).unwrap(), chunks[3]);
+
+        assert!(reconstructs.load(Ordering::Relaxed) > after_decode);
+
+        // Shard counts must match k + nu and m
+        let err = ClayCode::with_backend(4, 2, 5, RsCodec::new(5, 2).unwrap());
+        assert!(matches!(err, Err(ClayError::InvalidParameters(_))));
+        // (5, 2, 6) shortens by nu = 1, so its layers carry 6 data shards
+        assert!(ClayCode::with_backend(5, 2, 6, RsCodec::new(6, 2).unwrap()).is_ok());
     }
 
     #[test]
-    fn test_invalid_parameters() {
-        // k must be >= 1
-        assert!(ClayCode::new(0, 2, 1).is_err());
+    fn test_with_gamma() {
+        let default = ClayCode::new(4, 2, 5).unwrap();
+        assert_eq!(default.gamma, 2);
 
-        // m must be >= 1
-        assert!(ClayCode::new(4, 0, 3).is_err());
+        let clay = ClayCode::with_gamma(4, 2, 5, 0x1D).unwrap();
+        assert_eq!(clay.gamma, 0x1D);
 
-        // d must be in range
-        assert!(ClayCode::new(4, 2, 4).is_err()); // d < k+1
-        assert!(ClayCode::new(4, 2, 6).is_err()); // d > k+m-1
+        let data: Vec<u8> = (0..300).map(|i| (i * 7 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+        assert_ne!(chunks[clay.k], default.encode(&data).unwrap()[clay.k]);
+        assert!(clay.verify(&chunks).unwrap());
+
+        let available: HashMap<usize, Vec<u8>> =
+            (2..clay.n).map(|i| (i, chunks[i].clone())).collect();
+        let decoded = clay.decode(&available, &[0, 1]).unwrap();
+        assert_eq!(&decoded[..data.len()], &data[..]);
+
+        let helpers: HashMap<usize, Vec<u8>> =
+            (0..clay.n - 1).map(|i| (i, chunks[i].clone())).collect();
+        let repaired = clay.repair_from_full_chunks(clay.n - 1, &helpers, chunks[0].len()).unwrap();
+        assert_eq!(repaired, chunks[clay.n - 1]);
+
+        assert!(matches!(
+            ClayCode::with_gamma(4, 2, 5, 0),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            ClayCode::with_gamma(4, 2, 5, 1),
+            Err(ClayError::InvalidParameters(_))
+        ));
     }
 
     #[test]
@@ -624,6 +3757,26 @@ mod tests {
         assert_eq!(clay_default2.sub_chunk_no, clay_explicit2.sub_chunk_no);
     }
 
+    #[test]
+    fn test_from_n_k() {
+        let clay = ClayCode::from_n_k(14, 10, 13).unwrap();
+        assert_eq!(clay.params(), ClayCode::new(10, 4, 13).unwrap().params());
+        assert_eq!(
+            ClayCode::from_n_k_default(6, 4).unwrap().params(),
+            ClayCode::new_default(4, 2).unwrap().params()
+        );
+
+        for (n, k) in [(4, 4), (3, 4), (0, 0)] {
+            assert!(matches!(
+                ClayCode::from_n_k(n, k, 5),
+                Err(ClayError::InvalidParameters(_))
+            ));
+            assert!(ClayCode::from_n_k_default(n, k).is_err());
+        }
+        // d is still validated against k and m
+        assert!(ClayCode::from_n_k(6, 4, 6).is_err());
+    }
+
     #[test]
     fn test_decode_empty_available_with_erasures() {
         let clay = ClayCode::new(4, 2, 5).unwrap();
@@ -636,13 +3789,473 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_decode_owned_matches_decode() {
+        let clay = ClayCode::new(10, 4, 13).unwrap();
+        let data: Vec<u8> = (0..10000).map(|i| (i % 253) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+
+        let erasures = [1, 6, 11];
+        let available: HashMap<usize, Vec<u8>> = (0..clay.n)
+            .filter(|i| !erasures.contains(i))
+            .map(|i| (i, chunks[i].clone()))
+            .collect();
+
+        let borrowed = clay.decode(&available, &erasures).unwrap();
+        let owned = clay.decode_owned(available, &erasures).unwrap();
+        assert_eq!(owned, borrowed);
+        assert_eq!(&owned[..data.len()], &data[..]);
+
+        // Validation is shared with decode()
+        assert!(matches!(
+            clay.decode_owned(HashMap::new(), &[0]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        assert!(clay.decode_owned(HashMap::new(), &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_decode_refs_matches_decode() {
+        let clay = ClayCode::new(10, 4, 13).unwrap();
+        let data: Vec<u8> = (0..10000).map(|i| (i % 251) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+
+        // All chunks live in one arena and are lent as slices
+        let chunk_size = chunks[0].len();
+        let arena = chunks.concat();
+        let slice = |i: usize| &arena[i * chunk_size..(i + 1) * chunk_size];
+
+        for erasures in [vec![], vec![3], vec![0, 12], vec![1, 6, 11, 13]] {
+            let owned: HashMap<usize, Vec<u8>> = (0..clay.n)
+                .filter(|i| !erasures.contains(i))
+                .map(|i| (i, chunks[i].clone()))
+                .collect();
+            let refs: HashMap<usize, &[u8]> =
+                (0..clay.n).filter(|i| !erasures.contains(i)).map(|i| (i, slice(i))).collect();
+
+            let decoded = clay.decode_refs(&refs, &erasures).unwrap();
+            assert_eq!(decoded, clay.decode(&owned, &erasures).unwrap());
+            assert_eq!(&decoded[..data.len()], &data[..]);
+        }
+
+        // Validation is shared with decode()
+        let mut refs: HashMap<usize, &[u8]> = (1..clay.n).map(|i| (i, slice(i))).collect();
+        refs.insert(2, &arena[..chunk_size - clay.sub_chunk_no]);
+        assert!(matches!(
+            clay.decode_refs(&refs, &[0]),
+            Err(ClayError::InconsistentChunkSizes { .. })
+        ));
+        assert!(clay.decode_refs(&HashMap::new(), &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_decode_from_pairs_rejects_duplicates() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        let data: Vec<u8> = (0..300).map(|i| (i * 11 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+
+        let mut pairs: Vec<(usize, Vec<u8>)> =
+            [5, 1, 3, 2].iter().map(|&i| (i, chunks[i].clone())).collect();
+        let decoded = clay.decode_from_pairs(&pairs, &[]).unwrap();
+        assert_eq!(&decoded[..data.len()], &data[..]);
+
+        // A second source also claims node 3, with different bytes
+        pairs.push((3, chunks[4].clone()));
+        match clay.decode_from_pairs(&pairs, &[]) {
+            Err(ClayError::InvalidParameters(msg)) => assert!(msg.contains("3"), "{}", msg),
+            other => panic!("expected InvalidParameters, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_decode_nodes() {
+        let clay = ClayCode::new(6, 3, 8).unwrap();
+        let data: Vec<u8> = (0..4000).map(|i| (i * 29 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+        let without = |erasures: &[usize]| -> HashMap<usize, Vec<u8>> {
+            (0..clay.n)
+                .filter(|i| !erasures.contains(i))
+                .map(|i| (i, chunks[i].clone()))
+                .collect()
+        };
+
+        // (erasures, wanted): repair path, repair blocked by a y-section
+        // neighbour, several missing, and nothing missing
+        let cases: Vec<(Vec<usize>, Vec<usize>)> = vec![
+            (vec![2], vec![2]),
+            (vec![2, 7], vec![2, 5]),
+            (vec![0, 2], vec![2]),
+            (vec![1, 4, 7], vec![1, 4]),
+            (vec![3], vec![0, 8]),
+        ];
+        for (erasures, wanted) in cases {
+            let nodes = clay.decode_nodes(&without(&erasures), &erasures, &wanted).unwrap();
+            assert_eq!(nodes.len(), wanted.len());
+            for node in &wanted {
+                assert_eq!(nodes[node], chunks[*node], "Node {} for {:?}", node, erasures);
+            }
+        }
+
+        assert!(matches!(
+            clay.decode_nodes(&without(&[0]), &[0], &[9]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_range() {
+        let clay = ClayCode::new(6, 3, 8).unwrap();
+        let data: Vec<u8> = (0..4000).map(|i| (i * 31 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+        let chunk_size = chunks[0].len();
+        let without = |erasures: &[usize]| -> HashMap<usize, Vec<u8>> {
+            (0..clay.n)
+                .filter(|i| !erasures.contains(i))
+                .map(|i| (i, chunks[i].clone()))
+                .collect()
+        };
+
+        // Nothing erased, one erased data node, two in the same y-section,
+        // and data plus parity erased
+        let erasure_sets: Vec<Vec<usize>> = vec![vec![], vec![1], vec![0, 2], vec![4, 7, 8]];
+        let ranges = [
+            0..1,
+            10..90,
+            chunk_size - 5..chunk_size + 5,
+            chunk_size + 100..3 * chunk_size + 7,
+            0..6 * chunk_size,
+            500..500,
+        ];
+        for erasures in &erasure_sets {
+            let available = without(erasures);
+            let full = clay.decode(&available, erasures).unwrap();
+            for range in ranges.iter() {
+                assert_eq!(
+                    clay.read_range(&available, erasures, range.clone()).unwrap(),
+                    &full[range.clone()],
+                    "Range {:?} with erasures {:?}",
+                    range,
+                    erasures
+                );
+            }
+        }
+
+        assert!(matches!(
+            clay.read_range(&without(&[1]), &[1], 0..6 * chunk_size + 1),
+            Err(ClayError::InvalidParameters(_))
+        ));
+
+        // Shortened code, with parity indices shifted internally
+        let clay = ClayCode::new(5, 3, 7).unwrap();
+        let chunks = clay.encode(&data).unwrap();
+        let available: HashMap<usize, Vec<u8>> =
+            [0, 2, 4, 5, 7].iter().map(|&i| (i, chunks[i].clone())).collect();
+        let full = clay.decode(&available, &[1, 3, 6]).unwrap();
+        let range = 100..chunks[0].len() * 4 - 3;
+        assert_eq!(clay.read_range(&available, &[1, 3, 6], range.clone()).unwrap(), &full[range]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_decode_to_writer() {
+        struct FailingWriter;
+        impl Write for FailingWriter {
+            fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "closed"))
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let clay = ClayCode::new(6, 3, 8).unwrap();
+        let data: Vec<u8> = (0..5000).map(|i| (i * 11 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+
+        let erasures = [0, 7];
+        let available: HashMap<usize, Vec<u8>> = (0..clay.n)
+            .filter(|i| !erasures.contains(i))
+            .map(|i| (i, chunks[i].clone()))
+            .collect();
+
+        let mut out = Vec::new();
+        let written = clay.decode_to_writer(&available, &erasures, &mut out).unwrap();
+        assert_eq!(written, clay.k * chunks[0].len());
+        assert_eq!(out, clay.decode(&available, &erasures).unwrap());
+        assert_eq!(&out[..data.len()], &data[..]);
+
+        assert!(matches!(
+            clay.decode_to_writer(&available, &erasures, &mut FailingWriter),
+            Err(ClayError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_shards_fills_erasures() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        let data: Vec<u8> = (0..200).map(|i| (i * 3 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+
+        for erasures in [vec![], vec![0], vec![5], vec![1, 4], vec![2, 3]] {
+            let mut shards: Vec<Option<Vec<u8>>> = chunks.iter().cloned().map(Some).collect();
+            for &e in &erasures {
+                shards[e] = None;
+            }
+            clay.decode_shards(&mut shards).unwrap();
+            for (i, shard) in shards.iter().enumerate() {
+                assert_eq!(
+                    shard.as_ref(),
+                    Some(&chunks[i]),
+                    "Shard {} wrong for erasures {:?}",
+                    i,
+                    erasures
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_shards_invalid_input() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        let chunks = clay.encode(b"decode_shards input validation").unwrap();
+
+        // Wrong number of slots
+        let mut short: Vec<Option<Vec<u8>>> = chunks[..5].iter().cloned().map(Some).collect();
+        assert!(matches!(
+            clay.decode_shards(&mut short),
+            Err(ClayError::InvalidParameters(_))
+        ));
+
+        // More than m erased
+        let mut shards: Vec<Option<Vec<u8>>> = chunks.iter().cloned().map(Some).collect();
+        shards[0] = None;
+        shards[1] = None;
+        shards[2] = None;
+        assert!(matches!(
+            clay.decode_shards(&mut shards),
+            Err(ClayError::TooManyErasures { max: 2, actual: 3 })
+        ));
+    }
+
+    #[cfg(feature = "profile")]
+    #[test]
+    fn test_encode_decode_profiled() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        let data: Vec<u8> = (0..4096).map(|i| (i * 29 % 256) as u8).collect();
+
+        let (chunks, timings) = clay.encode_profiled(&data).unwrap();
+        assert_eq!(chunks, clay.encode(&data).unwrap());
+        assert_eq!(timings.layers_rs_decoded + timings.layers_transform_only, clay.sub_chunk_no);
+        assert!(timings.layers_rs_decoded > 0);
+
+        let mut available: HashMap<usize, Vec<u8>> = chunks.iter().cloned().enumerate().collect();
+
+        // Only parity lost: the data chunks are read as they are
+        available.remove(&5);
+        let (decoded, timings) = clay.decode_profiled(&available, &[]).unwrap();
+        assert_eq!(&decoded[..data.len()], &data[..]);
+        assert_eq!(timings, PhaseTimings::default());
+
+        // Two data nodes of one y-section lost: every layer is counted once
+        available.insert(5, chunks[5].clone());
+        available.remove(&0);
+        available.remove(&1);
+        let (decoded, timings) = clay.decode_profiled(&available, &[]).unwrap();
+        assert_eq!(&decoded[..data.len()], &data[..]);
+        assert_eq!(timings.layers_rs_decoded + timings.layers_transform_only, clay.sub_chunk_no);
+        assert!(timings.layers_rs_decoded > 0);
+    }
+
+    #[test]
+    fn test_companion_table() {
+        let clay = ClayCode::new(5, 3, 7).unwrap();
+        let (q, t, alpha) = (clay.q, clay.t, clay.sub_chunk_no);
+        let table = clay.companion_table();
+        assert_eq!(table.len(), t * q * alpha);
+
+        for z in 0..alpha {
+            let plane = clay.plane_vector(z).unwrap();
+            for (y, &z_y) in plane.iter().enumerate() {
+                for x in 0..q {
+                    let z_sw = table[(y * q + x) * alpha + z];
+                    let companion = clay.plane_vector(z_sw).unwrap();
+                    // The companion (z_y, y, z_sw) pairs back with (x, y, z)
+                    assert_eq!(companion[y], x);
+                    assert_eq!(table[(y * q + z_y) * alpha + z_sw], z);
+                    if x == z_y {
+                        assert_eq!(z_sw, z);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_encode_with_padding() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        let chunk_of = |chunks: &[Vec<u8>], erasures: &[usize]| {
+            let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
+            for (i, chunk) in chunks.iter().enumerate() {
+                if !erasures.contains(&i) {
+                    available.insert(i, chunk.clone());
+                }
+            }
+            available
+        };
+
+        let data: Vec<u8> = (0..50).map(|i| (i * 7 + 1) as u8).collect();
+        assert_eq!(
+            clay.encode_with_padding(&data, PaddingScheme::Zero).unwrap(),
+            clay.encode(&data).unwrap()
+        );
+
+        // Byte padding fills the tail of the last data chunk
+        let chunks = clay.encode_with_padding(&data, PaddingScheme::Byte(0xaa)).unwrap();
+        let chunk_size = chunks[0].len();
+        assert_eq!(chunk_size * 4, 64);
+        assert!(chunks[3][50 - 3 * chunk_size..].iter().all(|&b| b == 0xaa));
+        let decoded = clay.decode(&chunk_of(&chunks, &[0, 3]), &[0, 3]).unwrap();
+        assert_eq!(&decoded[..50], &data[..]);
+        assert!(decoded[50..].iter().all(|&b| b == 0xaa));
+
+        // PKCS#7 always pads, so a stripe-aligned input gains a whole stripe
+        for len in [0usize, 1, 50, 63, 64, 100] {
+            let data: Vec<u8> = (0..len).map(|i| (i * 13 % 256) as u8).collect();
+            let chunks = clay.encode_with_padding(&data, PaddingScheme::Pkcs7).unwrap();
+            assert!(chunks[0].len() * 4 > len, "len {}", len);
+            for erasures in [vec![], vec![1], vec![2, 5]] {
+                let available = chunk_of(&chunks, &erasures);
+                let decoded = clay.decode_unpad(&available, &erasures).unwrap();
+                assert_eq!(decoded, data, "len {} erasures {:?}", len, erasures);
+            }
+        }
+
+        // Zero padding is not valid PKCS#7
+        let chunks = clay.encode(&data).unwrap();
+        assert!(matches!(
+            clay.decode_unpad(&chunk_of(&chunks, &[]), &[]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+
+        // A wide stripe needs more padding than PKCS#7 can describe
+        let wide = ClayCode::new(10, 4, 13).unwrap();
+        assert!(matches!(
+            wide.encode_with_padding(&[1, 2, 3], PaddingScheme::Pkcs7),
+            Err(ClayError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn test_encode_decode_object() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        let chunk_size = 2 * clay.sub_chunk_no;
+        let stripe_size = clay.k * chunk_size;
+
+        let lens = [0usize, 1, stripe_size - 1, stripe_size, stripe_size + 1, 3 * stripe_size + 5];
+        for len in lens {
+            let data: Vec<u8> = (0..len).map(|i| (i * 19 % 256) as u8).collect();
+            let object = clay.encode_object(&data, chunk_size).unwrap();
+            let stripe_count = (len + stripe_size - 1) / stripe_size;
+            assert_eq!(
+                object.manifest,
+                ObjectManifest { len, chunk_size, stripe_count },
+                "len {}",
+                len
+            );
+            assert_eq!(object.stripes.len(), stripe_count);
+            assert!(object.stripes.iter().flatten().all(|chunk| chunk.len() == chunk_size));
+
+            // Each stripe loses a different pair of nodes
+            let survivors: Vec<HashMap<usize, Vec<u8>>> = object
+                .stripes
+                .iter()
+                .enumerate()
+                .map(|(s, chunks)| {
+                    let lost = [s % clay.n, (s + 2) % clay.n];
+                    (0..clay.n)
+                        .filter(|i| !lost.contains(i))
+                        .map(|i| (i, chunks[i].clone()))
+                        .collect()
+                })
+                .collect();
+            let decoded = clay.decode_object(&object.manifest, &survivors).unwrap();
+            assert_eq!(decoded, data, "len {}", len);
+        }
+
+        // A stripe-aligned object needs no stripe beyond its data
+        let data = vec![0xA5u8; stripe_size];
+        let object = clay.encode_object(&data, chunk_size).unwrap();
+        assert_eq!(object.stripes.len(), 1);
+        assert_eq!(&object.stripes[0][..clay.k].concat(), &data);
+        let survivors: Vec<HashMap<usize, Vec<u8>>> =
+            vec![(2..clay.n).map(|i| (i, object.stripes[0][i].clone())).collect()];
+        assert_eq!(clay.decode_object(&object.manifest, &survivors).unwrap(), data);
+
+        // Stripes that do not match the manifest
+        let manifest = object.manifest;
+        assert!(matches!(
+            clay.decode_object(&manifest, &[]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        let longer = ObjectManifest { len: stripe_size + 1, ..manifest };
+        assert!(clay.decode_object(&longer, &survivors).is_err());
+        let wider = ObjectManifest { chunk_size: 2 * chunk_size, ..manifest };
+        assert!(clay.decode_object(&wider, &survivors).is_err());
+
+        assert!(matches!(
+            clay.encode_object(&[1, 2, 3], chunk_size + 1),
+            Err(ClayError::InvalidChunkSize { .. })
+        ));
+        assert!(matches!(
+            clay.encode_object(&[1, 2, 3], clay.sub_chunk_no),
+            Err(ClayError::ChunkTooSmall { .. })
+        ));
+        let empty = clay.encode_object(&[], chunk_size).unwrap();
+        assert!(empty.stripes.is_empty());
+        assert!(clay.decode_object(&empty.manifest, &[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_encode_decode_exact_length() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+
+        for len in [0usize, 1, 55, 56, 57, 64, 1000] {
+            let data: Vec<u8> = (0..len).map(|i| (i * 11 % 256) as u8).collect();
+            let chunks = clay.encode_with_length(&data).unwrap();
+
+            // Erasures on node 0 lose the header, which must be reconstructed
+            for erasures in [vec![], vec![0], vec![0, 1], vec![0, 5]] {
+                let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
+                for (i, chunk) in chunks.iter().enumerate() {
+                    if !erasures.contains(&i) {
+                        available.insert(i, chunk.clone());
+                    }
+                }
+                let decoded = clay.decode_exact(&available, &erasures).unwrap();
+                assert_eq!(decoded, data, "len {} erasures {:?}", len, erasures);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_exact_rejects_bad_header() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        // Plain encode: the first 8 bytes are data, not a length header
+        let chunks = clay.encode(&[0xFFu8; 16]).unwrap();
+        let available: HashMap<usize, Vec<u8>> = chunks.into_iter().enumerate().collect();
+        assert!(matches!(
+            clay.decode_exact(&available, &[]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+    }
+
     // ============ Adversarial Tests ============
 
     #[test]
     fn test_decode_too_many_erasures() {
         let clay = ClayCode::new(4, 2, 5).unwrap();
         let data: Vec<u8> = (0..128).map(|i| (i % 256) as u8).collect();
-        let chunks = clay.encode(&data);
+        let chunks = clay.encode(&data).unwrap();
 
         // Try to decode with 3 erasures (more than m=2)
         let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
@@ -664,7 +4277,7 @@ mod tests {
     fn test_decode_inconsistent_chunk_sizes() {
         let clay = ClayCode::new(4, 2, 5).unwrap();
         let data: Vec<u8> = (0..128).map(|i| (i % 256) as u8).collect();
-        let chunks = clay.encode(&data);
+        let chunks = clay.encode(&data).unwrap();
 
         let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
         for (i, chunk) in chunks.iter().enumerate() {
@@ -694,7 +4307,7 @@ mod tests {
     fn test_decode_invalid_chunk_index() {
         let clay = ClayCode::new(4, 2, 5).unwrap();
         let data: Vec<u8> = (0..128).collect();
-        let chunks = clay.encode(&data);
+        let chunks = clay.encode(&data).unwrap();
 
         let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
         for (i, chunk) in chunks.iter().enumerate() {
@@ -715,7 +4328,7 @@ mod tests {
     fn test_decode_invalid_erasure_index() {
         let clay = ClayCode::new(4, 2, 5).unwrap();
         let data: Vec<u8> = (0..128).collect();
-        let chunks = clay.encode(&data);
+        let chunks = clay.encode(&data).unwrap();
 
         let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
         for (i, chunk) in chunks.iter().enumerate() {
@@ -737,7 +4350,7 @@ mod tests {
     fn test_decode_available_erasure_overlap() {
         let clay = ClayCode::new(4, 2, 5).unwrap();
         let data: Vec<u8> = (0..128).collect();
-        let chunks = clay.encode(&data);
+        let chunks = clay.encode(&data).unwrap();
 
         // Include node 0 in both available AND erasures - should be an error
         let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
@@ -753,11 +4366,164 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_decode_all() {
+        let clay = ClayCode::new(5, 3, 7).unwrap();
+        let data: Vec<u8> = (0..777).map(|i| (i % 256) as u8).collect();
+        let mut chunks = clay.encode(&data).unwrap();
+
+        let decoded = clay.decode_all(&chunks).unwrap();
+        assert_eq!(&decoded[..data.len()], &data[..]);
+        let available: HashMap<usize, Vec<u8>> = chunks.iter().cloned().enumerate().collect();
+        assert_eq!(decoded, clay.decode(&available, &[]).unwrap());
+
+        assert!(matches!(
+            clay.decode_all(&chunks[..7]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        chunks[6].truncate(clay.sub_chunk_no);
+        assert!(matches!(
+            clay.decode_all(&chunks),
+            Err(ClayError::InconsistentChunkSizes { mismatched_idx: 6, .. })
+        ));
+    }
+
+    #[test]
+    fn test_reconstruct_chunks() {
+        // Shortened code, so internal and external indices differ
+        let clay = ClayCode::new(5, 3, 7).unwrap();
+        let data: Vec<u8> = (0..5000).map(|i| (i * 11 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+
+        for missing in [vec![6], vec![0, 7], vec![2, 3, 5], vec![]] {
+            let available: HashMap<usize, Vec<u8>> = (0..clay.n)
+                .filter(|i| !missing.contains(i))
+                .map(|i| (i, chunks[i].clone()))
+                .collect();
+
+            let rebuilt = clay.reconstruct_chunks(&available, &missing).unwrap();
+            assert_eq!(rebuilt.len(), missing.len());
+            for &node in &missing {
+                assert_eq!(rebuilt[&node], chunks[node], "node {} of {:?}", node, missing);
+            }
+
+            // Undeclared losses are rebuilt too
+            assert_eq!(clay.reconstruct_chunks(&available, &[]).unwrap(), rebuilt);
+        }
+
+        let available: HashMap<usize, Vec<u8>> =
+            (4..clay.n).map(|i| (i, chunks[i].clone())).collect();
+        assert!(matches!(
+            clay.reconstruct_chunks(&available, &[]),
+            Err(ClayError::TooManyErasures { max: 3, actual: 4 })
+        ));
+    }
+
+    #[test]
+    fn test_decode_and_heal() {
+        let clay = ClayCode::new(5, 3, 7).unwrap();
+        let data: Vec<u8> = (0..5000).map(|i| (i * 13 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+
+        for missing in [vec![6], vec![0, 7], vec![2, 3, 5], vec![]] {
+            let available: HashMap<usize, Vec<u8>> = (0..clay.n)
+                .filter(|i| !missing.contains(i))
+                .map(|i| (i, chunks[i].clone()))
+                .collect();
+
+            let (decoded, healed) = clay.decode_and_heal(&available, &[]).unwrap();
+            assert_eq!(decoded, clay.decode(&available, &missing).unwrap());
+            assert_eq!(&decoded[..data.len()], &data[..]);
+            assert_eq!(healed, clay.reconstruct_chunks(&available, &missing).unwrap());
+        }
+
+        let available: HashMap<usize, Vec<u8>> =
+            (4..clay.n).map(|i| (i, chunks[i].clone())).collect();
+        assert!(matches!(
+            clay.decode_and_heal(&available, &[]),
+            Err(ClayError::TooManyErasures { max: 3, actual: 4 })
+        ));
+    }
+
+    #[test]
+    fn test_sub_chunks() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        let chunk: Vec<u8> = (0..48).collect();
+
+        let subs: Vec<&[u8]> = clay.sub_chunks(&chunk).unwrap().collect();
+        assert_eq!(subs.len(), clay.sub_chunk_no);
+        assert!(subs.iter().all(|s| s.len() == 6));
+        assert_eq!(subs.concat(), chunk);
+        for (z, sub) in subs.iter().enumerate() {
+            assert_eq!(clay.sub_chunk(&chunk, z).unwrap(), *sub);
+        }
+
+        assert!(matches!(
+            clay.sub_chunk(&chunk, 8),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        for bad in [&chunk[..0], &chunk[..47]] {
+            assert!(matches!(
+                clay.sub_chunks(bad).map(|it| it.count()),
+                Err(ClayError::InvalidChunkSize { expected: 8, .. })
+            ));
+            assert!(clay.sub_chunk(bad, 0).is_err());
+        }
+    }
+
+    #[test]
+    fn test_decode_infers_missing_erasures() {
+        let clay = ClayCode::new(4, 2, 5).unwrap();
+        let data: Vec<u8> = (0..300).map(|i| (i * 3 % 256) as u8).collect();
+        let chunks = clay.encode(&data).unwrap();
+        let without = |missing: &[usize]| -> HashMap<usize, Vec<u8>> {
+            (0..clay.n)
+                .filter(|i| !missing.contains(i))
+                .map(|i| (i, chunks[i].clone()))
+                .collect()
+        };
+
+        // Whatever survived, with none, some or all of the losses declared
+        for (missing, declared) in [
+            (vec![], vec![]),
+            (vec![2], vec![]),
+            (vec![1, 4], vec![]),
+            (vec![1, 4], vec![4]),
+            (vec![0, 5], vec![0, 5]),
+        ] {
+            let available = without(&missing);
+            let decoded = clay.decode(&available, &declared).unwrap();
+            assert_eq!(&decoded[..data.len()], &data[..], "missing {:?}", missing);
+            assert_eq!(clay.decode_owned(available.clone(), &declared).unwrap(), decoded);
+            let refs: HashMap<usize, &[u8]> =
+                available.iter().map(|(&i, c)| (i, c.as_slice())).collect();
+            assert_eq!(clay.decode_refs(&refs, &declared).unwrap(), decoded);
+
+            let range = 10..decoded.len() - 10;
+            assert_eq!(clay.read_range(&available, &declared, range.clone()).unwrap(), &decoded[range]);
+            let nodes = clay.decode_nodes(&available, &declared, &[0, 1, 2, 5]).unwrap();
+            for node in [0, 1, 2, 5] {
+                assert_eq!(nodes[&node], chunks[node], "missing {:?}", missing);
+            }
+        }
+
+        // Fewer than k chunks
+        assert!(matches!(
+            clay.decode(&without(&[0, 1, 2]), &[]),
+            Err(ClayError::TooManyErasures { max: 2, actual: 3 })
+        ));
+
+        // The strict form still insists on an exact erasure list
+        assert!(clay.decode_strict(&without(&[2]), &[]).is_err());
+        let decoded = clay.decode_strict(&without(&[2]), &[2]).unwrap();
+        assert_eq!(&decoded[..data.len()], &data[..]);
+    }
+
     #[test]
     fn test_decode_wrong_available_count() {
         let clay = ClayCode::new(4, 2, 5).unwrap();
         let data: Vec<u8> = (0..128).collect();
-        let chunks = clay.encode(&data);
+        let chunks = clay.encode(&data).unwrap();
 
         // Provide too few chunks for the declared erasures
         let mut available: HashMap<usize, Vec<u8>> = HashMap::new();
@@ -768,11 +4534,26 @@ mod tests {
         }
 
         // Say only node 0 is erased, but we only have 4 chunks (should have 5)
-        let result = clay.decode(&available, &[0]);
+        let result = clay.decode_strict(&available, &[0]);
         assert!(
             matches!(result, Err(ClayError::InvalidParameters(ref msg)) if msg.contains("Expected")),
             "Expected InvalidParameters error for wrong count, got {:?}",
             result
         );
     }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_reed_solomon_error_source() {
+        use std::error::Error;
+
+        let err: ClayError = RsError::TooFewShardsPresent.into();
+        assert!(matches!(err, ClayError::ReedSolomon(RsError::TooFewShardsPresent)));
+        assert!(err.to_string().starts_with("Reed-Solomon error: "));
+        let source = err.source().expect("RS errors expose their source");
+        assert_eq!(source.to_string(), RsError::TooFewShardsPresent.to_string());
+
+        // Contextual errors have no underlying cause
+        assert!(ClayError::ReconstructionFailed("context".into()).source().is_none());
+    }
 }
@@ -0,0 +1,22 @@
+//! Persistable code parameters
+//!
+//! Only the free parameters (k, m, d) are stored. Everything else (q, t,
+//! nu, α, β) is derived when the code is rebuilt, so stored parameters stay
+//! consistent even if the derivation changes.
+
+/// The free parameters of a Clay code
+///
+/// With the `serde` feature this implements `Serialize` and `Deserialize`.
+/// Rebuild the code with [`ClayCode::from_params`](crate::ClayCode::from_params).
+/// The coupling coefficient γ is not included; codes built with a custom γ
+/// must store it separately.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClayParams {
+    /// Number of data chunks
+    pub k: usize,
+    /// Number of parity chunks
+    pub m: usize,
+    /// Number of helper nodes for repair
+    pub d: usize,
+}
@@ -0,0 +1,57 @@
+//! Phase timings of the layered decode (`profile` feature)
+//!
+//! Encoding computes parity through the layered decode, so one set of
+//! counters covers both directions. Every layer is uncoupled with the
+//! pairwise transforms and then, if any of its U values are still unknown,
+//! handed to the RS decoder; the C values of erased nodes are recovered
+//! from the U values with the transforms again.
+
+use std::time::{Duration, Instant};
+
+/// Where the time of one encode or decode went
+///
+/// Times are summed over layers. With `rayon` the layers of a level run
+/// in parallel, so the sum can exceed the wall-clock time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PhaseTimings {
+    /// Nanoseconds in the pairwise transforms (uncoupling and C recovery)
+    pub transform_ns: u64,
+    /// Nanoseconds in the RS decoder
+    pub rs_ns: u64,
+    /// Layers that needed the RS decoder
+    pub layers_rs_decoded: usize,
+    /// Layers fully recovered by the transforms
+    pub layers_transform_only: usize,
+}
+
+impl PhaseTimings {
+    /// Record one layer uncoupled from `start` to `uncoupled` and, if
+    /// `rs_decoded`, RS-decoded from `uncoupled` until now
+    pub(crate) fn record_layer(&mut self, start: Instant, uncoupled: Instant, rs_decoded: bool) {
+        self.transform_ns += nanos(uncoupled - start);
+        if rs_decoded {
+            self.rs_ns += nanos(uncoupled.elapsed());
+            self.layers_rs_decoded += 1;
+        } else {
+            self.layers_transform_only += 1;
+        }
+    }
+
+    /// Record transform work started at `start`
+    pub(crate) fn record_transforms(&mut self, start: Instant) {
+        self.transform_ns += nanos(start.elapsed());
+    }
+
+    /// Add the counters of `other`, recorded on another thread
+    #[cfg(feature = "rayon")]
+    pub(crate) fn add(&mut self, other: &PhaseTimings) {
+        self.transform_ns += other.transform_ns;
+        self.rs_ns += other.rs_ns;
+        self.layers_rs_decoded += other.layers_rs_decoded;
+        self.layers_transform_only += other.layers_transform_only;
+    }
+}
+
+fn nanos(elapsed: Duration) -> u64 {
+    u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX)
+}
@@ -5,16 +5,61 @@
 //! downloading only β = α/q sub-chunks from each of d helper nodes, rather
 //! than k full chunks.
 
-use std::collections::{BTreeMap, BTreeSet, HashMap};
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+use alloc::{format, vec};
+use core::ops::Range;
+#[cfg(feature = "std")]
+use std::io::{Read, Seek, SeekFrom};
 
-use crate::checked_pow;
-use crate::coords::get_plane_vector;
-use crate::decode::{compute_cstar_from_c_and_u, decode_uncoupled_layer, get_companion_layer, DecodeParams};
+use crate::{checked_buffer_bytes, checked_pow, ClayCode};
+use crate::decode::{
+    compute_cstar_from_c_and_u_into, decode_layered, get_companion_layer,
+    DecodeParams,
+};
+use crate::encode::validate_sub_chunk_size;
 use crate::error::ClayError;
-use crate::transforms::{compute_u_from_c_and_ustar, prt_compute_both_oriented};
+use crate::HashMap;
+use crate::transforms::{
+    compute_u_from_c_and_ustar_into, pft_compute_both_into, prt_compute_both_into,
+    prt_compute_single,
+};
 
 /// Parameters needed for repair (alias to DecodeParams)
-pub type RepairParams = DecodeParams;
+pub type RepairParams<'a> = DecodeParams<'a>;
+
+/// Byte ranges to read from each helper: (helper_node_idx, byte_ranges)
+pub type HelperByteRanges = Vec<(usize, Vec<Range<usize>>)>;
+
+/// Bandwidth actually used by one repair
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RepairStats {
+    /// Number of helpers that supplied data
+    pub helpers_used: usize,
+    /// Sub-chunks read from each helper (β)
+    pub subchunks_per_helper: usize,
+    /// Total helper bytes read
+    pub bytes_read: usize,
+    /// Bytes of the recovered chunk
+    pub bytes_written: usize,
+}
+
+/// What a helper's sub-chunk in one repair layer is used for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HelperRole {
+    /// The helper is unpaired in the layer, so its C is its uncoupled U:
+    /// a plain input to the layer's MDS decode
+    Unpaired,
+    /// Paired with another helper (or a zero shortened node); the U of both
+    /// follows from their two C values
+    Paired,
+    /// Paired with an aloof node; its U follows from its C and the aloof
+    /// node's U, recovered by the MDS decode of an earlier layer
+    AloofCompanion,
+    /// In the lost node's y-section; its C and U give the lost node's C in
+    /// the companion layer
+    LostYSection,
+}
 
 /// Get the list of sub-chunk indices needed for repair
 ///
@@ -23,6 +68,17 @@ pub fn get_repair_subchunk_indices(
     params: &RepairParams,
     lost_node: usize,
 ) -> Result<Vec<usize>, ClayError> {
+    let mut result = Vec::with_capacity(params.sub_chunk_no / params.q);
+    repair_subchunk_indices_into(params, lost_node, &mut result)?;
+    Ok(result)
+}
+
+/// [`get_repair_subchunk_indices`] into a caller-owned buffer
+fn repair_subchunk_indices_into(
+    params: &RepairParams,
+    lost_node: usize,
+    result: &mut Vec<usize>,
+) -> Result<(), ClayError> {
     let y_lost = lost_node / params.q;
     let x_lost = lost_node % params.q;
 
@@ -37,19 +93,284 @@ pub fn get_repair_subchunk_indices(
         ClayError::Overflow(format!("q^y = {}^{} overflows", params.q, y_lost))
     })?;
 
-    let beta = params.sub_chunk_no / params.q;
-    let mut result = Vec::with_capacity(beta);
+    result.clear();
+    // Every index is below q^t = α, which was checked when the code was built
     for seq in 0..num_seq {
         let base = x_lost * seq_sc_count + seq * params.q * seq_sc_count;
         for offset in 0..seq_sc_count {
             result.push(base + offset);
         }
     }
-    Ok(result)
+    Ok(())
+}
+
+/// Get the sub-chunk indices every helper supplies to repair a lost node
+///
+/// Same as [`get_repair_subchunk_indices`] but takes an external node index
+/// (0 to n-1) and validates it.
+pub fn repair_subchunk_indices(
+    params: &RepairParams,
+    lost_node: usize,
+) -> Result<Vec<usize>, ClayError> {
+    if lost_node >= params.n {
+        return Err(ClayError::InvalidParameters(format!(
+            "Invalid lost node index: {} >= {}",
+            lost_node, params.n
+        )));
+    }
+
+    get_repair_subchunk_indices(params, params.to_internal(lost_node))
+}
+
+/// Get the repair sub-chunk indices of every node
+///
+/// Entry i is [`repair_subchunk_indices`] for external node i. It depends
+/// only on the code parameters, not on which helpers are chosen.
+pub fn repair_plan(params: &RepairParams) -> Vec<Vec<usize>> {
+    (0..params.n)
+        .map(|node| {
+            // Only fails on overflow, and α was checked when the code was built
+            repair_subchunk_indices(params, node).expect("repair indices fit in usize")
+        })
+        .collect()
+}
+
+/// Determine the byte ranges to read from each helper to repair a lost node
+///
+/// Helpers are chosen as by minimum_to_repair() with every other node
+/// available. Adjacent sub-chunks are merged into a single range.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `lost_node`: Index of the lost node (0 to n-1)
+/// - `chunk_size`: Full chunk size
+///
+/// # Returns
+/// Vector of (helper_node_idx, byte_ranges) with ranges in ascending order.
+/// Concatenating the bytes of all ranges gives that helper's repair data.
+pub fn repair_byte_ranges(
+    params: &RepairParams,
+    lost_node: usize,
+    chunk_size: usize,
+) -> Result<HelperByteRanges, ClayError> {
+    validate_sub_chunk_size(params.sub_chunk_no, chunk_size)?;
+
+    let sub_chunk_size = chunk_size / params.sub_chunk_no;
+    let available: Vec<usize> = (0..params.n).filter(|&i| i != lost_node).collect();
+    let plan = minimum_to_repair(params, lost_node, &available)?;
+
+    Ok(plan
+        .into_iter()
+        .map(|(helper, indices)| (helper, merge_sub_chunk_ranges(&indices, sub_chunk_size)))
+        .collect())
+}
+
+/// Convert ascending sub-chunk indices into byte ranges, merging runs
+fn merge_sub_chunk_ranges(indices: &[usize], sub_chunk_size: usize) -> Vec<Range<usize>> {
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for &idx in indices {
+        let start = idx * sub_chunk_size;
+        match ranges.last_mut() {
+            Some(last) if last.end == start => last.end = start + sub_chunk_size,
+            _ => ranges.push(start..start + sub_chunk_size),
+        }
+    }
+    ranges
+}
+
+/// Determine the sub-chunks needed to repair a lost node with exactly d helpers
+///
+/// Each helper supplies β = α/q sub-chunks with q = d - k + 1, so a larger d
+/// reads more helpers but less from each: the normalized bandwidth is
+/// d / (k·q). q fixes the layer structure of the stored chunks, so the only
+/// d a code can repair with is the one it was built with; any other d is
+/// rejected rather than silently replaced.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `lost_node`: Index of the lost node (0 to n-1)
+/// - `available`: Available node indices
+/// - `d`: Number of helpers to repair with; must satisfy
+///   k < d <= available.len() (k <= d when m = 1) and d = k + q - 1
+///
+/// # Returns
+/// Same as [`minimum_to_repair`], or error if d is out of range or
+/// inconsistent with q
+pub fn minimum_to_repair_with_d(
+    params: &RepairParams,
+    lost_node: usize,
+    available: &[usize],
+    d: usize,
+) -> Result<Vec<(usize, Vec<usize>)>, ClayError> {
+    validate_repair_d(params, d, available.len(), || {
+        helper_candidates(params, &[lost_node], |node| available.contains(&node))
+    })?;
+    minimum_to_repair(params, lost_node, available)
+}
+
+/// Repair a lost chunk with exactly d helpers
+///
+/// See [`minimum_to_repair_with_d`] for the constraints on d.
+pub fn repair_with_d(
+    params: &RepairParams,
+    lost_node: usize,
+    helper_data: &HashMap<usize, Vec<u8>>,
+    chunk_size: usize,
+    d: usize,
+) -> Result<Vec<u8>, ClayError> {
+    validate_repair_d(params, d, helper_data.len(), || {
+        helper_candidates(params, &[lost_node], |node| helper_data.contains_key(&node))
+    })?;
+    repair(params, lost_node, helper_data, chunk_size)
+}
+
+/// Determine the aloof nodes of a repair with the given helpers
+///
+/// Aloof nodes are neither lost nor helpers. Their U values are recovered
+/// through the MDS layer, so every aloof node adds RS work to the layers
+/// where it is red and raises those layers' intersection score.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `lost_node`: Index of the lost node (0 to n-1)
+/// - `helpers`: Helper node indices
+///
+/// # Returns
+/// Aloof node indices in ascending order, or error if a node index is out
+/// of range or the lost node is listed as a helper
+pub fn aloof_nodes(
+    params: &RepairParams,
+    lost_node: usize,
+    helpers: &[usize],
+) -> Result<Vec<usize>, ClayError> {
+    if lost_node >= params.n {
+        return Err(ClayError::InvalidParameters(format!(
+            "Invalid lost node index: {} >= {}",
+            lost_node, params.n
+        )));
+    }
+    if let Some(&node) = helpers.iter().find(|&&node| node >= params.n) {
+        return Err(ClayError::InvalidParameters(format!(
+            "Helper index {} out of range [0, {})",
+            node, params.n
+        )));
+    }
+    if helpers.contains(&lost_node) {
+        return Err(ClayError::InvalidParameters(format!(
+            "Node {} is both lost and supplied as a helper",
+            lost_node
+        )));
+    }
+    Ok(helper_candidates(params, &[lost_node], |node| helpers.contains(&node)))
+}
+
+/// Classify how one helper's sub-chunks are used in each repair layer
+///
+/// Helpers are chosen as by minimum_to_repair() with every other node
+/// available.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `lost_node`: Index of the lost node (0 to n-1)
+/// - `helper`: Index of one of the chosen helpers
+///
+/// # Returns
+/// (layer, role) for each of the β repair layers in ascending order, or
+/// error if an index is out of range or `helper` is not a chosen helper
+pub fn helper_layer_roles(
+    params: &RepairParams,
+    lost_node: usize,
+    helper: usize,
+) -> Result<Vec<(usize, HelperRole)>, ClayError> {
+    let available: Vec<usize> = (0..params.n).filter(|&i| i != lost_node).collect();
+    let helpers: Vec<usize> =
+        minimum_to_repair(params, lost_node, &available)?.into_iter().map(|(h, _)| h).collect();
+    if !helpers.contains(&helper) {
+        return Err(ClayError::InvalidParameters(format!(
+            "Node {} is not a helper for repairing node {}",
+            helper, lost_node
+        )));
+    }
+    let aloof: BTreeSet<usize> = helper_candidates(params, &[lost_node], |node| {
+        helpers.contains(&node)
+    })
+    .into_iter()
+    .map(|node| params.to_internal(node))
+    .collect();
+
+    let lost_y = params.to_internal(lost_node) / params.q;
+    let helper_internal = params.to_internal(helper);
+    let (x, y) = (helper_internal % params.q, helper_internal / params.q);
+    Ok(repair_subchunk_indices(params, lost_node)?
+        .into_iter()
+        .map(|z| {
+            let z_y = params.plane_vector(z)[y];
+            let role = if y == lost_y {
+                HelperRole::LostYSection
+            } else if z_y == x {
+                HelperRole::Unpaired
+            } else if aloof.contains(&(y * params.q + z_y)) {
+                HelperRole::AloofCompanion
+            } else {
+                HelperRole::Paired
+            };
+            (z, role)
+        })
+        .collect())
+}
+
+/// Nodes that could still be asked for helper data
+///
+/// Every node (external index) that is neither lost nor already `provided`.
+fn helper_candidates(
+    params: &RepairParams,
+    lost: &[usize],
+    provided: impl Fn(usize) -> bool,
+) -> Vec<usize> {
+    (0..params.n).filter(|&node| !lost.contains(&node) && !provided(node)).collect()
+}
+
+/// Check a caller-chosen helper count against k, the helpers on hand and q
+fn validate_repair_d(
+    params: &RepairParams,
+    d: usize,
+    available: usize,
+    candidates: impl FnOnce() -> Vec<usize>,
+) -> Result<(), ClayError> {
+    // A single parity repairs from all k survivors
+    let min_d = if params.m == 1 { params.k } else { params.k + 1 };
+    if d < min_d {
+        return Err(ClayError::InvalidParameters(format!(
+            "d must be at least {} for k = {}, m = {}, got {}",
+            min_d, params.k, params.m, d
+        )));
+    }
+    if d > available {
+        return Err(ClayError::InsufficientHelpers {
+            needed: d,
+            provided: available,
+            candidates: candidates(),
+        });
+    }
+    let code_d = params.k + params.q - 1;
+    if d != code_d {
+        return Err(ClayError::InvalidParameters(format!(
+            "d = {} needs q = {}, but this code has q = {} (d = {})",
+            d,
+            d - params.k + 1,
+            params.q,
+            code_d
+        )));
+    }
+    Ok(())
 }
 
 /// Determine minimum sub-chunks needed to repair a lost node
 ///
+/// The lost node's y-section partners come first; the remaining helpers are
+/// the available nodes of lowest index, so the result depends only on the
+/// set of `available` nodes and not on their order.
+///
 /// # Parameters
 /// - `params`: Code parameters
 /// - `lost_node`: Index of the lost node (0 to n-1)
@@ -57,11 +378,26 @@ pub fn get_repair_subchunk_indices(
 ///
 /// # Returns
 /// Vector of (helper_node_idx, sub_chunk_indices) where sub_chunk_indices
-/// is a vector of the specific sub-chunk indices needed from that helper.
+/// is a vector of the specific sub-chunk indices needed from that helper,
+/// or error if an index is out of range, `lost_node` is listed as
+/// available, or too few nodes are available
 pub fn minimum_to_repair(
     params: &RepairParams,
     lost_node: usize,
     available: &[usize],
+) -> Result<Vec<(usize, Vec<usize>)>, ClayError> {
+    let mut by_index = available.to_vec();
+    by_index.sort_unstable();
+    by_index.dedup();
+    select_helpers(params, lost_node, &by_index)
+}
+
+/// Pick the y-section partners, then fill the optional slots in the order
+/// of `preferred`
+fn select_helpers(
+    params: &RepairParams,
+    lost_node: usize,
+    preferred: &[usize],
 ) -> Result<Vec<(usize, Vec<usize>)>, ClayError> {
     if lost_node >= params.n {
         return Err(ClayError::InvalidParameters(format!(
@@ -69,13 +405,22 @@ pub fn minimum_to_repair(
             lost_node, params.n
         )));
     }
+    // Only real nodes can help; shortened nodes have no external index
+    if let Some(&node) = preferred.iter().find(|&&node| node >= params.n) {
+        return Err(ClayError::InvalidParameters(format!(
+            "Helper index {} out of range [0, {})",
+            node, params.n
+        )));
+    }
+    if preferred.contains(&lost_node) {
+        return Err(ClayError::InvalidParameters(format!(
+            "Lost node {} is listed as available",
+            lost_node
+        )));
+    }
 
     // Convert to internal index
-    let lost_internal = if lost_node < params.k {
-        lost_node
-    } else {
-        lost_node + params.nu
-    };
+    let lost_internal = params.to_internal(lost_node);
 
     // Get repair sub-chunk indices (the layers where lost node is "red")
     let repair_sub_chunk_indices = get_repair_subchunk_indices(params, lost_internal)?;
@@ -89,23 +434,19 @@ pub fn minimum_to_repair(
     for x in 0..params.q {
         let node = y_section * params.q + x;
         if node != lost_internal {
-            // Convert internal index to external
-            let external_idx = if node < params.k {
-                node
-            } else if node >= params.k + params.nu {
-                node - params.nu
-            } else {
-                continue; // Skip shortened nodes
+            // Shortened nodes have no external index and are skipped
+            let Some(external_idx) = params.to_external(node) else {
+                continue;
             };
 
-            if available.contains(&external_idx) {
+            if preferred.contains(&external_idx) {
                 result.push((external_idx, repair_sub_chunk_indices.clone()));
             }
         }
     }
 
     // Add more helpers until we have d total
-    for &node in available {
+    for &node in preferred {
         if result.len() >= d {
             break;
         }
@@ -118,6 +459,7 @@ pub fn minimum_to_repair(
         return Err(ClayError::InsufficientHelpers {
             needed: d,
             provided: result.len(),
+            candidates: helper_candidates(params, &[lost_node], |node| preferred.contains(&node)),
         });
     }
 
@@ -125,331 +467,1404 @@ pub fn minimum_to_repair(
     Ok(result)
 }
 
-/// Repair a lost chunk using partial data from helper nodes
+/// Determine the repair helpers, filling the optional slots by cost
+///
+/// The lost node's y-section partners are always included, as in
+/// [`minimum_to_repair`]. The remaining helpers are the available nodes of
+/// lowest `cost`, ties broken by node index.
 ///
 /// # Parameters
 /// - `params`: Code parameters
 /// - `lost_node`: Index of the lost node (0 to n-1)
-/// - `helper_data`: Map from helper node index to partial chunk data.
-///   Each helper's data must be the concatenation of sub-chunks at the
-///   indices returned by minimum_to_repair(), in that exact order.
-/// - `chunk_size`: Full chunk size
+/// - `available`: Available node indices
+/// - `cost`: Cost of reading from a node, e.g. 0 for the same rack
 ///
 /// # Returns
-/// The recovered full chunk, or error if repair fails
-pub fn repair(
+/// Vector of (helper_node_idx, sub_chunk_indices), y-section partners
+/// first, then the optional helpers in ascending cost
+pub fn minimum_to_repair_with_topology(
     params: &RepairParams,
     lost_node: usize,
-    helper_data: &HashMap<usize, Vec<u8>>,
-    chunk_size: usize,
-) -> Result<Vec<u8>, ClayError> {
-    let d = params.k + params.q - 1;
+    available: &[usize],
+    cost: &dyn Fn(usize) -> u32,
+) -> Result<Vec<(usize, Vec<usize>)>, ClayError> {
+    let mut by_cost = available.to_vec();
+    by_cost.sort_by_key(|&node| (cost(node), node));
+    by_cost.dedup();
+    select_helpers(params, lost_node, &by_cost)
+}
 
-    if lost_node >= params.n {
-        return Err(ClayError::InvalidParameters(format!(
-            "Invalid lost node index: {} >= {}",
-            lost_node, params.n
-        )));
-    }
+/// Determine the repair helpers, filling the optional slots from `prefer`
+///
+/// The lost node's y-section partners are always included, as in
+/// [`minimum_to_repair`]. The remaining helpers are taken from the
+/// available nodes listed in `prefer`, in that order, then from the other
+/// available nodes by index. Preferred nodes that are not available are
+/// ignored.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `lost_node`: Index of the lost node (0 to n-1)
+/// - `available`: Available node indices
+/// - `prefer`: Nodes to read from first, e.g. those on faster storage
+///
+/// # Returns
+/// Vector of (helper_node_idx, sub_chunk_indices), y-section partners
+/// first, then the optional helpers in the order they were picked
+pub fn minimum_to_repair_prefer(
+    params: &RepairParams,
+    lost_node: usize,
+    available: &[usize],
+    prefer: &[usize],
+) -> Result<Vec<(usize, Vec<usize>)>, ClayError> {
+    let mut rest = available.to_vec();
+    rest.sort_unstable();
+    rest.dedup();
 
-    if helper_data.len() < d {
-        return Err(ClayError::InsufficientHelpers {
-            needed: d,
-            provided: helper_data.len(),
-        });
+    let mut ordered: Vec<usize> = Vec::with_capacity(rest.len());
+    for &node in prefer {
+        if rest.binary_search(&node).is_ok() && !ordered.contains(&node) {
+            ordered.push(node);
+        }
     }
+    rest.retain(|node| !ordered.contains(node));
+    ordered.extend(rest);
+    select_helpers(params, lost_node, &ordered)
+}
 
-    if chunk_size == 0 || chunk_size % params.sub_chunk_no != 0 {
-        return Err(ClayError::InvalidChunkSize {
-            expected: params.sub_chunk_no,
-            actual: chunk_size,
-        });
+/// Determine the repair helpers that leave the least transform work
+///
+/// Every choice of d helpers reads the same β sub-chunks from each, and
+/// every repair layer recovers exactly m U values through the MDS code,
+/// so bandwidth and RS work are fixed. What the choice changes is where
+/// the aloof nodes (survivors left out) sit. In a layer where an aloof
+/// node is unpaired, every other node of its y-section decouples against
+/// the aloof node's U from an earlier layer, one value at a time, rather
+/// than pairwise with another helper; with a aloof nodes in a y-section
+/// that happens a·(q-a) times per β/q layers. The aloof nodes are packed
+/// into y-sections to minimize that total, ties going to the plan of
+/// lowest helper indices.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `lost_node`: Index of the lost node (0 to n-1)
+/// - `available`: Available node indices
+///
+/// # Returns
+/// Vector of (helper_node_idx, sub_chunk_indices) as [`minimum_to_repair`]
+/// returns it, y-section partners first
+pub fn minimum_to_repair_min_cpu(
+    params: &RepairParams,
+    lost_node: usize,
+    available: &[usize],
+) -> Result<Vec<(usize, Vec<usize>)>, ClayError> {
+    let mut available = available.to_vec();
+    available.sort_unstable();
+    available.dedup();
+    let d = params.k + params.q - 1;
+    if lost_node >= params.n || available.last().map_or(false, |&node| node >= params.n) {
+        // Reports the invalid index
+        return select_helpers(params, lost_node, &available);
     }
 
-    let lost_internal = if lost_node < params.k {
-        lost_node
-    } else {
-        lost_node + params.nu
-    };
-
-    let repair_sub_chunk_indices = get_repair_subchunk_indices(params, lost_internal)?;
-    let sub_chunk_size = chunk_size / params.sub_chunk_no;
-    let expected_helper_bytes = repair_sub_chunk_indices.len() * sub_chunk_size;
-
-    let total_nodes = params.q * params.t;
+    let lost_y = params.to_internal(lost_node) / params.q;
 
-    // Validate that all required y-section helpers are present
-    let lost_y = lost_internal / params.q;
-    for x in 0..params.q {
-        let node = lost_y * params.q + x;
-        if node == lost_internal {
-            continue; // This is the lost node itself
-        }
-        // Skip shortened nodes
-        if node >= params.k && node < params.k + params.nu {
+    // Per y-section: survivors that must be aloof (unavailable) and those
+    // that may be either
+    let mut unavailable = vec![0usize; params.t];
+    let mut optional: Vec<Vec<usize>> = vec![Vec::new(); params.t];
+    for node in (0..params.n).filter(|&node| node != lost_node) {
+        let y = params.to_internal(node) / params.q;
+        if y == lost_y {
             continue;
         }
-        // Convert internal to external
-        let external_idx = if node < params.k {
-            node
+        if available.binary_search(&node).is_ok() {
+            optional[y].push(node);
         } else {
-            node - params.nu
-        };
-        if !helper_data.contains_key(&external_idx) {
-            return Err(ClayError::MissingYSectionHelper {
-                lost_node,
-                missing_helper: external_idx,
-            });
+            unavailable[y] += 1;
         }
     }
 
-    // Create RS codec once for all layers
-    let rs = reed_solomon_erasure::ReedSolomon::<reed_solomon_erasure::galois_8::Field>::new(
-        params.original_count,
-        params.recovery_count,
-    )
-    .map_err(|e| ClayError::ReconstructionFailed(format!("RS init failed: {:?}", e)))?;
-
-    // Initialize U buffers for all nodes
-    let mut u_buf: Vec<Vec<u8>> = vec![vec![0u8; chunk_size]; total_nodes];
-
-    // Track which U values have been computed (for dependency checking)
-    let mut u_computed: Vec<Vec<bool>> = vec![vec![false; params.sub_chunk_no]; total_nodes];
-
-    // Create recovered data buffer
-    let mut recovered = vec![0u8; chunk_size];
-
-    // Build helper data map with internal indices and validate sizes
-    // Store references to avoid cloning helper data
-    let mut helper_internal: HashMap<usize, &[u8]> = HashMap::new();
-    for (&ext_idx, data) in helper_data.iter() {
-        if ext_idx >= params.n {
-            return Err(ClayError::InvalidParameters(format!(
-                "Helper index {} out of range [0, {})",
-                ext_idx, params.n
-            )));
-        }
-        let internal = if ext_idx < params.k {
-            ext_idx
+    // best[y][a]: least cost of sections 0..y holding a aloof nodes
+    let aloof_total = params.n - 1 - d;
+    let cost = |a: usize| a * (params.q - a);
+    let mut best = vec![vec![usize::MAX; aloof_total + 1]; params.t + 1];
+    let mut choice = vec![vec![0usize; aloof_total + 1]; params.t + 1];
+    best[0][0] = 0;
+    for y in 0..params.t {
+        let range = if y == lost_y {
+            0..=0
         } else {
-            ext_idx + params.nu
+            unavailable[y]..=unavailable[y] + optional[y].len()
         };
-        if data.len() != expected_helper_bytes {
-            return Err(ClayError::InsufficientHelperData {
-                helper: ext_idx,
-                expected: expected_helper_bytes,
-                actual: data.len(),
-            });
-        }
-        helper_internal.insert(internal, data.as_slice());
-    }
-
-    // Build set of aloof nodes (not helpers and not the lost node)
-    let mut aloof_nodes: BTreeSet<usize> = BTreeSet::new();
-    for i in 0..total_nodes {
-        if i != lost_internal && !helper_internal.contains_key(&i) {
-            if i < params.k || i >= params.k + params.nu {
-                aloof_nodes.insert(i);
+        for a in 0..=aloof_total {
+            if best[y][a] == usize::MAX {
+                continue;
+            }
+            for here in range.clone().take_while(|&here| a + here <= aloof_total) {
+                let total = best[y][a] + cost(here);
+                if total < best[y + 1][a + here] {
+                    best[y + 1][a + here] = total;
+                    choice[y + 1][a + here] = here;
+                }
             }
         }
     }
-
-    // Add shortened nodes as helpers with zero data (allocated once)
-    let zero_data = vec![0u8; expected_helper_bytes];
-    for i in params.k..(params.k + params.nu) {
-        helper_internal.insert(i, &zero_data);
+    if best[params.t][aloof_total] == usize::MAX {
+        // Too few survivors; reports which nodes could still help
+        return select_helpers(params, lost_node, &available);
     }
 
-    // Build mapping from layer z to position in helper data
-    let mut repair_plane_to_ind: HashMap<usize, usize> = HashMap::new();
-    for (idx, &z) in repair_sub_chunk_indices.iter().enumerate() {
-        repair_plane_to_ind.insert(z, idx);
+    // Leave out the highest-indexed optional nodes of each section
+    let mut helpers: Vec<usize> = available.clone();
+    let mut remaining = aloof_total;
+    for y in (0..params.t).rev() {
+        let here = choice[y + 1][remaining];
+        remaining -= here;
+        let left_out = here - unavailable[y];
+        let section = &optional[y];
+        helpers.retain(|node| !section[section.len() - left_out..].contains(node));
     }
+    select_helpers(params, lost_node, &helpers)
+}
 
-    // Build ordered planes by intersection score
-    let mut ordered_planes: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
-    for &z in &repair_sub_chunk_indices {
-        let z_vec = get_plane_vector(z, params.t, params.q);
-        let mut order = 0;
+/// Repair a lost chunk and report the bandwidth it used
+///
+/// Same as [`repair`], plus the helper bytes read and chunk bytes written.
+pub fn repair_with_stats(
+    params: &RepairParams,
+    lost_node: usize,
+    helper_data: &HashMap<usize, Vec<u8>>,
+    chunk_size: usize,
+) -> Result<(Vec<u8>, RepairStats), ClayError> {
+    let recovered = repair(params, lost_node, helper_data, chunk_size)?;
+    let stats = RepairStats {
+        helpers_used: helper_data.len(),
+        subchunks_per_helper: params.sub_chunk_no / params.q,
+        bytes_read: helper_data.values().map(Vec::len).sum(),
+        bytes_written: chunk_size,
+    };
+    Ok((recovered, stats))
+}
 
-        // Check if lost node is "red" in this layer
-        if lost_internal % params.q == z_vec[lost_internal / params.q] {
-            order += 1;
-        }
+/// Repair a lost chunk using partial data from helper nodes
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `lost_node`: Index of the lost node (0 to n-1)
+/// - `helper_data`: Map from helper node index to partial chunk data.
+///   Each helper's data must be the concatenation of sub-chunks at the
+///   indices returned by minimum_to_repair(), in that exact order.
+/// - `chunk_size`: Full chunk size
+///
+/// # Returns
+/// The recovered full chunk, or error if repair fails
+pub fn repair(
+    params: &RepairParams,
+    lost_node: usize,
+    helper_data: &HashMap<usize, Vec<u8>>,
+    chunk_size: usize,
+) -> Result<Vec<u8>, ClayError> {
+    validate_repair_request(params, lost_node, helper_data, chunk_size)?;
+    let mut ws = RepairWorkspace::with_params(params, chunk_size);
+    let x = repair_into(params, &mut ws, lost_node, helper_data)?;
+    Ok(core::mem::take(&mut ws.scratch.recovered[x]))
+}
+
+/// Repair a lost chunk into a caller-provided buffer
+///
+/// Same as [`repair`] with `out.len()` as the chunk size. The buffer is
+/// checked against the helper data before any work is done, and is only
+/// written once the repair has succeeded.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `lost_node`: Index of the lost node (0 to n-1)
+/// - `helper_data`: Map from helper node index to partial chunk data, as
+///   for [`repair`]
+/// - `out`: Buffer for the recovered chunk, exactly one chunk long
+///
+/// # Returns
+/// Ok once `out` holds the recovered chunk, or error if `out` is not the
+/// chunk size the helper data was cut from or repair fails
+pub fn repair_to_buffer(
+    params: &RepairParams,
+    lost_node: usize,
+    helper_data: &HashMap<usize, Vec<u8>>,
+    out: &mut [u8],
+) -> Result<(), ClayError> {
+    let chunk_size = out.len();
+    validate_repair_request(params, lost_node, helper_data, chunk_size)?;
+
+    // Each helper sends β = α/q sub-chunks, a q-th of its chunk
+    let first_helper = helper_data.keys().min().copied().unwrap_or_default();
+    let helper_bytes = helper_data.get(&first_helper).map_or(0, Vec::len);
+    if helper_bytes.checked_mul(params.q) != Some(chunk_size) {
+        return Err(ClayError::InvalidParameters(format!(
+            "Output buffer has {} bytes, but helper {} sent {} bytes, a {}-byte chunk's share",
+            chunk_size,
+            first_helper,
+            helper_bytes,
+            helper_bytes.saturating_mul(params.q)
+        )));
+    }
+
+    let mut ws = RepairWorkspace::with_params(params, chunk_size);
+    let x = repair_into(params, &mut ws, lost_node, helper_data)?;
+    out.copy_from_slice(&ws.scratch.recovered[x]);
+    Ok(())
+}
+
+/// Reusable buffers for repairing chunks of one size
+///
+/// Holds every working buffer of the low-bandwidth repair, allocated once
+/// by [`RepairWorkspace::new`]. Repairing into it with
+/// [`ClayCode::repair_with`] performs no heap allocation once each lost
+/// node position of a y-section has been repaired (on GF(2^8) codes).
+#[derive(Debug)]
+pub struct RepairWorkspace {
+    k: usize,
+    nu: usize,
+    sub_chunk_no: usize,
+    chunk_size: usize,
+    /// Sub-chunk indices of the current repair
+    indices: Vec<usize>,
+    scratch: RepairScratch,
+}
+
+impl RepairWorkspace {
+    /// Allocate a workspace for `code` and chunks of `chunk_size` bytes
+    ///
+    /// # Parameters
+    /// - `code`: Code to repair with
+    /// - `chunk_size`: Bytes per chunk; must be a non-zero multiple of α with
+    ///   at least 2 bytes per sub-chunk
+    ///
+    /// # Returns
+    /// Result with RepairWorkspace or error if the chunk size is misaligned
+    /// or its buffers would not fit in memory
+    pub fn new(code: &ClayCode, chunk_size: usize) -> Result<Self, ClayError> {
+        validate_sub_chunk_size(code.sub_chunk_no, chunk_size)?;
+        checked_buffer_bytes(code.q * code.t, chunk_size)?;
+        Ok(Self::with_params(&code.encode_params(), chunk_size))
+    }
+
+    /// Allocate without checking `chunk_size`
+    fn with_params(params: &RepairParams, chunk_size: usize) -> Self {
+        RepairWorkspace {
+            k: params.k,
+            nu: params.nu,
+            sub_chunk_no: params.sub_chunk_no,
+            chunk_size,
+            indices: Vec::with_capacity(params.sub_chunk_no / params.q),
+            scratch: RepairScratch::new(params, chunk_size),
+        }
+    }
+
+    /// Size of each chunk repaired
+    pub fn chunk_size(&self) -> usize {
+        self.chunk_size
+    }
+}
+
+/// Repair a lost chunk into the buffers of a workspace
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `ws`: Workspace created for the same code
+/// - `lost_node`: Index of the lost node (0 to n-1)
+/// - `helper_data`: Map from helper node index to partial chunk data, as
+///   for [`repair`]
+///
+/// # Returns
+/// The recovered chunk, borrowed from the workspace, or error if the
+/// workspace belongs to a different code or repair fails
+pub fn repair_with<'w>(
+    params: &RepairParams,
+    ws: &'w mut RepairWorkspace,
+    lost_node: usize,
+    helper_data: &HashMap<usize, Vec<u8>>,
+) -> Result<&'w [u8], ClayError> {
+    if (ws.k, ws.nu, ws.sub_chunk_no) != (params.k, params.nu, params.sub_chunk_no)
+        || ws.scratch.u_buf.len() != params.q * params.t
+    {
+        return Err(ClayError::InvalidParameters(
+            "Workspace was created for a different code".into(),
+        ));
+    }
+    validate_repair_request(params, lost_node, helper_data, ws.chunk_size)?;
+    let x = repair_into(params, ws, lost_node, helper_data)?;
+    Ok(&ws.scratch.recovered[x])
+}
+
+/// Check the lost node, the helper count and the chunk size of a repair
+fn validate_repair_request(
+    params: &RepairParams,
+    lost_node: usize,
+    helper_data: &HashMap<usize, Vec<u8>>,
+    chunk_size: usize,
+) -> Result<(), ClayError> {
+    let d = params.k + params.q - 1;
+
+    if lost_node >= params.n {
+        return Err(ClayError::InvalidParameters(format!(
+            "Invalid lost node index: {} >= {}",
+            lost_node, params.n
+        )));
+    }
+
+    // A stale entry for the lost node would otherwise count as a helper
+    if helper_data.contains_key(&lost_node) {
+        return Err(ClayError::InvalidParameters(format!(
+            "Node {} is both lost and supplied as a helper",
+            lost_node
+        )));
+    }
+
+    if helper_data.len() < d {
+        return Err(ClayError::InsufficientHelpers {
+            needed: d,
+            provided: helper_data.len(),
+            candidates: helper_candidates(params, &[lost_node], |node| {
+                helper_data.contains_key(&node)
+            }),
+        });
+    }
+
+    validate_sub_chunk_size(params.sub_chunk_no, chunk_size)?;
+    // Working U buffers hold a full chunk per internal node
+    checked_buffer_bytes(params.q * params.t, chunk_size)?;
+    Ok(())
+}
+
+/// Validate the helper data and repair `lost_node` into `ws`
+///
+/// # Returns
+/// The lost node's x, indexing its chunk in the workspace's recovered buffers
+fn repair_into(
+    params: &RepairParams,
+    ws: &mut RepairWorkspace,
+    lost_node: usize,
+    helper_data: &HashMap<usize, Vec<u8>>,
+) -> Result<usize, ClayError> {
+    let chunk_size = ws.chunk_size;
+    let lost_internal = params.to_internal(lost_node);
+
+    repair_subchunk_indices_into(params, lost_internal, &mut ws.indices)?;
+    let sub_chunk_size = chunk_size / params.sub_chunk_no;
+    let expected_helper_bytes = ws.indices.len() * sub_chunk_size;
+
+    // Validate that all required y-section helpers are present
+    let lost_y = lost_internal / params.q;
+    for x in 0..params.q {
+        let node = lost_y * params.q + x;
+        if node == lost_internal {
+            continue; // This is the lost node itself
+        }
+        // Skip shortened nodes
+        let Some(external_idx) = params.to_external(node) else {
+            continue;
+        };
+        if !helper_data.contains_key(&external_idx) {
+            return Err(ClayError::MissingYSectionHelper {
+                lost_node,
+                missing_helper: external_idx,
+            });
+        }
+    }
+
+    // Every helper sends the same β sub-chunks, so their sizes must agree
+    // with each other, whatever chunk_size the caller passed
+    let first_helper = helper_data.keys().min().copied().unwrap_or_default();
+    let first = helper_data.get(&first_helper).map_or(0, Vec::len);
+    if let Some((&offending_helper, data)) = helper_data
+        .iter()
+        .filter(|(_, data)| data.len() != first)
+        .min_by_key(|(&idx, _)| idx)
+    {
+        return Err(ClayError::InconsistentHelperSizes {
+            first,
+            offending_helper,
+            offending_size: data.len(),
+        });
+    }
+
+    // In range, external indices map to distinct internal nodes (parity
+    // shifts past the nu shortened ones), so helpers cannot collapse
+    for (&ext_idx, data) in helper_data.iter() {
+        if ext_idx >= params.n {
+            return Err(ClayError::InvalidParameters(format!(
+                "Helper index {} out of range [0, {})",
+                ext_idx, params.n
+            )));
+        }
+        if data.len() != expected_helper_bytes {
+            return Err(ClayError::InsufficientHelperData {
+                helper: ext_idx,
+                expected: expected_helper_bytes,
+                actual: data.len(),
+            });
+        }
+    }
+
+    // Helper data is looked up by internal index; shortened nodes are
+    // supplied by repair_y_section itself
+    let helper = |node: usize| {
+        params.to_external(node).and_then(|external| helper_data.get(&external)).map(Vec::as_slice)
+    };
+    ws.scratch.lost.fill(false);
+    ws.scratch.lost[lost_internal] = true;
+    repair_y_section(params, helper, &ws.indices, chunk_size, &mut ws.scratch)?;
+
+    Ok(lost_internal % params.q)
+}
+
+/// Repair a lost chunk from full helper chunks
+///
+/// Extracts the sub-chunks repair() needs from each helper and runs it.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `lost_node`: Index of the lost node (0 to n-1)
+/// - `helpers`: Map from helper node index to its full chunk
+/// - `chunk_size`: Full chunk size; every helper chunk must be this long
+///
+/// # Returns
+/// The recovered full chunk, or error if repair fails
+pub fn repair_from_full_chunks(
+    params: &RepairParams,
+    lost_node: usize,
+    helpers: &HashMap<usize, Vec<u8>>,
+    chunk_size: usize,
+) -> Result<Vec<u8>, ClayError> {
+    validate_sub_chunk_size(params.sub_chunk_no, chunk_size)?;
+
+    for chunk in helpers.values() {
+        if chunk.len() != chunk_size {
+            return Err(ClayError::InvalidChunkSize {
+                expected: chunk_size,
+                actual: chunk.len(),
+            });
+        }
+    }
+
+    let repair_sub_chunk_indices = repair_subchunk_indices(params, lost_node)?;
+    let sub_chunk_size = chunk_size / params.sub_chunk_no;
+
+    let helper_data: HashMap<usize, Vec<u8>> = helpers
+        .iter()
+        .map(|(&idx, chunk)| {
+            let mut data = Vec::with_capacity(repair_sub_chunk_indices.len() * sub_chunk_size);
+            for &sc in &repair_sub_chunk_indices {
+                let offset = sc * sub_chunk_size;
+                data.extend_from_slice(&chunk[offset..offset + sub_chunk_size]);
+            }
+            (idx, data)
+        })
+        .collect();
+
+    repair(params, lost_node, &helper_data, chunk_size)
+}
+
+/// Repair a lost chunk by reading only the needed bytes of each helper
+///
+/// Every helper is read at the byte ranges of its β repair sub-chunks
+/// (adjacent sub-chunks merged, as repair_byte_ranges() gives them), so
+/// disk I/O matches the network savings of the repair.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `lost_node`: Index of the lost node (0 to n-1)
+/// - `helpers`: Map from helper node index to a reader over its full chunk
+/// - `chunk_size`: Full chunk size
+///
+/// # Returns
+/// The recovered full chunk, or error if reading or repair fails
+#[cfg(feature = "std")]
+pub fn repair_from_readers<R: Read + Seek>(
+    params: &RepairParams,
+    lost_node: usize,
+    helpers: &mut HashMap<usize, R>,
+    chunk_size: usize,
+) -> Result<Vec<u8>, ClayError> {
+    validate_sub_chunk_size(params.sub_chunk_no, chunk_size)?;
+    let indices = repair_subchunk_indices(params, lost_node)?;
+    let sub_chunk_size = chunk_size / params.sub_chunk_no;
+    let ranges = merge_sub_chunk_ranges(&indices, sub_chunk_size);
+
+    let mut helper_data: HashMap<usize, Vec<u8>> = HashMap::new();
+    for (&helper, reader) in helpers.iter_mut() {
+        let mut data = vec![0u8; indices.len() * sub_chunk_size];
+        let mut filled = 0;
+        for range in &ranges {
+            let end = filled + range.len();
+            reader
+                .seek(SeekFrom::Start(range.start as u64))
+                .and_then(|_| reader.read_exact(&mut data[filled..end]))
+                .map_err(|e| {
+                    ClayError::Io(format!(
+                        "Helper {} read at byte {} failed: {}",
+                        helper, range.start, e
+                    ))
+                })?;
+            filled = end;
+        }
+        helper_data.insert(helper, data);
+    }
+
+    repair(params, lost_node, &helper_data, chunk_size)
+}
+
+/// Repair a lost chunk from sub-chunks keyed by their index
+///
+/// Like repair(), but each helper supplies a map from sub-chunk index to
+/// that sub-chunk's bytes, so the order the sub-chunks were fetched in
+/// does not matter. Sub-chunks beyond the β needed are ignored.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `lost_node`: Index of the lost node (0 to n-1)
+/// - `helpers`: Map from helper node index to its map of sub-chunk index
+///   to sub-chunk bytes
+/// - `chunk_size`: Full chunk size
+///
+/// # Returns
+/// The recovered full chunk, or MissingSubChunk naming the first
+/// (helper, sub-chunk) pair that is needed but absent
+pub fn repair_from_subchunk_map(
+    params: &RepairParams,
+    lost_node: usize,
+    helpers: &HashMap<usize, HashMap<usize, Vec<u8>>>,
+    chunk_size: usize,
+) -> Result<Vec<u8>, ClayError> {
+    validate_sub_chunk_size(params.sub_chunk_no, chunk_size)?;
+    let repair_sub_chunk_indices = repair_subchunk_indices(params, lost_node)?;
+    let sub_chunk_size = chunk_size / params.sub_chunk_no;
+
+    let mut nodes: Vec<usize> = helpers.keys().copied().collect();
+    nodes.sort_unstable();
+    let mut helper_data: HashMap<usize, Vec<u8>> = HashMap::new();
+    for helper in nodes {
+        let sub_chunks = &helpers[&helper];
+        let mut data = Vec::with_capacity(repair_sub_chunk_indices.len() * sub_chunk_size);
+        for &sc in &repair_sub_chunk_indices {
+            let bytes = sub_chunks
+                .get(&sc)
+                .ok_or(ClayError::MissingSubChunk { helper, sub_chunk: sc })?;
+            if bytes.len() != sub_chunk_size {
+                return Err(ClayError::InsufficientHelperData {
+                    helper,
+                    expected: sub_chunk_size,
+                    actual: bytes.len(),
+                });
+            }
+            data.extend_from_slice(bytes);
+        }
+        helper_data.insert(helper, data);
+    }
+
+    repair(params, lost_node, &helper_data, chunk_size)
+}
+
+/// Repair a lost chunk and cross-check it against extra surviving chunks
+///
+/// A plain repair reads exactly as much as the MDS layer needs, so a bad
+/// helper yields a wrong chunk without any error. Here the recovered chunk,
+/// the extra full chunks and the helper sub-chunks are put back into the
+/// stripe, and every layer where they fix more than k + nu uncoupled values
+/// is checked against the layer's RS code. The helpers' repair layers are
+/// always checked; more extras cover more layers. An extra chunk from a
+/// helper takes the place of that helper's sub-chunks, so corruption in
+/// transit from any helper, in the lost y-section or not, is caught by
+/// also reading the helper's full chunk.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `lost_node`: Index of the lost node (0 to n-1)
+/// - `helper_data`: Map from helper node index to partial chunk data, as
+///   for repair()
+/// - `chunk_size`: Full chunk size
+/// - `extra_parity_check`: Map from surviving node index (helper or not) to
+///   its full chunk
+///
+/// # Returns
+/// The recovered full chunk, or ReconstructionFailed if a checked layer is
+/// not an RS codeword
+pub fn repair_verified(
+    params: &RepairParams,
+    lost_node: usize,
+    helper_data: &HashMap<usize, Vec<u8>>,
+    chunk_size: usize,
+    extra_parity_check: &HashMap<usize, Vec<u8>>,
+) -> Result<Vec<u8>, ClayError> {
+    let recovered = repair(params, lost_node, helper_data, chunk_size)?;
+
+    if extra_parity_check.is_empty() {
+        return Err(ClayError::InvalidParameters(
+            "No extra chunks to verify the repair against".into(),
+        ));
+    }
+    let mut extras: Vec<usize> = extra_parity_check.keys().copied().collect();
+    extras.sort_unstable();
+    for &node in &extras {
+        if node >= params.n || node == lost_node {
+            return Err(ClayError::InvalidParameters(format!(
+                "Extra chunk {} must be a surviving node",
+                node
+            )));
+        }
+        let chunk = &extra_parity_check[&node];
+        if chunk.len() != chunk_size {
+            return Err(ClayError::InvalidChunkSize {
+                expected: chunk_size,
+                actual: chunk.len(),
+            });
+        }
+    }
+
+    let total_nodes = params.q * params.t;
+    let sub_chunk_size = chunk_size / params.sub_chunk_no;
+
+    // Full chunks by internal index: recovered, extra and zero shortened
+    let zeros = vec![0u8; chunk_size];
+    let mut full: Vec<Option<&[u8]>> = vec![None; total_nodes];
+    full[params.k..params.k + params.nu].fill(Some(&zeros));
+    full[params.to_internal(lost_node)] = Some(&recovered);
+    for (&node, chunk) in extra_parity_check {
+        full[params.to_internal(node)] = Some(chunk);
+    }
+
+    // Helper sub-chunks by internal index, and where each repair layer sits
+    let mut partial: Vec<Option<&[u8]>> = vec![None; total_nodes];
+    for (&node, data) in helper_data {
+        partial[params.to_internal(node)] = Some(data);
+    }
+    let mut plane_to_ind: Vec<Option<usize>> = vec![None; params.sub_chunk_no];
+    for (ind, &z) in repair_subchunk_indices(params, lost_node)?.iter().enumerate() {
+        plane_to_ind[z] = Some(ind);
+    }
+    let c_value = |node: usize, z: usize| -> Option<&[u8]> {
+        let (chunk, ind) = match full[node] {
+            Some(chunk) => (chunk, z),
+            None => (partial[node]?, plane_to_ind[z]?),
+        };
+        Some(&chunk[ind * sub_chunk_size..(ind + 1) * sub_chunk_size])
+    };
+
+    let mut checked = 0;
+    for z in 0..params.sub_chunk_no {
+        let z_vec = params.plane_vector(z);
+        let layer: Vec<Option<Vec<u8>>> = (0..total_nodes)
+            .map(|node_xy| {
+                let x = node_xy % params.q;
+                let y = node_xy / params.q;
+                let c_xy = c_value(node_xy, z)?;
+                if z_vec[y] == x {
+                    return Some(c_xy.to_vec());
+                }
+                let z_sw = get_companion_layer(params, z, x, y);
+                let c_sw = c_value(y * params.q + z_vec[y], z_sw)?;
+                Some(prt_compute_single(c_xy, c_sw, params.mul))
+            })
+            .collect();
+
+        match layer_consistent(params, layer, sub_chunk_size)? {
+            Some(true) => checked += 1,
+            Some(false) => {
+                return Err(ClayError::ReconstructionFailed(format!(
+                    "Repair of node {} disagrees with the helper and extra chunks in layer {}",
+                    lost_node, z
+                )));
+            }
+            None => {}
+        }
+    }
+    if checked == 0 {
+        return Err(ClayError::InvalidParameters(format!(
+            "Extra chunks {:?} leave no layer with more than {} known uncoupled values",
+            extras,
+            params.k + params.nu
+        )));
+    }
+
+    Ok(recovered)
+}
+
+/// Check the known uncoupled values of one layer against the RS code
+///
+/// The first k + nu known values are kept, the rest of the layer is rebuilt
+/// from them and must match every other known value.
+///
+/// # Returns
+/// Whether the layer is consistent, or None if at most k + nu values are
+/// known, which any codeword could match
+fn layer_consistent(
+    params: &RepairParams,
+    layer: Vec<Option<Vec<u8>>>,
+    sub_chunk_size: usize,
+) -> Result<Option<bool>, ClayError> {
+    let data_shards = params.k + params.nu;
+    if layer.iter().flatten().count() <= data_shards {
+        return Ok(None);
+    }
+
+    let mut present = 0;
+    let mut held_back = Vec::new();
+    let mut shards: Vec<(Vec<u8>, bool)> = Vec::with_capacity(layer.len());
+    for (node, u) in layer.into_iter().enumerate() {
+        match u {
+            Some(u) if present < data_shards => {
+                present += 1;
+                shards.push((u, true));
+            }
+            Some(u) => {
+                held_back.push((node, u));
+                shards.push((vec![0u8; sub_chunk_size], false));
+            }
+            None => shards.push((vec![0u8; sub_chunk_size], false)),
+        }
+    }
+    params.rs.reconstruct(&mut shards)?;
+
+    Ok(Some(held_back.iter().all(|(node, u)| shards[*node].0 == *u)))
+}
+
+/// Working buffers of the low-bandwidth repair, reusable across repairs
+#[derive(Debug)]
+struct RepairScratch {
+    /// U values of every internal node, laid out like the chunks
+    u_buf: Vec<Vec<u8>>,
+    /// Which U values have been computed, per node and layer
+    u_computed: Vec<Vec<bool>>,
+    /// U values of one layer as RS shards, flagged present when known
+    layer_u: Vec<(Vec<u8>, bool)>,
+    /// Internal nodes being repaired, all in one y-section; set by the caller
+    lost: Vec<bool>,
+    /// Internal nodes that are neither helpers, lost nor shortened
+    aloof: Vec<bool>,
+    /// Position of each layer in the helper data, if helpers send it
+    plane_to_ind: Vec<Option<usize>>,
+    /// Repair planes as (intersection score, layer), in processing order
+    planes: Vec<(usize, usize)>,
+    /// Helper data of the shortened nodes, which are known zeros
+    zeros: Vec<u8>,
+    /// Recovered chunks of the lost y-section by x, allocated when first used
+    recovered: Vec<Vec<u8>>,
+    /// Scratch sub-chunks for one transform pair
+    scratch_a: Vec<u8>,
+    scratch_b: Vec<u8>,
+}
+
+impl RepairScratch {
+    /// Allocate buffers for chunks of `chunk_size` bytes
+    fn new(params: &RepairParams, chunk_size: usize) -> Self {
+        let total_nodes = params.q * params.t;
+        let sub_chunk_size = chunk_size / params.sub_chunk_no;
+        RepairScratch {
+            u_buf: vec![vec![0u8; chunk_size]; total_nodes],
+            u_computed: vec![vec![false; params.sub_chunk_no]; total_nodes],
+            layer_u: vec![(vec![0u8; sub_chunk_size], true); total_nodes],
+            lost: vec![false; total_nodes],
+            aloof: vec![false; total_nodes],
+            plane_to_ind: vec![None; params.sub_chunk_no],
+            planes: Vec::with_capacity(params.sub_chunk_no / params.q),
+            zeros: Vec::new(),
+            recovered: vec![Vec::new(); params.q],
+            scratch_a: vec![0u8; sub_chunk_size],
+            scratch_b: vec![0u8; sub_chunk_size],
+        }
+    }
+}
+
+/// Repair one or more lost nodes that all lie in the same y-section
+///
+/// This is the core of the low-bandwidth repair. Every node that is neither
+/// a helper nor lost (including lost nodes from other y-sections) is
+/// treated as aloof and recovered through the MDS layer where needed.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `helper`: Sub-chunks of the helper with a given internal index,
+///   concatenated in the order of `repair_sub_chunk_indices`; never asked
+///   for shortened nodes
+/// - `repair_sub_chunk_indices`: Layers available from every helper; must
+///   include every layer where a lost node is red
+/// - `chunk_size`: Full chunk size
+/// - `s`: Scratch for this code and chunk size, with `s.lost` marking the
+///   internal indices of the lost nodes
+///
+/// # Returns
+/// Ok once the chunk of each lost node is in `s.recovered`, indexed by its x
+fn repair_y_section<'h>(
+    params: &RepairParams,
+    helper: impl Fn(usize) -> Option<&'h [u8]>,
+    repair_sub_chunk_indices: &[usize],
+    chunk_size: usize,
+    s: &mut RepairScratch,
+) -> Result<(), ClayError> {
+    let total_nodes = params.q * params.t;
+    let sub_chunk_size = chunk_size / params.sub_chunk_no;
+    let lost_y = match s.lost.iter().position(|&lost| lost) {
+        Some(node) => node / params.q,
+        None => return Ok(()),
+    };
+    let RepairScratch {
+        u_buf,
+        u_computed,
+        layer_u,
+        lost,
+        aloof,
+        plane_to_ind,
+        planes,
+        zeros,
+        recovered,
+        scratch_a,
+        scratch_b,
+    } = s;
+
+    // No U value is known yet (the buffers may hold a previous repair)
+    for computed in u_computed.iter_mut() {
+        computed.fill(false);
+    }
+
+    // Shortened nodes act as helpers with zero data
+    let is_shortened = |node: usize| params.to_external(node).is_none();
+    zeros.clear();
+    zeros.resize(repair_sub_chunk_indices.len() * sub_chunk_size, 0);
+    let zeros: &[u8] = zeros;
+    let helper_at = |node: usize| if is_shortened(node) { Some(zeros) } else { helper(node) };
+
+    // Aloof nodes: not helpers and not being repaired
+    for (node, is_aloof) in aloof.iter_mut().enumerate() {
+        *is_aloof = !lost[node] && !is_shortened(node) && helper(node).is_none();
+    }
+
+    // Map from layer z to position in helper data
+    plane_to_ind.fill(None);
+    for (idx, &z) in repair_sub_chunk_indices.iter().enumerate() {
+        plane_to_ind[z] = Some(idx);
+    }
+    let c_offset = |z: usize| plane_to_ind[z].map(|idx| idx * sub_chunk_size);
+
+    // Order planes by intersection score, restricted to the layers where
+    // one of the lost nodes is red
+    planes.clear();
+    for &z in repair_sub_chunk_indices {
+        let z_vec = params.plane_vector(z);
+        if !lost[lost_y * params.q + z_vec[lost_y]] {
+            continue;
+        }
+
+        // The lost node that is "red" in this layer, plus red aloof nodes
+        let order = 1 + (0..total_nodes)
+            .filter(|&node| aloof[node] && node % params.q == z_vec[node / params.q])
+            .count();
+        planes.push((order, z));
+    }
+    planes.sort_unstable();
+
+    #[cfg(feature = "tracing")]
+    let _span = tracing::debug_span!(
+        "repair_y_section",
+        lost = ?(0..total_nodes).filter(|&node| lost[node]).collect::<Vec<_>>(),
+        helpers = (0..total_nodes).filter(|&node| helper_at(node).is_some()).count() - params.nu,
+        aloof = ?(0..total_nodes).filter(|&node| aloof[node]).collect::<Vec<_>>()
+    )
+    .entered();
+    #[cfg(feature = "tracing")]
+    tracing::debug!(
+        planes = planes.len(),
+        max_iscore = planes.last().map_or(0, |&(order, _)| order),
+        "ordered repair planes"
+    );
+
+    // Base erasures: lost nodes' y-section + aloof nodes
+    let base_erased = |node: usize| node / params.q == lost_y || aloof[node];
+    #[cfg(feature = "tracing")]
+    let base_erasures = (0..total_nodes).filter(|&node| base_erased(node)).count();
+
+    // Process planes in order of increasing intersection score
+    #[cfg(feature = "tracing")]
+    let phases = tracing::debug_span!("repair_uncoupled").entered();
+    for &(_, z) in planes.iter() {
+        let z_vec = params.plane_vector(z);
+
+        // Per-layer erasures start with the base erasures; any node whose
+        // U cannot be computed is flagged absent too
+        for (node, (_, present)) in layer_u.iter_mut().enumerate() {
+            *present = !base_erased(node);
+        }
+
+        // Phase 1: Compute U values from C values for non-erased nodes
+        for (y, &z_y) in z_vec.iter().enumerate() {
+            for x in 0..params.q {
+                let node_xy = y * params.q + x;
+                if base_erased(node_xy) {
+                    continue;
+                }
+
+                let Some(helper_chunk) = helper_at(node_xy) else {
+                    // No helper data for this node - mark for MDS
+                    layer_u[node_xy].1 = false;
+                    continue;
+                };
+                let z_sw = get_companion_layer(params, z, x, y);
+                let node_sw = y * params.q + z_y;
+                let c_xy_offset = c_offset(z).unwrap_or_default();
+                let c_xy = &helper_chunk[c_xy_offset..c_xy_offset + sub_chunk_size];
+
+                if z_y == x {
+                    // Red vertex: U = C
+                    u_buf[node_xy][z * sub_chunk_size..(z + 1) * sub_chunk_size]
+                        .copy_from_slice(c_xy);
+                    u_computed[node_xy][z] = true;
+                } else if aloof[node_sw] {
+                    // Companion is aloof - need U* from previous iteration
+                    if u_computed[node_sw][z_sw] {
+                        let offset_zsw = z_sw * sub_chunk_size;
+                        let u_sw = &u_buf[node_sw][offset_zsw..offset_zsw + sub_chunk_size];
+
+                        // Compute U from C and U* using PFT relationship
+                        compute_u_from_c_and_ustar_into(c_xy, u_sw, params.mul, scratch_a);
+                        u_buf[node_xy][z * sub_chunk_size..(z + 1) * sub_chunk_size]
+                            .copy_from_slice(scratch_a);
+                        u_computed[node_xy][z] = true;
+                    } else {
+                        // Companion's U not available - mark this node as needing MDS
+                        layer_u[node_xy].1 = false;
+                    }
+                } else if let Some(helper_sw) = helper_at(node_sw) {
+                    // Both nodes are helpers - use PRT
+                    if let Some(c_sw_offset) = c_offset(z_sw) {
+                        let c_sw = &helper_sw[c_sw_offset..c_sw_offset + sub_chunk_size];
+
+                        // PRT: compute U from C pair using correct orientation
+                        let (u_xy, u_sw_val) = (&mut *scratch_a, &mut *scratch_b);
+                        if x < z_y {
+                            prt_compute_both_into(c_xy, c_sw, params.mul, u_xy, u_sw_val);
+                        } else {
+                            prt_compute_both_into(c_sw, c_xy, params.mul, u_sw_val, u_xy);
+                        }
+                        u_buf[node_xy][z * sub_chunk_size..(z + 1) * sub_chunk_size]
+                            .copy_from_slice(u_xy);
+                        u_buf[node_sw][z_sw * sub_chunk_size..(z_sw + 1) * sub_chunk_size]
+                            .copy_from_slice(u_sw_val);
+                        u_computed[node_xy][z] = true;
+                        u_computed[node_sw][z_sw] = true;
+                    }
+                } else {
+                    // No way to compute U - mark for MDS
+                    layer_u[node_xy].1 = false;
+                }
+            }
+        }
+
+        // Phase 2: Decode uncoupled code to recover U for nodes we couldn't compute
+        let missing = layer_u.iter().filter(|(_, present)| !present).count();
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            layer = z,
+            mds_recoveries = missing,
+            extra = missing - base_erasures,
+            "repair layer"
+        );
+        if missing > params.m {
+            return Err(ClayError::TooManyErasures {
+                max: params.m,
+                actual: missing,
+            });
+        }
+        let offset = z * sub_chunk_size;
+        for (u, (shard, present)) in u_buf.iter().zip(layer_u.iter_mut()) {
+            if *present {
+                shard.copy_from_slice(&u[offset..offset + sub_chunk_size]);
+            }
+        }
+        params.rs.reconstruct(layer_u)?;
+        for (node, (shard, present)) in layer_u.iter().enumerate() {
+            if !present {
+                u_buf[node][offset..offset + sub_chunk_size].copy_from_slice(shard);
+                u_computed[node][z] = true;
+            }
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    drop(phases);
+
+    // Every lost chunk is fully written below; start from zeros all the same
+    for (x, out) in recovered.iter_mut().enumerate() {
+        if lost[lost_y * params.q + x] {
+            out.clear();
+            out.resize(chunk_size, 0);
+        }
+    }
+
+    // Phase 3: Compute C values for the lost nodes. This only needs the U
+    // values of the lost y-section, which are known for every repair plane
+    // once all planes have been processed.
+    #[cfg(feature = "tracing")]
+    let _phase = tracing::debug_span!("repair_coupled").entered();
+    for &(_, z) in planes.iter() {
+        let z_y = params.plane_vector(z)[lost_y];
+        let red_node = lost_y * params.q + z_y;
+
+        for x in 0..params.q {
+            let node = lost_y * params.q + x;
+            let offset_z = z * sub_chunk_size;
+
+            if x == z_y {
+                // Red vertex: C = U
+                if lost[node] {
+                    recovered[x][offset_z..offset_z + sub_chunk_size]
+                        .copy_from_slice(&u_buf[node][offset_z..offset_z + sub_chunk_size]);
+                }
+                continue;
+            }
+
+            // The red node is lost; its C at the companion layer follows
+            // from this node's U (and C, if this node is a helper)
+            let z_sw = get_companion_layer(params, z, x, lost_y);
+            let offset_zsw = z_sw * sub_chunk_size;
+            let u_node = &u_buf[node][offset_z..offset_z + sub_chunk_size];
+
+            if let Some(helper_chunk) = helper_at(node) {
+                let c_node_offset = c_offset(z).unwrap_or_default();
+                let c_node = &helper_chunk[c_node_offset..c_node_offset + sub_chunk_size];
+
+                // Compute C* (lost node's C at z_sw) from C and U
+                compute_cstar_from_c_and_u_into(c_node, u_node, params.mul, scratch_a);
+                if lost[red_node] {
+                    recovered[z_y][offset_zsw..offset_zsw + sub_chunk_size]
+                        .copy_from_slice(scratch_a);
+                }
+            } else if lost[node] && x < z_y {
+                // Both lost: their U values are known, so apply PFT (once per pair)
+                let u_sw = &u_buf[red_node][offset_zsw..offset_zsw + sub_chunk_size];
+                pft_compute_both_into(u_node, u_sw, params.mul, scratch_a, scratch_b);
+                recovered[x][offset_z..offset_z + sub_chunk_size].copy_from_slice(scratch_a);
+                if lost[red_node] {
+                    recovered[z_y][offset_zsw..offset_zsw + sub_chunk_size]
+                        .copy_from_slice(scratch_b);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert lost external node indices into validated, sorted internal indices
+fn lost_nodes_internal(
+    params: &RepairParams,
+    lost_nodes: &[usize],
+) -> Result<BTreeSet<usize>, ClayError> {
+    if lost_nodes.is_empty() {
+        return Err(ClayError::InvalidParameters("No lost nodes given".into()));
+    }
+    if lost_nodes.len() > params.m {
+        return Err(ClayError::TooManyErasures {
+            max: params.m,
+            actual: lost_nodes.len(),
+        });
+    }
+
+    let mut lost_internal = BTreeSet::new();
+    for &node in lost_nodes {
+        if node >= params.n {
+            return Err(ClayError::InvalidParameters(format!(
+                "Invalid lost node index: {} >= {}",
+                node, params.n
+            )));
+        }
+        if !lost_internal.insert(params.to_internal(node)) {
+            return Err(ClayError::InvalidParameters(format!(
+                "Lost node {} listed more than once",
+                node
+            )));
+        }
+    }
+    Ok(lost_internal)
+}
+
+/// Group lost internal indices by y-section
+fn group_by_y_section(params: &RepairParams, lost: &BTreeSet<usize>) -> BTreeMap<usize, BTreeSet<usize>> {
+    let mut groups: BTreeMap<usize, BTreeSet<usize>> = BTreeMap::new();
+    for &node in lost {
+        groups.entry(node / params.q).or_default().insert(node);
+    }
+    groups
+}
+
+/// Sorted union of the repair layers of every lost node
+fn multi_repair_subchunk_indices(
+    params: &RepairParams,
+    lost: &BTreeSet<usize>,
+) -> Result<Vec<usize>, ClayError> {
+    let mut indices = BTreeSet::new();
+    for &node in lost {
+        indices.extend(get_repair_subchunk_indices(params, node)?);
+    }
+    Ok(indices.into_iter().collect())
+}
+
+/// Check whether every y-section group can be repaired from `helpers`
+///
+/// Each repair layer of a group erases the whole lost y-section plus every
+/// aloof node, which the MDS layer can only absorb if that is at most m.
+fn low_bandwidth_feasible(
+    params: &RepairParams,
+    groups: &BTreeMap<usize, BTreeSet<usize>>,
+    helpers: &BTreeSet<usize>,
+) -> bool {
+    let total_nodes = params.q * params.t;
+    groups.keys().all(|&y| {
+        // Every real node of the y-section must be lost or a helper
+        let section_ok = (0..params.q).map(|x| y * params.q + x).all(|node| {
+            params.to_external(node).is_none()
+                || groups[&y].contains(&node)
+                || helpers.contains(&node)
+        });
+        let aloof = (0..total_nodes)
+            .filter(|&i| i / params.q != y)
+            .filter(|&i| params.to_external(i).is_some())
+            .filter(|i| !helpers.contains(i))
+            .count();
+        section_ok && params.q + aloof <= params.m
+    })
+}
+
+/// Determine the sub-chunks needed to repair several lost nodes at once
+///
+/// Lost nodes are grouped by y-section and each group is repaired with the
+/// low-bandwidth scheme, treating the other lost nodes as aloof. Every helper
+/// supplies the union of the groups' repair layers. When that is not
+/// possible with the available nodes, or would read at least as much as a
+/// full decode, the plan falls back to k helpers supplying whole chunks.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `lost_nodes`: Indices of the lost nodes (at most m, each 0 to n-1)
+/// - `available`: Available node indices
+///
+/// # Returns
+/// Vector of (helper_node_idx, sub_chunk_indices), where every helper is
+/// asked for the same sorted sub-chunk indices.
+pub fn minimum_to_repair_multi(
+    params: &RepairParams,
+    lost_nodes: &[usize],
+    available: &[usize],
+) -> Result<Vec<(usize, Vec<usize>)>, ClayError> {
+    let lost = lost_nodes_internal(params, lost_nodes)?;
+    let groups = group_by_y_section(params, &lost);
+    let indices = multi_repair_subchunk_indices(params, &lost)?;
+
+    let mut available_internal: Vec<usize> = Vec::new();
+    for &node in available {
+        if node >= params.n {
+            return Err(ClayError::InvalidParameters(format!(
+                "Available node index {} out of range [0, {})",
+                node, params.n
+            )));
+        }
+        let internal = params.to_internal(node);
+        if !lost.contains(&internal) && !available_internal.contains(&internal) {
+            available_internal.push(internal);
+        }
+    }
 
-        // Check aloof nodes
-        for &node in &aloof_nodes {
-            if node % params.q == z_vec[node / params.q] {
-                order += 1;
+    // Required helpers: the surviving partners in each lost y-section
+    let mut helpers: BTreeSet<usize> = BTreeSet::new();
+    let mut order: Vec<usize> = Vec::new();
+    for &y in groups.keys() {
+        for x in 0..params.q {
+            let node = y * params.q + x;
+            if !lost.contains(&node) && available_internal.contains(&node) && helpers.insert(node) {
+                order.push(node);
             }
         }
+    }
 
-        ordered_planes.entry(order).or_default().push(z);
+    // Fill optional helpers until every group is repairable
+    for &node in &available_internal {
+        if low_bandwidth_feasible(params, &groups, &helpers) {
+            break;
+        }
+        if helpers.insert(node) {
+            order.push(node);
+        }
     }
 
-    // Base erasure set: lost node's y-section + aloof nodes
-    let mut base_erasures: BTreeSet<usize> = BTreeSet::new();
-    for x in 0..params.q {
-        base_erasures.insert(lost_y * params.q + x);
+    // Lost nodes and helpers are real nodes, never shortened ones
+    let to_external = |node: usize| params.to_external(node).unwrap_or(node);
+
+    if low_bandwidth_feasible(params, &groups, &helpers)
+        && helpers.len() * indices.len() < params.k * params.sub_chunk_no
+    {
+        return Ok(order
+            .into_iter()
+            .map(|node| (to_external(node), indices.clone()))
+            .collect());
     }
-    for &node in &aloof_nodes {
-        base_erasures.insert(node);
+
+    // Fall back to a full decode from k whole chunks
+    if available_internal.len() < params.k {
+        return Err(ClayError::InsufficientHelpers {
+            needed: params.k,
+            provided: available_internal.len(),
+            candidates: helper_candidates(params, lost_nodes, |node| available.contains(&node)),
+        });
     }
+    let all_indices: Vec<usize> = (0..params.sub_chunk_no).collect();
+    Ok(available_internal
+        .into_iter()
+        .take(params.k)
+        .map(|node| (to_external(node), all_indices.clone()))
+        .collect())
+}
 
-    // Process planes in order of increasing intersection score
-    for (&_order, planes) in &ordered_planes {
-        for &z in planes {
-            let z_vec = get_plane_vector(z, params.t, params.q);
-
-            // Per-layer erasure set: starts with base erasures
-            // Add any node whose U we couldn't compute
-            let mut layer_erasures = base_erasures.clone();
-
-            // Phase 1: Compute U values from C values for non-erased nodes
-            for y in 0..params.t {
-                for x in 0..params.q {
-                    let node_xy = y * params.q + x;
-
-                    if !base_erasures.contains(&node_xy) {
-                        if let Some(helper_chunk) = helper_internal.get(&node_xy) {
-                            let z_y = z_vec[y];
-                            let z_sw = get_companion_layer(params, z, x, y, z_y);
-                            let node_sw = y * params.q + z_y;
-
-                            if z_y == x {
-                                // Red vertex: U = C
-                                let c_offset = repair_plane_to_ind[&z] * sub_chunk_size;
-                                u_buf[node_xy][z * sub_chunk_size..(z + 1) * sub_chunk_size]
-                                    .copy_from_slice(
-                                        &helper_chunk[c_offset..c_offset + sub_chunk_size],
-                                    );
-                                u_computed[node_xy][z] = true;
-                            } else if aloof_nodes.contains(&node_sw) {
-                                // Companion is aloof - need U* from previous iteration
-                                if u_computed[node_sw][z_sw] {
-                                    let c_offset = repair_plane_to_ind[&z] * sub_chunk_size;
-                                    let c_xy =
-                                        &helper_chunk[c_offset..c_offset + sub_chunk_size];
-                                    let u_sw = &u_buf[node_sw]
-                                        [z_sw * sub_chunk_size..(z_sw + 1) * sub_chunk_size];
-
-                                    // Compute U from C and U* using PFT relationship
-                                    let u_xy = compute_u_from_c_and_ustar(c_xy, u_sw);
-                                    u_buf[node_xy][z * sub_chunk_size..(z + 1) * sub_chunk_size]
-                                        .copy_from_slice(&u_xy);
-                                    u_computed[node_xy][z] = true;
-                                } else {
-                                    // Companion's U not available - mark this node as needing MDS
-                                    layer_erasures.insert(node_xy);
-                                }
-                            } else if let Some(helper_sw) = helper_internal.get(&node_sw) {
-                                // Both nodes are helpers - use PRT
-                                if let Some(&sw_idx) = repair_plane_to_ind.get(&z_sw) {
-                                    let c_xy_offset = repair_plane_to_ind[&z] * sub_chunk_size;
-                                    let c_sw_offset = sw_idx * sub_chunk_size;
-                                    let c_xy =
-                                        &helper_chunk[c_xy_offset..c_xy_offset + sub_chunk_size];
-                                    let c_sw =
-                                        &helper_sw[c_sw_offset..c_sw_offset + sub_chunk_size];
-
-                                    // PRT: compute U from C pair using correct orientation
-                                    let (u_xy, u_sw_val) =
-                                        prt_compute_both_oriented(c_xy, c_sw, x < z_y);
-                                    u_buf[node_xy][z * sub_chunk_size..(z + 1) * sub_chunk_size]
-                                        .copy_from_slice(&u_xy);
-                                    u_buf[node_sw]
-                                        [z_sw * sub_chunk_size..(z_sw + 1) * sub_chunk_size]
-                                        .copy_from_slice(&u_sw_val);
-                                    u_computed[node_xy][z] = true;
-                                    u_computed[node_sw][z_sw] = true;
-                                }
-                            } else {
-                                // No way to compute U - mark for MDS
-                                layer_erasures.insert(node_xy);
-                            }
-                        } else {
-                            // No helper data for this node - mark for MDS
-                            layer_erasures.insert(node_xy);
-                        }
-                    }
-                }
-            }
+/// Repair several lost chunks at once
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `lost_nodes`: Indices of the lost nodes (at most m, each 0 to n-1)
+/// - `helper_data`: Map from helper node index to its data, following the
+///   plan from `minimum_to_repair_multi`: either the concatenation of the
+///   planned sub-chunks in ascending order, or whole chunks for the
+///   full-decode fallback
+/// - `chunk_size`: Full chunk size
+///
+/// # Returns
+/// Map from lost node index to its recovered full chunk
+pub fn repair_multi(
+    params: &RepairParams,
+    lost_nodes: &[usize],
+    helper_data: &HashMap<usize, Vec<u8>>,
+    chunk_size: usize,
+) -> Result<HashMap<usize, Vec<u8>>, ClayError> {
+    let lost = lost_nodes_internal(params, lost_nodes)?;
 
-            // Phase 2: Decode uncoupled code to recover U for nodes we couldn't compute
-            decode_uncoupled_layer(params, &layer_erasures, z, sub_chunk_size, &mut u_buf, &rs)?;
-            for &node in &layer_erasures {
-                u_computed[node][z] = true;
-            }
+    validate_sub_chunk_size(params.sub_chunk_no, chunk_size)?;
+    // Working U buffers hold a full chunk per internal node
+    checked_buffer_bytes(params.q * params.t, chunk_size)?;
 
-            // Phase 3: Compute C values for the lost node
-            for &node in &base_erasures {
-                if aloof_nodes.contains(&node) {
-                    continue;
-                }
+    let mut helper_internal: HashMap<usize, &[u8]> = HashMap::new();
+    for (&ext_idx, data) in helper_data.iter() {
+        if ext_idx >= params.n {
+            return Err(ClayError::InvalidParameters(format!(
+                "Helper index {} out of range [0, {})",
+                ext_idx, params.n
+            )));
+        }
+        let internal = params.to_internal(ext_idx);
+        if lost.contains(&internal) {
+            return Err(ClayError::InvalidParameters(format!(
+                "Node {} is both lost and supplied as a helper",
+                ext_idx
+            )));
+        }
+        if helper_internal.insert(internal, data.as_slice()).is_some() {
+            return Err(ClayError::InvalidParameters(format!(
+                "Helper {} maps to an internal node already supplied",
+                ext_idx
+            )));
+        }
+    }
 
-                let x = node % params.q;
-                let y = node / params.q;
-                let z_y = z_vec[y];
-                let node_sw = y * params.q + z_y;
-                let z_sw = get_companion_layer(params, z, x, y, z_y);
-
-                if x == z_y {
-                    // Red vertex: C = U
-                    if node == lost_internal {
-                        recovered[z * sub_chunk_size..(z + 1) * sub_chunk_size].copy_from_slice(
-                            &u_buf[node][z * sub_chunk_size..(z + 1) * sub_chunk_size],
-                        );
-                    }
-                } else if node_sw == lost_internal {
-                    // node is a helper in y-section, its companion is the lost node
-                    if let Some(helper_chunk) = helper_internal.get(&node) {
-                        let c_offset = repair_plane_to_ind[&z] * sub_chunk_size;
-                        let c_node = &helper_chunk[c_offset..c_offset + sub_chunk_size];
-                        let u_node = &u_buf[node][z * sub_chunk_size..(z + 1) * sub_chunk_size];
-
-                        // Compute C* (lost node's C at z_sw) from C and U
-                        let c_lost = compute_cstar_from_c_and_u(c_node, u_node);
-                        recovered[z_sw * sub_chunk_size..(z_sw + 1) * sub_chunk_size]
-                            .copy_from_slice(&c_lost);
-                    }
-                }
+    let groups = group_by_y_section(params, &lost);
+    let indices = multi_repair_subchunk_indices(params, &lost)?;
+    let sub_chunk_size = chunk_size / params.sub_chunk_no;
+    let partial_bytes = indices.len() * sub_chunk_size;
+    let helpers: BTreeSet<usize> = helper_internal.keys().copied().collect();
+    // Lost nodes and helpers are real nodes, never shortened ones
+    let to_external = |node: usize| params.to_external(node).unwrap_or(node);
+
+    let mut recovered: HashMap<usize, Vec<u8>> = HashMap::new();
+
+    if low_bandwidth_feasible(params, &groups, &helpers)
+        && helper_internal.values().all(|data| data.len() == partial_bytes)
+    {
+        let mut scratch = RepairScratch::new(params, chunk_size);
+        let helper = |node: usize| helper_internal.get(&node).copied();
+        for group in groups.values() {
+            scratch.lost.fill(false);
+            for &node in group {
+                scratch.lost[node] = true;
             }
+            repair_y_section(params, helper, &indices, chunk_size, &mut scratch)?;
+            for &node in group {
+                let chunk = core::mem::take(&mut scratch.recovered[node % params.q]);
+                recovered.insert(to_external(node), chunk);
+            }
+        }
+        return Ok(recovered);
+    }
+
+    // Full-decode fallback: every helper must supply its whole chunk
+    if helper_internal.len() < params.k {
+        return Err(ClayError::InsufficientHelpers {
+            needed: params.k,
+            provided: helper_internal.len(),
+            candidates: helper_candidates(params, lost_nodes, |node| {
+                helper_data.contains_key(&node)
+            }),
+        });
+    }
+    for (&node, data) in &helper_internal {
+        if data.len() != chunk_size {
+            return Err(ClayError::InsufficientHelperData {
+                helper: to_external(node),
+                expected: chunk_size,
+                actual: data.len(),
+            });
+        }
+    }
+
+    let total_nodes = params.q * params.t;
+    let mut chunks: Vec<Vec<u8>> = vec![vec![0u8; chunk_size]; total_nodes];
+    let mut erased: BTreeSet<usize> = BTreeSet::new();
+    for (node, chunk) in chunks.iter_mut().enumerate() {
+        if let Some(data) = helper_internal.get(&node) {
+            chunk.copy_from_slice(data);
+        } else if params.to_external(node).is_some() {
+            erased.insert(node);
         }
     }
+    if erased.len() > params.m {
+        // Surplus non-helpers beyond the lost nodes would exceed m
+        return Err(ClayError::TooManyErasures {
+            max: params.m,
+            actual: erased.len(),
+        });
+    }
 
+    decode_layered(params, &erased, &mut chunks, sub_chunk_size)?;
+    for &node in &lost {
+        recovered.insert(to_external(node), core::mem::take(&mut chunks[node]));
+    }
     Ok(recovered)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::partial_helper_data;
+    use crate::ClayCode;
 
-    fn test_params() -> RepairParams {
-        RepairParams {
-            k: 4,
-            m: 2,
-            n: 6,
-            q: 2,
-            t: 3,
-            nu: 0,
-            sub_chunk_no: 8,
-            original_count: 4,
-            recovery_count: 2,
-        }
+    fn test_code() -> ClayCode {
+        // (4, 2, 5) configuration
+        ClayCode::new(4, 2, 5).unwrap()
     }
 
     #[test]
     fn test_repair_subchunk_indices_count() {
-        let params = test_params();
+        let code = test_code();
+        let params = code.encode_params();
         let beta = params.sub_chunk_no / params.q; // 8 / 2 = 4
 
         for lost_node in 0..params.n {
-            let internal = if lost_node < params.k {
-                lost_node
-            } else {
-                lost_node + params.nu
-            };
-            let indices = get_repair_subchunk_indices(&params, internal).unwrap();
+            let indices =
+                get_repair_subchunk_indices(&params, params.to_internal(lost_node)).unwrap();
             assert_eq!(
                 indices.len(),
                 beta,
@@ -460,9 +1875,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_repair_plan() {
+        for code in [test_code(), ClayCode::new(5, 2, 6).unwrap()] {
+            let params = code.encode_params();
+            let plan = repair_plan(&params);
+            assert_eq!(plan.len(), params.n);
+            for (node, indices) in plan.iter().enumerate() {
+                assert_eq!(indices.len(), params.sub_chunk_no / params.q);
+                assert_eq!(*indices, repair_subchunk_indices(&params, node).unwrap());
+            }
+        }
+    }
+
     #[test]
     fn test_minimum_to_repair_helpers_count() {
-        let params = test_params();
+        let code = test_code();
+        let params = code.encode_params();
         let d = params.k + params.q - 1; // 4 + 2 - 1 = 5
 
         let available: Vec<usize> = (1..params.n).collect();
@@ -473,7 +1902,8 @@ mod tests {
 
     #[test]
     fn test_minimum_to_repair_includes_y_section() {
-        let params = test_params();
+        let code = test_code();
+        let params = code.encode_params();
 
         // For node 0, y-section contains node 1 (both at y=0)
         let available: Vec<usize> = (1..params.n).collect();
@@ -486,18 +1916,491 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_minimum_to_repair_ignores_available_order() {
+        let code = ClayCode::new(6, 3, 7).unwrap();
+        let params = code.encode_params();
+
+        let available: Vec<usize> = (1..params.n).collect();
+        let expected = minimum_to_repair(&params, 0, &available).unwrap();
+        let helpers: Vec<usize> = expected.iter().map(|(h, _)| *h).collect();
+        assert_eq!(helpers, [1, 2, 3, 4, 5, 6, 7]);
+
+        let mut shuffled = available.clone();
+        let len = shuffled.len();
+        for step in [3, 5, 7] {
+            for i in 0..len {
+                shuffled.swap(i, (i * step + 1) % len);
+            }
+            assert_eq!(minimum_to_repair(&params, 0, &shuffled).unwrap(), expected);
+        }
+        shuffled.reverse();
+        assert_eq!(minimum_to_repair(&params, 0, &shuffled).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_minimum_to_repair_validates_available() {
+        let code = test_code();
+        let params = code.encode_params();
+        let expected = minimum_to_repair(&params, 0, &[1, 2, 3, 4, 5]).unwrap();
+
+        // Repeats are harmless
+        assert_eq!(minimum_to_repair(&params, 0, &[1, 2, 2, 3, 4, 5, 1]).unwrap(), expected);
+
+        for (available, needle) in [(vec![0, 1, 2, 3, 4, 5], "0"), (vec![1, 2, 3, 4, 5, 6], "6")] {
+            match minimum_to_repair(&params, 0, &available) {
+                Err(ClayError::InvalidParameters(msg)) => assert!(msg.contains(needle), "{}", msg),
+                other => panic!("expected InvalidParameters, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_selectors_reject_lost_node_as_available() {
+        let code = test_code();
+        let params = code.encode_params();
+        let available = [0, 1, 2, 3, 4, 5];
+
+        for result in [
+            minimum_to_repair(&params, 0, &available),
+            minimum_to_repair_with_topology(&params, 0, &available, &|node| node as u32),
+            minimum_to_repair_prefer(&params, 0, &available, &[3]),
+            minimum_to_repair_min_cpu(&params, 0, &available),
+        ] {
+            match result {
+                Err(ClayError::InvalidParameters(msg)) => {
+                    assert!(msg.contains("Lost node 0"), "{}", msg)
+                }
+                other => panic!("expected InvalidParameters, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_repair_shortened_code() {
+        // n = 7 with q = 2 needs one shortened node (internal index 5),
+        // which is node 4's y-section partner
+        let code = ClayCode::new(5, 2, 6).unwrap();
+        let params = code.encode_params();
+        assert_eq!(params.nu, 1);
+        let d = params.k + params.q - 1;
+
+        let data: Vec<u8> =
+            (0..params.k * params.sub_chunk_no * 5).map(|i| (i * 7 % 251) as u8).collect();
+        let chunks = code.encode(&data).unwrap();
+        let chunk_size = chunks[0].len();
+        let sub_chunk_size = chunk_size / params.sub_chunk_no;
+
+        for lost_node in 0..params.n {
+            let available: Vec<usize> = (0..params.n).filter(|&i| i != lost_node).collect();
+            let plan = minimum_to_repair(&params, lost_node, &available).unwrap();
+            assert_eq!(plan.len(), d);
+            assert!(plan.iter().all(|(h, _)| *h < params.n && *h != lost_node));
+
+            let helper_data = partial_helper_data(&chunks, &plan, sub_chunk_size);
+            assert_eq!(
+                repair(&params, lost_node, &helper_data, chunk_size).unwrap(),
+                chunks[lost_node],
+                "Repair failed for node {}",
+                lost_node
+            );
+        }
+
+        // An index past the real nodes is rejected rather than returned
+        let available: Vec<usize> = (1..=params.n).collect();
+        assert!(matches!(
+            minimum_to_repair(&params, 0, &available),
+            Err(ClayError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn test_repair_with_workspace_matches_repair() {
+        // Unshortened and shortened (nu = 1) codes
+        for code in [test_code(), ClayCode::new(5, 2, 6).unwrap()] {
+            let params = code.encode_params();
+            let data: Vec<u8> =
+                (0..params.k * params.sub_chunk_no * 3).map(|i| (i * 11) as u8).collect();
+            let chunks = code.encode(&data).unwrap();
+            let chunk_size = chunks[0].len();
+            let sub_chunk_size = chunk_size / params.sub_chunk_no;
+            let mut ws = RepairWorkspace::new(&code, chunk_size).unwrap();
+            assert_eq!(ws.chunk_size(), chunk_size);
+
+            // Reuse the workspace for every node, twice over
+            for lost_node in (0..params.n).chain(0..params.n) {
+                let available: Vec<usize> = (0..params.n).filter(|&i| i != lost_node).collect();
+                let plan = minimum_to_repair(&params, lost_node, &available).unwrap();
+                let helper_data = partial_helper_data(&chunks, &plan, sub_chunk_size);
+
+                let repaired = repair_with(&params, &mut ws, lost_node, &helper_data).unwrap();
+                assert_eq!(repaired, &chunks[lost_node][..], "Repair failed for {}", lost_node);
+            }
+        }
+    }
+
+    #[test]
+    fn test_repair_to_buffer() {
+        let code = ClayCode::new(5, 2, 6).unwrap();
+        let params = code.encode_params();
+        let data: Vec<u8> =
+            (0..params.k * params.sub_chunk_no * 4).map(|i| (i * 7) as u8).collect();
+        let chunks = code.encode(&data).unwrap();
+        let chunk_size = chunks[0].len();
+        let sub_chunk_size = chunk_size / params.sub_chunk_no;
+
+        // One buffer reused for every node
+        let mut out = vec![0u8; chunk_size];
+        let mut last_helper_data = HashMap::new();
+        for lost_node in 0..params.n {
+            let available: Vec<usize> = (0..params.n).filter(|&i| i != lost_node).collect();
+            let plan = minimum_to_repair(&params, lost_node, &available).unwrap();
+            let helper_data = partial_helper_data(&chunks, &plan, sub_chunk_size);
+            repair_to_buffer(&params, lost_node, &helper_data, &mut out).unwrap();
+            assert_eq!(out, chunks[lost_node], "Repair failed for {}", lost_node);
+            last_helper_data = helper_data;
+        }
+
+        // A buffer of another valid chunk size is rejected and left untouched
+        let lost_node = params.n - 1;
+        let mut wrong = vec![0xaau8; chunk_size * 2];
+        assert!(matches!(
+            repair_to_buffer(&params, lost_node, &last_helper_data, &mut wrong),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        assert!(wrong.iter().all(|&b| b == 0xaa));
+
+        let mut misaligned = vec![0u8; chunk_size + 1];
+        assert!(matches!(
+            repair_to_buffer(&params, lost_node, &last_helper_data, &mut misaligned),
+            Err(ClayError::InvalidChunkSize { .. })
+        ));
+    }
+
+    #[test]
+    fn test_repair_with_workspace_errors() {
+        let code = test_code();
+        let params = code.encode_params();
+
+        assert!(matches!(
+            RepairWorkspace::new(&code, 12),
+            Err(ClayError::InvalidChunkSize { expected: 8, actual: 12 })
+        ));
+
+        let mut ws = RepairWorkspace::new(&code, 16).unwrap();
+        let helper_data: HashMap<usize, Vec<u8>> =
+            (1..params.n).map(|i| (i, vec![0u8; 8])).collect();
+        assert!(matches!(
+            repair_with(&params, &mut ws, params.n, &helper_data),
+            Err(ClayError::InvalidParameters(_))
+        ));
+
+        let other = ClayCode::new(5, 3, 7).unwrap();
+        assert!(matches!(
+            repair_with(&other.encode_params(), &mut ws, 0, &helper_data),
+            Err(ClayError::InvalidParameters(_))
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_repair_from_readers() {
+        use std::io::Cursor;
+
+        let code = ClayCode::new(5, 2, 6).unwrap();
+        let params = code.encode_params();
+        let data: Vec<u8> = (0..2000).map(|i| (i * 31 % 256) as u8).collect();
+        let chunks = code.encode(&data).unwrap();
+        let chunk_size = chunks[0].len();
+
+        for lost_node in 0..params.n {
+            let mut readers: HashMap<usize, Cursor<&[u8]>> = (0..params.n)
+                .filter(|&i| i != lost_node)
+                .map(|i| (i, Cursor::new(chunks[i].as_slice())))
+                .collect();
+            let recovered =
+                repair_from_readers(&params, lost_node, &mut readers, chunk_size).unwrap();
+            assert_eq!(recovered, chunks[lost_node], "Repair failed for node {}", lost_node);
+        }
+
+        // A truncated helper file is an I/O error
+        let mut readers: HashMap<usize, Cursor<&[u8]>> =
+            (1..params.n).map(|i| (i, Cursor::new(&chunks[i][..0]))).collect();
+        assert!(matches!(
+            repair_from_readers(&params, 0, &mut readers, chunk_size),
+            Err(ClayError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_repair_rejects_lost_node_as_helper() {
+        let code = test_code();
+        let params = code.encode_params();
+        let data: Vec<u8> = (0..400).map(|i| (i * 7 % 256) as u8).collect();
+        let chunks = code.encode(&data).unwrap();
+        let chunk_size = chunks[0].len();
+
+        for lost_node in 0..params.n {
+            // The stale entry for the lost node plus all d real helpers
+            let indices = repair_subchunk_indices(&params, lost_node).unwrap();
+            let sub_chunk_size = chunk_size / params.sub_chunk_no;
+            let plan: Vec<(usize, Vec<usize>)> =
+                (0..params.n).map(|i| (i, indices.clone())).collect();
+            let helper_data = partial_helper_data(&chunks, &plan, sub_chunk_size);
+            match repair(&params, lost_node, &helper_data, chunk_size) {
+                Err(ClayError::InvalidParameters(msg)) => {
+                    assert!(msg.contains("both lost and supplied"), "{}", msg)
+                }
+                other => panic!("expected InvalidParameters, got {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_repair_rejects_one_byte_sub_chunks() {
+        let code = test_code();
+        let params = code.encode_params();
+        // chunk_size == α leaves a single byte per sub-chunk
+        let chunk_size = params.sub_chunk_no;
+        let helpers: HashMap<usize, Vec<u8>> =
+            (1..params.n).map(|i| (i, vec![0u8; chunk_size])).collect();
+
+        assert!(matches!(
+            repair_from_full_chunks(&params, 0, &helpers, chunk_size),
+            Err(ClayError::ChunkTooSmall { min: 16, actual: 8 })
+        ));
+        assert!(matches!(
+            repair(&params, 0, &helpers, chunk_size),
+            Err(ClayError::ChunkTooSmall { min: 16, actual: 8 })
+        ));
+        assert!(matches!(
+            RepairWorkspace::new(&code, chunk_size),
+            Err(ClayError::ChunkTooSmall { min: 16, actual: 8 })
+        ));
+
+        let chunks: HashMap<usize, Vec<u8>> =
+            (2..params.n).map(|i| (i, vec![0u8; chunk_size])).collect();
+        assert!(matches!(
+            code.decode(&chunks, &[0, 1]),
+            Err(ClayError::ChunkTooSmall { min: 16, actual: 8 })
+        ));
+    }
+
+    #[test]
+    fn test_minimum_to_repair_prefer() {
+        // (6, 3, 7): q = 2, d = 7, one survivor is left out
+        let code = ClayCode::new(6, 3, 7).unwrap();
+        let params = code.encode_params();
+        let available: Vec<usize> = (1..params.n).collect();
+        let helpers = |plan: Vec<(usize, Vec<usize>)>| -> Vec<usize> {
+            plan.into_iter().map(|(node, _)| node).collect()
+        };
+
+        // Without preferences node 8, the last by index, is left out
+        let plan = minimum_to_repair_prefer(&params, 0, &available, &[]).unwrap();
+        assert_eq!(plan, minimum_to_repair(&params, 0, &available).unwrap());
+
+        // Partner 1 first, then the preferred parity nodes, then by index
+        let plan = minimum_to_repair_prefer(&params, 0, &available, &[8, 7, 6]).unwrap();
+        assert_eq!(helpers(plan), [1, 8, 7, 6, 2, 3, 4]);
+
+        // Unavailable and repeated preferences are skipped
+        let available: Vec<usize> = (1..params.n).filter(|&i| i != 7).collect();
+        let plan = minimum_to_repair_prefer(&params, 0, &available, &[7, 8, 8, 1]).unwrap();
+        assert_eq!(helpers(plan), [1, 8, 2, 3, 4, 5, 6]);
+
+        assert!(matches!(
+            minimum_to_repair_prefer(&params, 0, &[0, 1, 2], &[]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn test_minimum_to_repair_min_cpu() {
+        // q = 3, t = 6, nu = 2: three survivors are left out of each repair
+        let code = ClayCode::new(10, 6, 12).unwrap();
+        let params = code.encode_params();
+        let y_of = |node: usize| code.node_to_xy(node).unwrap().1;
+
+        // With every survivor available, a whole y-section is left out
+        let available: Vec<usize> = (1..params.n).collect();
+        let plan = minimum_to_repair_min_cpu(&params, 0, &available).unwrap();
+        assert_eq!(plan.len(), code.d);
+        let helpers: Vec<usize> = plan.iter().map(|(h, _)| *h).collect();
+        let aloof = aloof_nodes(&params, 0, &helpers).unwrap();
+        assert_eq!(aloof.len(), 3);
+        assert!(aloof.iter().all(|&node| y_of(node) == y_of(aloof[0])), "{:?}", aloof);
+
+        // An unavailable node pulls the rest of its y-section out with it,
+        // where the lowest-index plan would leave out 3, 14 and 15
+        let available: Vec<usize> = (1..params.n).filter(|&node| node != 3).collect();
+        let plan = minimum_to_repair_min_cpu(&params, 0, &available).unwrap();
+        let helpers: Vec<usize> = plan.iter().map(|(h, _)| *h).collect();
+        assert_eq!(aloof_nodes(&params, 0, &helpers).unwrap(), [3, 4, 5]);
+        let plan = minimum_to_repair(&params, 0, &available).unwrap();
+        let default: Vec<usize> = plan.into_iter().map(|(h, _)| h).collect();
+        assert_eq!(aloof_nodes(&params, 0, &default).unwrap(), [3, 14, 15]);
+
+        // Repair works from the chosen helpers
+        let data: Vec<u8> = (0..10 * params.sub_chunk_no * 2).map(|i| (i % 241) as u8).collect();
+        let chunks = code.encode(&data).unwrap();
+        let sub_chunk_size = chunks[0].len() / params.sub_chunk_no;
+        for lost_node in [0, 7, 15] {
+            let available: Vec<usize> = (0..params.n).filter(|&i| i != lost_node).collect();
+            let plan = minimum_to_repair_min_cpu(&params, lost_node, &available).unwrap();
+            let helper_data = partial_helper_data(&chunks, &plan, sub_chunk_size);
+            let recovered = repair(&params, lost_node, &helper_data, chunks[0].len()).unwrap();
+            assert_eq!(recovered, chunks[lost_node], "Repair failed for node {}", lost_node);
+        }
+
+        // Too few survivors, or bad indices, fail like minimum_to_repair
+        assert!(matches!(
+            minimum_to_repair_min_cpu(&params, 0, &[1, 2, 3]),
+            Err(ClayError::InsufficientHelpers { .. })
+        ));
+        assert!(matches!(
+            minimum_to_repair_min_cpu(&params, 0, &[1, params.n]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+    }
+
+    #[test]
+    fn test_aloof_nodes() {
+        // n = 9, d = 7: one survivor is left out of the repair
+        let code = ClayCode::new(6, 3, 7).unwrap();
+        let params = code.encode_params();
+
+        let available: Vec<usize> = (1..params.n).collect();
+        let plan = minimum_to_repair(&params, 0, &available).unwrap();
+        let helpers: Vec<usize> = plan.into_iter().map(|(h, _)| h).collect();
+        assert_eq!(aloof_nodes(&params, 0, &helpers).unwrap(), [8]);
+        assert_eq!(aloof_nodes(&params, 0, &available).unwrap(), Vec::<usize>::new());
+        assert_eq!(aloof_nodes(&params, 4, &[0, 1, 2]).unwrap(), [3, 5, 6, 7, 8]);
+
+        assert!(matches!(aloof_nodes(&params, 9, &[]), Err(ClayError::InvalidParameters(_))));
+        assert!(matches!(aloof_nodes(&params, 0, &[9]), Err(ClayError::InvalidParameters(_))));
+        assert!(matches!(aloof_nodes(&params, 0, &[0, 1]), Err(ClayError::InvalidParameters(_))));
+    }
+
+    #[test]
+    fn test_helper_layer_roles() {
+        // q = 2, nu = 1; repairing node 0 leaves node 8 aloof
+        let code = ClayCode::new(6, 3, 7).unwrap();
+        let params = code.encode_params();
+        let layers = repair_subchunk_indices(&params, 0).unwrap();
+        let count = |helper: usize, role: HelperRole| {
+            let roles = helper_layer_roles(&params, 0, helper).unwrap();
+            assert_eq!(roles.iter().map(|&(z, _)| z).collect::<Vec<_>>(), layers);
+            roles.iter().filter(|&&(_, r)| r == role).count()
+        };
+        let half = code.beta / 2;
+
+        assert_eq!(count(1, HelperRole::LostYSection), code.beta);
+        // Unpaired in half the layers, paired with its y-section partner otherwise
+        assert_eq!((count(2, HelperRole::Unpaired), count(2, HelperRole::Paired)), (half, half));
+        // Node 6 shares its y-section with the zero shortened node
+        assert_eq!((count(6, HelperRole::Unpaired), count(6, HelperRole::Paired)), (half, half));
+        // Node 7 shares its y-section with the aloof node 8
+        assert_eq!(
+            (count(7, HelperRole::Unpaired), count(7, HelperRole::AloofCompanion)),
+            (half, half)
+        );
+
+        for bad in [0, 8, 9] {
+            assert!(matches!(
+                helper_layer_roles(&params, 0, bad),
+                Err(ClayError::InvalidParameters(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_repair_across_shortening_gap() {
+        // nu = 1 (q = 2 and q = 3) and nu = 2: external k - 1 is internal
+        // k - 1, external k is internal k + nu
+        for (k, m, d, nu) in [(3, 2, 4, 1), (5, 3, 7, 1), (4, 3, 6, 2)] {
+            let code = ClayCode::new(k, m, d).unwrap();
+            let params = code.encode_params();
+            assert_eq!(params.nu, nu);
+            let data: Vec<u8> =
+                (0..k * 4 * params.sub_chunk_no).map(|i| (i * 7 + 3) as u8).collect();
+            let chunks = code.encode(&data).unwrap();
+            let chunk_size = chunks[0].len();
+            let sub_chunk_size = chunk_size / params.sub_chunk_no;
+            // Every chunk differs, so a chunk from the wrong index cannot match
+            for pair in chunks.windows(2) {
+                assert_ne!(pair[0], pair[1]);
+            }
+
+            for lost_node in [k - 1, k] {
+                let available: Vec<usize> = (0..params.n).filter(|&i| i != lost_node).collect();
+                let plan = minimum_to_repair(&params, lost_node, &available).unwrap();
+                let helper_data = partial_helper_data(&chunks, &plan, sub_chunk_size);
+                let recovered = repair(&params, lost_node, &helper_data, chunk_size).unwrap();
+                assert_eq!(recovered, chunks[lost_node], "({},{},{}) node {}", k, m, d, lost_node);
+
+                let full: HashMap<usize, Vec<u8>> =
+                    available.iter().map(|&i| (i, chunks[i].clone())).collect();
+                let recovered = repair_from_full_chunks(&params, lost_node, &full, chunk_size);
+                assert_eq!(recovered.unwrap(), chunks[lost_node]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_repair_helper_size_checks() {
+        let code = test_code();
+        let params = code.encode_params();
+        let chunks = code.encode(&[7u8; 200]).unwrap();
+        let chunk_size = chunks[0].len();
+
+        let available: Vec<usize> = (1..params.n).collect();
+        let helper_info = minimum_to_repair(&params, 0, &available).unwrap();
+        let sub_chunk_size = chunk_size / params.sub_chunk_no;
+        let mut helper_data = partial_helper_data(&chunks, &helper_info, sub_chunk_size);
+        assert_eq!(repair(&params, 0, &helper_data, chunk_size).unwrap(), chunks[0]);
+
+        // Consistent helpers, but chunk_size does not match them
+        assert!(matches!(
+            repair(&params, 0, &helper_data, chunk_size * 2),
+            Err(ClayError::InsufficientHelperData { .. })
+        ));
+
+        // One helper from a differently sized stripe
+        helper_data.get_mut(&3).unwrap().truncate(sub_chunk_size * 2);
+        assert_eq!(
+            repair(&params, 0, &helper_data, chunk_size),
+            Err(ClayError::InconsistentHelperSizes {
+                first: helper_data[&1].len(),
+                offending_helper: 3,
+                offending_size: sub_chunk_size * 2,
+            })
+        );
+    }
+
     #[test]
     fn test_minimum_to_repair_insufficient_helpers() {
-        let params = test_params();
+        let code = test_code();
+        let params = code.encode_params();
         let d = params.k + params.q - 1;
 
         // Only provide d-1 helpers
         let available: Vec<usize> = (1..d).collect();
         let result = minimum_to_repair(&params, 0, &available);
 
-        assert!(matches!(
+        // Any node other than the lost one and those given would do
+        let expected: Vec<usize> = (d..params.n).collect();
+        assert_eq!(
             result,
-            Err(ClayError::InsufficientHelpers { .. })
+            Err(ClayError::InsufficientHelpers { needed: d, provided: d - 1, candidates: expected })
+        );
+
+        // repair() names the helpers it was not given
+        let helper_data: HashMap<usize, Vec<u8>> =
+            [1, 2, 4, 5].iter().map(|&node| (node, vec![0u8; 4])).collect();
+        assert!(matches!(
+            repair(&params, 0, &helper_data, 16),
+            Err(ClayError::InsufficientHelpers { candidates, .. }) if candidates == [3]
         ));
     }
 }
@@ -0,0 +1,142 @@
+//! Incremental decoding from chunks that arrive one at a time
+//!
+//! A reader fetching survivors over the network can hand each chunk to a
+//! session as soon as it lands, in any order, and decode as soon as k
+//! distinct chunks are in.
+
+use alloc::format;
+use alloc::vec::Vec;
+
+use crate::encode::validate_chunk_size;
+use crate::error::ClayError;
+use crate::{ClayCode, HashMap};
+
+/// Decode that collects chunks as they arrive, created with
+/// [`ClayCode::decode_session`]
+#[derive(Debug)]
+pub struct DecodeSession {
+    code: ClayCode,
+    chunks: HashMap<usize, Vec<u8>>,
+}
+
+impl DecodeSession {
+    /// Start a session with no chunks
+    pub fn new(code: &ClayCode) -> Self {
+        DecodeSession {
+            code: code.clone(),
+            chunks: HashMap::new(),
+        }
+    }
+
+    /// Accept one chunk
+    ///
+    /// # Parameters
+    /// - `node`: Chunk index (0 to n-1)
+    /// - `chunk`: The full chunk of that node
+    ///
+    /// # Returns
+    /// Ok, or error if the index is out of range or already fed, or the
+    /// chunk size is misaligned or differs from earlier chunks
+    pub fn feed(&mut self, node: usize, chunk: Vec<u8>) -> Result<(), ClayError> {
+        if node >= self.code.n {
+            return Err(ClayError::InvalidParameters(format!(
+                "Chunk index {} out of range [0, {})",
+                node, self.code.n
+            )));
+        }
+        if self.chunks.contains_key(&node) {
+            return Err(ClayError::InvalidParameters(format!(
+                "Chunk {} was already fed",
+                node
+            )));
+        }
+        validate_chunk_size(&self.code.encode_params(), chunk.len())?;
+        if let Some(first) = self.chunks.values().next() {
+            if first.len() != chunk.len() {
+                return Err(ClayError::InconsistentChunkSizes {
+                    first_size: first.len(),
+                    mismatched_idx: node,
+                    mismatched_size: chunk.len(),
+                });
+            }
+        }
+        self.chunks.insert(node, chunk);
+        Ok(())
+    }
+
+    /// Number of distinct chunks fed so far
+    pub fn received(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Decode once enough chunks have arrived
+    ///
+    /// Every node not yet fed counts as erased, so this succeeds as soon as
+    /// k chunks are in.
+    ///
+    /// # Returns
+    /// Ok(Some(data)) with the original data (may include padding),
+    /// Ok(None) while fewer than k chunks have arrived, or error if
+    /// decoding fails
+    pub fn try_finish(&self) -> Result<Option<Vec<u8>>, ClayError> {
+        if self.chunks.len() < self.code.k {
+            return Ok(None);
+        }
+        self.code.decode(&self.chunks, &[]).map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_decodes_once_k_arrive() {
+        let code = ClayCode::new(4, 2, 5).unwrap();
+        let data: Vec<u8> = (0..700).map(|i| (i * 23 % 256) as u8).collect();
+        let chunks = code.encode(&data).unwrap();
+
+        let mut session = code.decode_session();
+        // Parity first, data nodes 0 and 1 never arrive
+        for (fed, node) in [5, 2, 4, 3].into_iter().enumerate() {
+            assert_eq!(session.try_finish().unwrap(), None, "finished after {} chunks", fed);
+            session.feed(node, chunks[node].clone()).unwrap();
+        }
+        assert_eq!(session.received(), 4);
+        let decoded = session.try_finish().unwrap().unwrap();
+        assert_eq!(&decoded[..data.len()], &data[..]);
+    }
+
+    #[test]
+    fn test_session_rejects_bad_chunks() {
+        let code = ClayCode::new(4, 2, 5).unwrap();
+        let chunks = code.encode(&[7u8; 100]).unwrap();
+        let mut session = code.decode_session();
+
+        session.feed(1, chunks[1].clone()).unwrap();
+        assert!(matches!(
+            session.feed(1, chunks[1].clone()),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            session.feed(6, chunks[1].clone()),
+            Err(ClayError::InvalidParameters(_))
+        ));
+        assert!(matches!(
+            session.feed(2, vec![0u8; 12]),
+            Err(ClayError::InvalidChunkSize { .. })
+        ));
+        assert_eq!(
+            session.feed(2, vec![0u8; code.sub_chunk_no]),
+            Err(ClayError::ChunkTooSmall {
+                min: 2 * code.sub_chunk_no,
+                actual: code.sub_chunk_no,
+            })
+        );
+        assert!(matches!(
+            session.feed(2, vec![0u8; chunks[1].len() * 2]),
+            Err(ClayError::InconsistentChunkSizes { .. })
+        ));
+        assert_eq!(session.received(), 1);
+    }
+}
@@ -0,0 +1,197 @@
+//! SIMD kernels for the pairwise transforms (`simd` feature)
+//!
+//! A product by a constant c splits over the nibbles of x:
+//! c·x = c·(x & 0x0f) + c·(x & 0xf0), so two 16-entry tables and a byte
+//! shuffle multiply 16 bytes at once. The kernels cover whole 16-byte
+//! blocks and return how many bytes they wrote; callers finish the tail
+//! with the scalar tables. On targets without SSSE3 they write nothing.
+
+/// Products of one constant by every low nibble and every high nibble
+#[derive(Clone)]
+#[cfg_attr(not(any(target_arch = "x86", target_arch = "x86_64")), allow(dead_code))]
+pub(crate) struct NibbleTable {
+    lo: [u8; 16],
+    hi: [u8; 16],
+}
+
+impl NibbleTable {
+    /// Split a full multiply-by-constant table
+    pub(crate) fn new(table: &[u8; 256]) -> Self {
+        let mut lo = [0u8; 16];
+        let mut hi = [0u8; 16];
+        for i in 0..16 {
+            lo[i] = table[i];
+            hi[i] = table[i << 4];
+        }
+        NibbleTable { lo, hi }
+    }
+}
+
+/// PRT over whole 16-byte blocks: U = C + γ·C*, U* = γ·C + C*
+///
+/// Returns the number of leading bytes written to both outputs.
+#[cfg_attr(not(any(target_arch = "x86", target_arch = "x86_64")), allow(unused_variables))]
+pub(crate) fn prt_both(
+    c: &[u8],
+    c_star: &[u8],
+    gamma: &NibbleTable,
+    out_u: &mut [u8],
+    out_u_star: &mut [u8],
+) -> usize {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if has_ssse3() {
+        let len = c.len().min(c_star.len()).min(out_u.len()).min(out_u_star.len());
+        // SAFETY: SSSE3 is available and every slice holds at least len bytes
+        return unsafe { x86::prt_both(len, c, c_star, gamma, out_u, out_u_star) };
+    }
+    0
+}
+
+/// PFT over whole 16-byte blocks: C = (U + γ·U*) / det, C* = (γ·U + U*) / det
+///
+/// Returns the number of leading bytes written to both outputs.
+#[cfg_attr(not(any(target_arch = "x86", target_arch = "x86_64")), allow(unused_variables))]
+pub(crate) fn pft_both(
+    u: &[u8],
+    u_star: &[u8],
+    gamma: &NibbleTable,
+    det_inv: &NibbleTable,
+    out_c: &mut [u8],
+    out_c_star: &mut [u8],
+) -> usize {
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if has_ssse3() {
+        let len = u.len().min(u_star.len()).min(out_c.len()).min(out_c_star.len());
+        // SAFETY: SSSE3 is available and every slice holds at least len bytes
+        return unsafe { x86::pft_both(len, u, u_star, gamma, det_inv, out_c, out_c_star) };
+    }
+    0
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn has_ssse3() -> bool {
+    #[cfg(feature = "std")]
+    {
+        std::is_x86_feature_detected!("ssse3")
+    }
+    #[cfg(not(feature = "std"))]
+    {
+        cfg!(target_feature = "ssse3")
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86 {
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    use super::NibbleTable;
+
+    /// A table loaded into a pair of registers
+    #[derive(Clone, Copy)]
+    struct Tables {
+        lo: __m128i,
+        hi: __m128i,
+    }
+
+    #[target_feature(enable = "ssse3")]
+    unsafe fn load(table: &NibbleTable) -> Tables {
+        Tables {
+            lo: _mm_loadu_si128(table.lo.as_ptr() as *const __m128i),
+            hi: _mm_loadu_si128(table.hi.as_ptr() as *const __m128i),
+        }
+    }
+
+    /// Multiply 16 bytes by the constant of `t`
+    #[target_feature(enable = "ssse3")]
+    unsafe fn mul(x: __m128i, t: Tables) -> __m128i {
+        let mask = _mm_set1_epi8(0x0f);
+        let lo = _mm_and_si128(x, mask);
+        let hi = _mm_and_si128(_mm_srli_epi64(x, 4), mask);
+        _mm_xor_si128(_mm_shuffle_epi8(t.lo, lo), _mm_shuffle_epi8(t.hi, hi))
+    }
+
+    /// # Safety
+    /// SSSE3 must be available and every slice must hold at least `len` bytes
+    #[target_feature(enable = "ssse3")]
+    pub(super) unsafe fn prt_both(
+        len: usize,
+        c: &[u8],
+        c_star: &[u8],
+        gamma: &NibbleTable,
+        out_u: &mut [u8],
+        out_u_star: &mut [u8],
+    ) -> usize {
+        let gamma = load(gamma);
+        let done = len / 16 * 16;
+        for i in (0..done).step_by(16) {
+            let x = _mm_loadu_si128(c.as_ptr().add(i) as *const __m128i);
+            let x_star = _mm_loadu_si128(c_star.as_ptr().add(i) as *const __m128i);
+            let u = _mm_xor_si128(x, mul(x_star, gamma));
+            let u_star = _mm_xor_si128(mul(x, gamma), x_star);
+            _mm_storeu_si128(out_u.as_mut_ptr().add(i) as *mut __m128i, u);
+            _mm_storeu_si128(out_u_star.as_mut_ptr().add(i) as *mut __m128i, u_star);
+        }
+        done
+    }
+
+    /// # Safety
+    /// SSSE3 must be available and every slice must hold at least `len` bytes
+    #[target_feature(enable = "ssse3")]
+    pub(super) unsafe fn pft_both(
+        len: usize,
+        u: &[u8],
+        u_star: &[u8],
+        gamma: &NibbleTable,
+        det_inv: &NibbleTable,
+        out_c: &mut [u8],
+        out_c_star: &mut [u8],
+    ) -> usize {
+        let (gamma, det_inv) = (load(gamma), load(det_inv));
+        let done = len / 16 * 16;
+        for i in (0..done).step_by(16) {
+            let x = _mm_loadu_si128(u.as_ptr().add(i) as *const __m128i);
+            let x_star = _mm_loadu_si128(u_star.as_ptr().add(i) as *const __m128i);
+            let c = mul(_mm_xor_si128(x, mul(x_star, gamma)), det_inv);
+            let c_star = mul(_mm_xor_si128(mul(x, gamma), x_star), det_inv);
+            _mm_storeu_si128(out_c.as_mut_ptr().add(i) as *mut __m128i, c);
+            _mm_storeu_si128(out_c_star.as_mut_ptr().add(i) as *mut __m128i, c_star);
+        }
+        done
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::galois::{add as gf_add, coupling_det, inv as gf_inv, mul as gf_mul};
+    use crate::transforms::{pft_compute_both_into, prt_compute_both_into, GfMulTable};
+
+    #[test]
+    fn test_transforms_match_scalar_arithmetic() {
+        // Lengths below, at and between block boundaries exercise the tail
+        for gamma in [2u8, 3, 7, 0x53, 0xca] {
+            let mul = GfMulTable::new(gamma);
+            let det_inv = gf_inv(coupling_det(gamma));
+            for len in [0usize, 1, 15, 16, 17, 31, 32, 100, 4096] {
+                let a: Vec<u8> = (0..len).map(|i| (i * 31 + gamma as usize) as u8).collect();
+                let b: Vec<u8> = (0..len).map(|i| (i * 17 + 101) as u8).collect();
+                let (mut out1, mut out2) = (vec![0u8; len], vec![0u8; len]);
+
+                prt_compute_both_into(&a, &b, &mul, &mut out1, &mut out2);
+                for i in 0..len {
+                    assert_eq!(out1[i], gf_add(a[i], gf_mul(gamma, b[i])), "γ {} len {}", gamma, len);
+                    assert_eq!(out2[i], gf_add(gf_mul(gamma, a[i]), b[i]), "γ {} len {}", gamma, len);
+                }
+
+                pft_compute_both_into(&a, &b, &mul, &mut out1, &mut out2);
+                for i in 0..len {
+                    let c = gf_mul(det_inv, gf_add(a[i], gf_mul(gamma, b[i])));
+                    let c_star = gf_mul(det_inv, gf_add(gf_mul(gamma, a[i]), b[i]));
+                    assert_eq!((out1[i], out2[i]), (c, c_star), "γ {} len {}", gamma, len);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,176 @@
+//! Streaming encoder for Clay codes
+//!
+//! Encodes a reader stripe by stripe so the input never has to fit in
+//! memory. Every stripe holds the same number of data bytes, so each chunk
+//! written for a stripe has the same size and the final stripe is padded
+//! with zeros.
+
+use std::io::{ErrorKind, Read, Write};
+
+use crate::encode::{compute_parity, validate_chunk_size};
+use crate::error::ClayError;
+use crate::ClayCode;
+
+/// Stripe-by-stripe encoder, created with [`ClayCode::encoder`]
+///
+/// The stripe buffers are allocated once and reused for every stripe.
+#[derive(Debug)]
+pub struct Encoder {
+    code: ClayCode,
+    stripe_size: usize,
+    /// Stripe laid out by internal node index (k data, nu shortened, m parity)
+    chunks: Vec<Vec<u8>>,
+}
+
+impl Encoder {
+    /// Create an encoder for stripes of `stripe_size` data bytes
+    ///
+    /// # Parameters
+    /// - `code`: Code to encode with
+    /// - `stripe_size`: Data bytes per stripe; k times a chunk size that
+    ///   `encode_with_chunk_size` accepts
+    ///
+    /// # Returns
+    /// Result with Encoder or error if the stripe size is misaligned
+    pub fn new(code: &ClayCode, stripe_size: usize) -> Result<Self, ClayError> {
+        if stripe_size % code.k != 0 {
+            return Err(ClayError::InvalidParameters(format!(
+                "Stripe size {} is not a multiple of k = {}",
+                stripe_size, code.k
+            )));
+        }
+        let chunk_size = stripe_size / code.k;
+        validate_chunk_size(&code.encode_params(), chunk_size)?;
+
+        let total_nodes = code.q * code.t;
+        Ok(Encoder {
+            code: code.clone(),
+            stripe_size,
+            chunks: vec![vec![0u8; chunk_size]; total_nodes],
+        })
+    }
+
+    /// Data bytes consumed per stripe
+    pub fn stripe_size(&self) -> usize {
+        self.stripe_size
+    }
+
+    /// Size of each chunk written per stripe
+    pub fn chunk_size(&self) -> usize {
+        self.stripe_size / self.code.k
+    }
+
+    /// Read one stripe, encode it and write each chunk to its writer
+    ///
+    /// A short read at the end of the input is zero-padded to a full
+    /// stripe. Once the reader is exhausted nothing is written and 0 is
+    /// returned.
+    ///
+    /// # Parameters
+    /// - `reader`: Source of the data
+    /// - `writers`: One writer per chunk, in node order (n total)
+    ///
+    /// # Returns
+    /// Number of data bytes consumed from the reader, or error if reading,
+    /// encoding or writing fails
+    pub fn encode_stripe(
+        &mut self,
+        reader: &mut impl Read,
+        writers: &mut [impl Write],
+    ) -> Result<usize, ClayError> {
+        let params = self.code.encode_params();
+        if writers.len() != params.n {
+            return Err(ClayError::InvalidParameters(format!(
+                "Expected {} writers, got {}",
+                params.n,
+                writers.len()
+            )));
+        }
+
+        let mut consumed = 0;
+        for chunk in &mut self.chunks[..params.k] {
+            let filled = read_full(reader, chunk)?;
+            chunk[filled..].fill(0);
+            consumed += filled;
+        }
+        if consumed == 0 {
+            return Ok(0);
+        }
+
+        let sub_chunk_size = self.chunk_size() / params.sub_chunk_no;
+        compute_parity(&params, &mut self.chunks, sub_chunk_size)?;
+
+        let data = self.chunks[..params.k].iter();
+        let parity = self.chunks[params.k + params.nu..].iter();
+        for (writer, chunk) in writers.iter_mut().zip(data.chain(parity)) {
+            writer
+                .write_all(chunk)
+                .map_err(|e| ClayError::Io(format!("Chunk write failed: {}", e)))?;
+        }
+
+        Ok(consumed)
+    }
+}
+
+/// Read until `buf` is full or the reader is exhausted
+fn read_full(reader: &mut impl Read, buf: &mut [u8]) -> Result<usize, ClayError> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match reader.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(ClayError::Io(format!("Stripe read failed: {}", e))),
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stripe_matches_encode() {
+        let code = ClayCode::new(4, 2, 5).unwrap();
+        let mut encoder = Encoder::new(&code, 2 * 4 * 8).unwrap();
+        let data: Vec<u8> = (0..50).collect();
+
+        let mut writers = vec![Vec::new(); code.n];
+        let consumed = encoder.encode_stripe(&mut &data[..], &mut writers).unwrap();
+        assert_eq!(consumed, 50);
+        assert_eq!(writers, code.encode(&data).unwrap());
+
+        // Any chunk size encode produces, e.g. 3 * α
+        let mut encoder = Encoder::new(&code, 3 * 4 * 8).unwrap();
+        let data: Vec<u8> = (0..90).collect();
+        let mut writers = vec![Vec::new(); code.n];
+        encoder.encode_stripe(&mut &data[..], &mut writers).unwrap();
+        assert_eq!(writers, code.encode(&data).unwrap());
+    }
+
+    #[test]
+    fn test_encoder_rejects_bad_input() {
+        let code = ClayCode::new(4, 2, 5).unwrap();
+        assert!(matches!(
+            Encoder::new(&code, 0),
+            Err(ClayError::InvalidChunkSize { expected: 8, actual: 0 })
+        ));
+        assert!(matches!(Encoder::new(&code, 98), Err(ClayError::InvalidParameters(_))));
+        assert!(matches!(
+            Encoder::new(&code, 48),
+            Err(ClayError::InvalidChunkSize { expected: 8, actual: 12 })
+        ));
+        assert!(matches!(
+            Encoder::new(&code, 32),
+            Err(ClayError::ChunkTooSmall { min: 16, actual: 8 })
+        ));
+
+        let mut encoder = Encoder::new(&code, 64).unwrap();
+        let mut writers = vec![Vec::new(); code.n - 1];
+        assert!(matches!(
+            encoder.encode_stripe(&mut &[1u8; 10][..], &mut writers),
+            Err(ClayError::InvalidParameters(_))
+        ));
+    }
+}
@@ -0,0 +1,149 @@
+//! Fixed encodings that pin the wire format
+//!
+//! Each vector gives code parameters (gamma 2, GF(2^8)), an input and the
+//! exact n chunks `encode` must produce for it, data chunks first, as hex.
+//! The input bytes are `(i * 37 + 11) mod 256`. Ports to other languages can
+//! check themselves against the same table; a change here means chunks
+//! written by earlier releases no longer decode.
+
+use alloc::vec::Vec;
+
+use crate::ClayCode;
+
+/// One encoding: `encode(input)` with `ClayCode::new(k, m, d)` yields
+/// `expected_chunks_hex`
+struct TestVector {
+    k: usize,
+    m: usize,
+    d: usize,
+    input_hex: &'static str,
+    expected_chunks_hex: &'static [&'static str],
+}
+
+/// (4,2,5) unshortened with q = 2, (3,2,4) shortened by one node, and
+/// (6,3,8) with q = 3
+const VECTORS: &[TestVector] = &[
+    TestVector {
+        k: 4,
+        m: 2,
+        d: 5,
+        input_hex: concat!(
+            "0b30557a9fc4e90e33587da2c7ec11365b80a5caef14395e83a8cdf2173c6186",
+            "abd0f51a3f6489aed3f81d42678cb1d6fb20456a8fb4d9fe23486d92b7dc0126",
+        ),
+        expected_chunks_hex: &[
+            "0b30557a9fc4e90e33587da2c7ec1136",
+            "5b80a5caef14395e83a8cdf2173c6186",
+            "abd0f51a3f6489aed3f81d42678cb1d6",
+            "fb20456a8fb4d9fe23486d92b7dc0126",
+            "bc0df940e68003f51b4311dad4facee8",
+            "351d0079b45b74c8afcaff3d7f5f5ffe",
+        ],
+    },
+    TestVector {
+        k: 3,
+        m: 2,
+        d: 4,
+        input_hex: concat!(
+            "0b30557a9fc4e90e33587da2c7ec11365b80a5caef14395e83a8cdf2173c6186",
+            "abd0f51a3f6489aed3f81d42678cb1d6",
+        ),
+        expected_chunks_hex: &[
+            "0b30557a9fc4e90e33587da2c7ec1136",
+            "5b80a5caef14395e83a8cdf2173c6186",
+            "abd0f51a3f6489aed3f81d42678cb1d6",
+            "7cb7c9632511509b9d8a323215249026",
+            "b521ae43941490c4a563bbd11eb523bb",
+        ],
+    },
+    TestVector {
+        k: 6,
+        m: 3,
+        d: 8,
+        input_hex: concat!(
+            "0b30557a9fc4e90e33587da2c7ec11365b80a5caef14395e83a8cdf2173c6186",
+            "abd0f51a3f6489aed3f81d42678cb1d6fb20456a8fb4d9fe23486d92b7dc0126",
+            "4b7095badf04294e7398bde2072c51769bc0e50a2f54799ec3e80d32577ca1c6",
+            "eb10355a7fa4c9ee13385d82a7ccf1163b6085aacff4193e6388add2f71c4166",
+            "8bb0d5fa1f44698eb3d8fd22476c91b6db00254a6f94b9de03284d7297bce106",
+            "2b50759abfe4092e53789dc2e70c31567ba0c5ea0f34597ea3c8ed12375c81a6",
+            "cbf0153a5f84a9cef3183d6287acd1f61b40658aafd4f91e43688db2d7fc2146",
+            "6b90b5daff24496e93b8dd02274c7196bbe0052a4f7499bee3082d52779cc1e6",
+            "0b30557a9fc4e90e33587da2c7ec11365b80a5caef14395e83a8cdf2173c6186",
+            "abd0f51a3f6489aed3f81d42678cb1d6fb20456a8fb4d9fe23486d92b7dc0126",
+            "4b7095ba",
+        ),
+        expected_chunks_hex: &[
+            concat!(
+                "0b30557a9fc4e90e33587da2c7ec11365b80a5caef14395e83a8cdf2173c6186",
+                "abd0f51a3f6489aed3f81d42678cb1d6fb20456a8fb4",
+            ),
+            concat!(
+                "d9fe23486d92b7dc01264b7095badf04294e7398bde2072c51769bc0e50a2f54",
+                "799ec3e80d32577ca1c6eb10355a7fa4c9ee13385d82",
+            ),
+            concat!(
+                "a7ccf1163b6085aacff4193e6388add2f71c41668bb0d5fa1f44698eb3d8fd22",
+                "476c91b6db00254a6f94b9de03284d7297bce1062b50",
+            ),
+            concat!(
+                "759abfe4092e53789dc2e70c31567ba0c5ea0f34597ea3c8ed12375c81a6cbf0",
+                "153a5f84a9cef3183d6287acd1f61b40658aafd4f91e",
+            ),
+            concat!(
+                "43688db2d7fc21466b90b5daff24496e93b8dd02274c7196bbe0052a4f7499be",
+                "e3082d52779cc1e60b30557a9fc4e90e33587da2c7ec",
+            ),
+            concat!(
+                "11365b80a5caef14395e83a8cdf2173c6186abd0f51a3f6489aed3f81d42678c",
+                "b1d6fb20456a8fb4d9fe23486d92b7dc01264b7095ba",
+            ),
+            concat!(
+                "b5657e0c11d11b150d5c1f409da909af3a97794cd2542f1f0605dcd0efc4a92a",
+                "e3064f4abd0c14b3c38b59ba997f5a92a002848c23b9",
+            ),
+            concat!(
+                "f6a67c02fcb503aee5c2e97775a1a02a5fa7d76a9f4a2f1d819ee0b346ae0840",
+                "e8e1a8dfef1c994cdb3569020d592b45aa8cf3f614b6",
+            ),
+            concat!(
+                "a1a1c8c473c297e57197eeb037bf8e68fe8a985a8974c1a0ce3f3525ccc43e4b",
+                "823ca48c7d7f04e74d556969daffa0f099272d313ff1",
+            ),
+        ],
+    },
+];
+
+fn from_hex(hex: &str) -> Vec<u8> {
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_encode_matches_vectors() {
+    for v in VECTORS {
+        let clay = ClayCode::new(v.k, v.m, v.d).unwrap();
+        let input = from_hex(v.input_hex);
+        let chunks = clay.encode(&input).unwrap();
+
+        assert_eq!(chunks.len(), v.expected_chunks_hex.len());
+        for (i, (chunk, expected)) in chunks.iter().zip(v.expected_chunks_hex).enumerate() {
+            assert_eq!(
+                *chunk,
+                from_hex(expected),
+                "({},{},{}) chunk {} differs",
+                v.k, v.m, v.d, i
+            );
+        }
+    }
+}
+
+#[test]
+fn test_input_matches_documented_pattern() {
+    for v in VECTORS {
+        let input = from_hex(v.input_hex);
+        assert!(input.iter().enumerate().all(|(i, &b)| b == (i * 37 + 11) as u8));
+    }
+}
@@ -0,0 +1,159 @@
+//! Round-trip checks for use in downstream test suites
+//!
+//! Each check builds a code from (k, m, d), runs one path end to end and
+//! reports the first mismatch as a descriptive string, so callers can drive
+//! it from their own property tests with arbitrary parameters and erasure
+//! patterns. `partial_helper_data` gathers the helper sub-chunks a repair
+//! plan asks for, as a helper node would send them.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::{ClayCode, HashMap};
+
+/// Encode `data`, drop the `erasures` nodes, decode and compare
+///
+/// # Parameters
+/// - `k`, `m`, `d`: Code parameters
+/// - `data`: Data to encode
+/// - `erasures`: Nodes to drop before decoding
+///
+/// # Returns
+/// `Ok(())` if the decoded data starts with `data`, otherwise a message
+/// naming the code, the erasures and what went wrong
+pub fn roundtrip_check(
+    k: usize,
+    m: usize,
+    d: usize,
+    data: &[u8],
+    erasures: &[usize],
+) -> Result<(), String> {
+    let clay = ClayCode::new(k, m, d).map_err(|e| format!("({}, {}, {}): {}", k, m, d, e))?;
+    let case = || format!("({}, {}, {}) erasures {:?}", k, m, d, erasures);
+
+    let chunks = clay.encode(data).map_err(|e| format!("{}: encode failed: {}", case(), e))?;
+    let available: HashMap<usize, Vec<u8>> = chunks
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| !erasures.contains(i))
+        .collect();
+
+    let decoded = clay
+        .decode(&available, erasures)
+        .map_err(|e| format!("{}: decode failed: {}", case(), e))?;
+    if decoded.len() < data.len() {
+        return Err(format!(
+            "{}: decoded {} bytes, expected at least {}",
+            case(),
+            decoded.len(),
+            data.len()
+        ));
+    }
+    if let Some(pos) = data.iter().zip(&decoded).position(|(a, b)| a != b) {
+        return Err(format!(
+            "{}: decoded data differs at byte {} (expected {:#04x}, got {:#04x})",
+            case(),
+            pos,
+            data[pos],
+            decoded[pos]
+        ));
+    }
+    Ok(())
+}
+
+/// Build repair input by slicing the sub-chunks a plan asks for
+///
+/// # Parameters
+/// - `chunks`: Full chunks of the stripe, indexed by node
+/// - `plan`: (helper, sub-chunk indices) pairs, as from minimum_to_repair
+/// - `sub_chunk_size`: Bytes per sub-chunk
+///
+/// # Returns
+/// Map from each helper to its requested sub-chunks, concatenated in plan
+/// order as repair expects them
+pub fn partial_helper_data(
+    chunks: &[Vec<u8>],
+    plan: &[(usize, Vec<usize>)],
+    sub_chunk_size: usize,
+) -> HashMap<usize, Vec<u8>> {
+    plan.iter()
+        .map(|(helper, indices)| {
+            let mut data = Vec::with_capacity(indices.len() * sub_chunk_size);
+            for &sc in indices {
+                let bytes = sc * sub_chunk_size..(sc + 1) * sub_chunk_size;
+                data.extend_from_slice(&chunks[*helper][bytes]);
+            }
+            (*helper, data)
+        })
+        .collect()
+}
+
+/// Repair `lost_node` through minimum_to_repair and repair, and compare
+///
+/// A few stripes of deterministic data are encoded, the sub-chunks asked
+/// for by `minimum_to_repair` are gathered from every other node, and the
+/// repaired chunk must equal the encoded one.
+///
+/// # Parameters
+/// - `k`, `m`, `d`: Code parameters
+/// - `lost_node`: Node to repair
+///
+/// # Returns
+/// `Ok(())` if the repaired chunk matches, otherwise a message naming the
+/// code, the node and what went wrong
+pub fn repair_check(k: usize, m: usize, d: usize, lost_node: usize) -> Result<(), String> {
+    let clay = ClayCode::new(k, m, d).map_err(|e| format!("({}, {}, {}): {}", k, m, d, e))?;
+    let case = || format!("({}, {}, {}) lost node {}", k, m, d, lost_node);
+
+    let len = k * clay.sub_chunk_no * 8;
+    let data: Vec<u8> = (0..len).map(|i| (i.wrapping_mul(31) + 7) as u8).collect();
+    let chunks = clay.encode(&data).map_err(|e| format!("{}: encode failed: {}", case(), e))?;
+    let chunk_size = chunks[0].len();
+    let sub_chunk_size = chunk_size / clay.sub_chunk_no;
+
+    let available: Vec<usize> = (0..clay.n).filter(|&i| i != lost_node).collect();
+    let plan = clay
+        .minimum_to_repair(lost_node, &available)
+        .map_err(|e| format!("{}: minimum_to_repair failed: {}", case(), e))?;
+
+    let helper_data = partial_helper_data(&chunks, &plan, sub_chunk_size);
+    let repaired = clay
+        .repair(lost_node, &helper_data, chunk_size)
+        .map_err(|e| format!("{}: repair failed: {}", case(), e))?;
+    if repaired != chunks[lost_node] {
+        let pos = repaired.iter().zip(&chunks[lost_node]).position(|(a, b)| a != b);
+        return Err(format!("{}: repaired chunk differs at byte {:?}", case(), pos));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_check() {
+        let data: Vec<u8> = (0..3000).map(|i| (i % 256) as u8).collect();
+        assert_eq!(roundtrip_check(4, 2, 5, &data, &[]), Ok(()));
+        assert_eq!(roundtrip_check(4, 2, 5, &data, &[0, 5]), Ok(()));
+        assert_eq!(roundtrip_check(5, 3, 7, &data, &[1, 2, 6]), Ok(()));
+
+        let err = roundtrip_check(4, 2, 5, &data, &[0, 1, 2]).unwrap_err();
+        assert!(err.contains("(4, 2, 5) erasures [0, 1, 2]"), "{}", err);
+        assert!(roundtrip_check(4, 2, 9, &data, &[]).is_err());
+    }
+
+    #[test]
+    fn test_repair_check() {
+        for lost in 0..6 {
+            assert_eq!(repair_check(4, 2, 5, lost), Ok(()));
+        }
+        for lost in [0, 4, 7] {
+            assert_eq!(repair_check(5, 3, 7, lost), Ok(()));
+        }
+
+        let err = repair_check(4, 2, 5, 6).unwrap_err();
+        assert!(err.contains("lost node 6"), "{}", err);
+    }
+}
@@ -11,86 +11,160 @@
 //! ```
 //!
 //! γ must satisfy: γ ≠ 0 and γ² ≠ 1
+//!
+//! γ is fixed per code, so every multiplication in the transforms is by one
+//! of a few constants. [`GfMulTable`] precomputes those products once and
+//! the transform loops become plain table lookups. With the `simd` feature
+//! the full pairwise transforms shuffle 16 bytes at a time instead.
+
+use alloc::vec;
+use alloc::vec::Vec;
 
-use reed_solomon_erasure::galois_8::{add as gf_add, mul as gf_mul, div as gf_div};
+use crate::galois::{add as gf_add, coupling_det, inv as gf_inv, mul as gf_mul};
+#[cfg(feature = "simd")]
+use crate::simd::{self, NibbleTable};
 
-/// Gamma value for pairwise transforms.
+/// Default gamma value for pairwise transforms.
 /// Must satisfy: γ ≠ 0, γ² ≠ 1
 /// In GF(2^8), 2 works well since 2² = 4 ≠ 1
-pub const GAMMA: u8 = 2;
+pub const DEFAULT_GAMMA: u8 = 2;
+
+/// Check that γ keeps the pairwise transform invertible: γ ≠ 0, γ² ≠ 1
+pub fn is_valid_gamma(gamma: u8) -> bool {
+    gamma != 0 && gf_mul(gamma, gamma) != 1
+}
+
+/// Multiply-by-constant tables for one γ
+///
+/// Holds the products by γ, 1/γ, det = 1 + γ² and 1/det for every byte.
+#[derive(Clone)]
+pub struct GfMulTable {
+    gamma: u8,
+    by_gamma: [u8; 256],
+    by_gamma_inv: [u8; 256],
+    by_det: [u8; 256],
+    by_det_inv: [u8; 256],
+    #[cfg(feature = "simd")]
+    nibbles_gamma: NibbleTable,
+    #[cfg(feature = "simd")]
+    nibbles_det_inv: NibbleTable,
+}
+
+impl GfMulTable {
+    /// Build the tables for a γ that satisfies [`is_valid_gamma`]
+    pub fn new(gamma: u8) -> Self {
+        debug_assert!(is_valid_gamma(gamma));
+        let det = coupling_det(gamma);
+        let build = |factor: u8| {
+            let mut table = [0u8; 256];
+            for (x, product) in table.iter_mut().enumerate() {
+                *product = gf_mul(factor, x as u8);
+            }
+            table
+        };
+
+        let by_gamma = build(gamma);
+        let by_det_inv = build(gf_inv(det));
+        GfMulTable {
+            gamma,
+            #[cfg(feature = "simd")]
+            nibbles_gamma: NibbleTable::new(&by_gamma),
+            #[cfg(feature = "simd")]
+            nibbles_det_inv: NibbleTable::new(&by_det_inv),
+            by_gamma,
+            by_gamma_inv: build(gf_inv(gamma)),
+            by_det: build(det),
+            by_det_inv,
+        }
+    }
 
-/// GF(2^8) multiplicative inverse: a^(-1) = 1/a
-#[inline]
-pub fn gf_inv(a: u8) -> u8 {
-    gf_div(1, a)
+    /// The γ these tables were built for
+    pub fn gamma(&self) -> u8 {
+        self.gamma
+    }
+
+    /// γ * x
+    #[inline]
+    pub fn mul_gamma(&self, x: u8) -> u8 {
+        self.by_gamma[x as usize]
+    }
+
+    /// x / γ
+    #[inline]
+    pub fn div_gamma(&self, x: u8) -> u8 {
+        self.by_gamma_inv[x as usize]
+    }
+
+    /// det * x
+    #[inline]
+    pub fn mul_det(&self, x: u8) -> u8 {
+        self.by_det[x as usize]
+    }
+
+    /// x / det
+    #[inline]
+    pub fn div_det(&self, x: u8) -> u8 {
+        self.by_det_inv[x as usize]
+    }
+}
+
+impl core::fmt::Debug for GfMulTable {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("GfMulTable").field("gamma", &self.gamma).finish()
+    }
 }
 
 /// PRT: Pairwise Reverse Transform (C-plane → U-plane)
 ///
-/// Computes both U and U* from C and C*:
+/// Computes both U and U* from C and C*, writing them into slices of the
+/// same length as `c`:
 /// ```text
 /// [U ]   [1  γ] [C ]
 /// [U*] = [γ  1] [C*]
 /// ```
-///
-/// # Arguments
-/// * `c` - C values (primary)
-/// * `c_star` - C* values (companion)
-///
-/// # Returns
-/// Tuple of (U, U*) vectors
-pub fn prt_compute_both(c: &[u8], c_star: &[u8]) -> (Vec<u8>, Vec<u8>) {
-    let len = c.len();
-    let mut u = vec![0u8; len];
-    let mut u_star = vec![0u8; len];
-
-    for i in 0..len {
+pub fn prt_compute_both_into(
+    c: &[u8],
+    c_star: &[u8],
+    mul: &GfMulTable,
+    out_u: &mut [u8],
+    out_u_star: &mut [u8],
+) {
+    #[cfg(feature = "simd")]
+    let done = simd::prt_both(c, c_star, &mul.nibbles_gamma, out_u, out_u_star);
+    #[cfg(not(feature = "simd"))]
+    let done = 0;
+    for i in done..c.len() {
         // U = C + γ*C*
-        u[i] = gf_add(c[i], gf_mul(GAMMA, c_star[i]));
+        out_u[i] = gf_add(c[i], mul.mul_gamma(c_star[i]));
         // U* = γ*C + C*
-        u_star[i] = gf_add(gf_mul(GAMMA, c[i]), c_star[i]);
+        out_u_star[i] = gf_add(mul.mul_gamma(c[i]), c_star[i]);
     }
-
-    (u, u_star)
 }
 
-/// PRT with orientation handling
-///
-/// Given c_xy at node (x,y) layer z, and c_sw at node (z_y,y) layer z_sw:
-/// - If xy_is_primary (x < z_y): c_xy is C, c_sw is C*
-/// - Otherwise (x > z_y): c_xy is C*, c_sw is C
+/// PRT for a single vertex of a coupled pair
 ///
-/// # Returns
-/// Tuple of (u_xy, u_sw) - U values for each node at their respective layers
-pub fn prt_compute_both_oriented(c_xy: &[u8], c_sw: &[u8], xy_is_primary: bool) -> (Vec<u8>, Vec<u8>) {
-    let len = c_xy.len();
-    let mut u_xy = vec![0u8; len];
-    let mut u_sw = vec![0u8; len];
-
-    if xy_is_primary {
-        // c_xy is C (primary), c_sw is C* (starred)
-        // u_xy = U = C + γ*C* = c_xy + γ*c_sw
-        // u_sw = U* = γ*C + C* = γ*c_xy + c_sw
-        for i in 0..len {
-            u_xy[i] = gf_add(c_xy[i], gf_mul(GAMMA, c_sw[i]));
-            u_sw[i] = gf_add(gf_mul(GAMMA, c_xy[i]), c_sw[i]);
-        }
-    } else {
-        // c_xy is C* (starred), c_sw is C (primary)
-        // u_xy = U* = γ*C + C* = γ*c_sw + c_xy
-        // u_sw = U = C + γ*C* = c_sw + γ*c_xy
-        for i in 0..len {
-            u_xy[i] = gf_add(gf_mul(GAMMA, c_sw[i]), c_xy[i]);
-            u_sw[i] = gf_add(c_sw[i], gf_mul(GAMMA, c_xy[i]));
-        }
-    }
+/// The transform matrix is symmetric, so the U value of either vertex is
+/// its own C plus γ times its companion's C, whichever side is primary:
+/// ```text
+/// U_xy = C_xy + γ*C_sw
+/// ```
+pub fn prt_compute_single(c_xy: &[u8], c_sw: &[u8], mul: &GfMulTable) -> Vec<u8> {
+    let mut u_xy = vec![0u8; c_xy.len()];
+    prt_compute_single_into(c_xy, c_sw, mul, &mut u_xy);
+    u_xy
+}
 
-    (u_xy, u_sw)
+/// Single-vertex PRT writing U_xy into a caller-provided slice
+pub fn prt_compute_single_into(c_xy: &[u8], c_sw: &[u8], mul: &GfMulTable, out_u_xy: &mut [u8]) {
+    for ((out, &c), &c_star) in out_u_xy.iter_mut().zip(c_xy).zip(c_sw) {
+        *out = gf_add(c, mul.mul_gamma(c_star));
+    }
 }
 
 /// PFT: Pairwise Forward Transform (U-plane → C-plane)
 ///
-/// Computes both C and C* from U and U*:
+/// Computes both C and C* from U and U*, writing them into slices of the
+/// same length as `u`:
 /// ```text
 /// [C ]   [1  γ]⁻¹ [U ]
 /// [C*] = [γ  1]   [U*]
@@ -98,120 +172,228 @@ pub fn prt_compute_both_oriented(c_xy: &[u8], c_sw: &[u8], xy_is_primary: bool)
 ///
 /// The inverse matrix is: (1/(1-γ²)) × [1, -γ; -γ, 1]
 /// In GF(2^8), subtraction = addition, so: (1/(1+γ²)) × [1, γ; γ, 1]
-///
-/// # Arguments
-/// * `u` - U values (primary)
-/// * `u_star` - U* values (companion)
-///
-/// # Returns
-/// Tuple of (C, C*) vectors
-pub fn pft_compute_both(u: &[u8], u_star: &[u8]) -> (Vec<u8>, Vec<u8>) {
-    let len = u.len();
-    let mut c = vec![0u8; len];
-    let mut c_star = vec![0u8; len];
-
-    // det = 1 - γ² = 1 + γ² (in GF(2^8), subtraction = addition)
-    let det = gf_add(1, gf_mul(GAMMA, GAMMA));
-    let det_inv = gf_inv(det);
-
-    for i in 0..len {
+pub fn pft_compute_both_into(
+    u: &[u8],
+    u_star: &[u8],
+    mul: &GfMulTable,
+    out_c: &mut [u8],
+    out_c_star: &mut [u8],
+) {
+    #[cfg(feature = "simd")]
+    let done =
+        simd::pft_both(u, u_star, &mul.nibbles_gamma, &mul.nibbles_det_inv, out_c, out_c_star);
+    #[cfg(not(feature = "simd"))]
+    let done = 0;
+    for i in done..u.len() {
         // C = (U + γ*U*) / det
-        c[i] = gf_mul(gf_add(u[i], gf_mul(GAMMA, u_star[i])), det_inv);
+        out_c[i] = mul.div_det(gf_add(u[i], mul.mul_gamma(u_star[i])));
         // C* = (γ*U + U*) / det
-        c_star[i] = gf_mul(gf_add(gf_mul(GAMMA, u[i]), u_star[i]), det_inv);
+        out_c_star[i] = mul.div_det(gf_add(mul.mul_gamma(u[i]), u_star[i]));
     }
-
-    (c, c_star)
 }
 
-/// Compute C from U and C* (partial PFT)
+/// Compute C from U and C* (partial PFT), writing C into a caller-provided slice
 ///
 /// Used when we have U at one vertex and C* at its companion.
 /// From the PRT equation: U = C + γ*C*
 /// Therefore: C = U - γ*C* = U + γ*C* (in GF(2^8))
-pub fn compute_c_from_u_and_cstar(u_xy: &[u8], c_companion: &[u8]) -> Vec<u8> {
-    let len = u_xy.len();
-    let mut c = vec![0u8; len];
-
-    for i in 0..len {
+pub fn compute_c_from_u_and_cstar_into(
+    u_xy: &[u8],
+    c_companion: &[u8],
+    mul: &GfMulTable,
+    out_c: &mut [u8],
+) {
+    for i in 0..u_xy.len() {
         // C = U + γ*C* (using the fact that U = C + γ*C*)
-        c[i] = gf_add(u_xy[i], gf_mul(GAMMA, c_companion[i]));
+        out_c[i] = gf_add(u_xy[i], mul.mul_gamma(c_companion[i]));
     }
-
-    c
 }
 
-/// Compute U from C and U* (partial transform)
+/// Compute U from C and U* (partial transform), writing U into a
+/// caller-provided slice
 ///
 /// From PFT inverse, given C and U*:
 /// det * C = U + γ*U*
 /// Therefore: U = det*C + γ*U* (in GF(2^8))
-pub fn compute_u_from_c_and_ustar(c_xy: &[u8], u_companion: &[u8]) -> Vec<u8> {
-    let len = c_xy.len();
-    let mut u = vec![0u8; len];
-
-    let det = gf_add(1, gf_mul(GAMMA, GAMMA));
-
-    for i in 0..len {
+pub fn compute_u_from_c_and_ustar_into(
+    c_xy: &[u8],
+    u_companion: &[u8],
+    mul: &GfMulTable,
+    out_u: &mut [u8],
+) {
+    for i in 0..c_xy.len() {
         // U = det*C + γ*U*
-        u[i] = gf_add(gf_mul(det, c_xy[i]), gf_mul(GAMMA, u_companion[i]));
+        out_u[i] = gf_add(mul.mul_det(c_xy[i]), mul.mul_gamma(u_companion[i]));
     }
-
-    u
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// (U, U*) of a pair through prt_compute_both_into
+    fn prt(c: &[u8], c_star: &[u8], mul: &GfMulTable) -> (Vec<u8>, Vec<u8>) {
+        let (mut u, mut u_star) = (vec![0u8; c.len()], vec![0u8; c.len()]);
+        prt_compute_both_into(c, c_star, mul, &mut u, &mut u_star);
+        (u, u_star)
+    }
+
+    /// (C, C*) of a pair through pft_compute_both_into
+    fn pft(u: &[u8], u_star: &[u8], mul: &GfMulTable) -> (Vec<u8>, Vec<u8>) {
+        let (mut c, mut c_star) = (vec![0u8; u.len()], vec![0u8; u.len()]);
+        pft_compute_both_into(u, u_star, mul, &mut c, &mut c_star);
+        (c, c_star)
+    }
+
+    /// C from U and C* through compute_c_from_u_and_cstar_into
+    fn c_from_u(u: &[u8], c_star: &[u8], mul: &GfMulTable) -> Vec<u8> {
+        let mut c = vec![0u8; u.len()];
+        compute_c_from_u_and_cstar_into(u, c_star, mul, &mut c);
+        c
+    }
+
+    /// U from C and U* through compute_u_from_c_and_ustar_into
+    fn u_from_c(c: &[u8], u_star: &[u8], mul: &GfMulTable) -> Vec<u8> {
+        let mut u = vec![0u8; c.len()];
+        compute_u_from_c_and_ustar_into(c, u_star, mul, &mut u);
+        u
+    }
+
     #[test]
     fn test_gamma_properties() {
         // Verify γ ≠ 0
-        assert_ne!(GAMMA, 0);
+        assert_ne!(DEFAULT_GAMMA, 0);
         // Verify γ² ≠ 1
-        let gamma_sq = gf_mul(GAMMA, GAMMA);
+        let gamma_sq = gf_mul(DEFAULT_GAMMA, DEFAULT_GAMMA);
         assert_ne!(gamma_sq, 1);
+        assert!(is_valid_gamma(DEFAULT_GAMMA));
+
+        // 0 and 1 are the only γ with γ = 0 or γ² = 1 in characteristic 2
+        assert!(!is_valid_gamma(0));
+        assert!(!is_valid_gamma(1));
+        assert!((2..=255).all(is_valid_gamma));
+    }
+
+    #[test]
+    fn test_prt_pft_roundtrip_any_gamma() {
+        let c = vec![0x12, 0x34, 0x56, 0x78];
+        let c_star = vec![0xAB, 0xCD, 0xEF, 0x01];
+
+        for gamma in [3u8, 0x1D, 0x80, 0xFF] {
+            let mul = GfMulTable::new(gamma);
+            let (u, u_star) = prt(&c, &c_star, &mul);
+            let (c_back, c_star_back) = pft(&u, &u_star, &mul);
+            assert_eq!(c, c_back, "gamma = {}", gamma);
+            assert_eq!(c_star, c_star_back, "gamma = {}", gamma);
+        }
     }
 
     #[test]
     fn test_prt_pft_roundtrip() {
+        let mul = GfMulTable::new(DEFAULT_GAMMA);
         let c = vec![0x12, 0x34, 0x56, 0x78];
         let c_star = vec![0xAB, 0xCD, 0xEF, 0x01];
 
         // C → U via PRT
-        let (u, u_star) = prt_compute_both(&c, &c_star);
+        let (u, u_star) = prt(&c, &c_star, &mul);
 
         // U → C via PFT
-        let (c_back, c_star_back) = pft_compute_both(&u, &u_star);
+        let (c_back, c_star_back) = pft(&u, &u_star, &mul);
 
         assert_eq!(c, c_back);
         assert_eq!(c_star, c_star_back);
     }
 
+    #[test]
+    fn test_prt_single_matches_both_orientations() {
+        let mul = GfMulTable::new(DEFAULT_GAMMA);
+        let c = vec![0x12, 0x34, 0x56, 0x78];
+        let c_star = vec![0xAB, 0xCD, 0xEF, 0x01];
+
+        let (u, u_star) = prt(&c, &c_star, &mul);
+        assert_eq!(prt_compute_single(&c, &c_star, &mul), u);
+        assert_eq!(prt_compute_single(&c_star, &c, &mul), u_star);
+    }
+
+    #[test]
+    fn test_prt_single_into_matches_allocating() {
+        let c = vec![0x12, 0x34, 0x56, 0x78, 0x9A];
+        let c_star = vec![0xAB, 0xCD, 0xEF, 0x01, 0x23];
+        let mul = GfMulTable::new(DEFAULT_GAMMA);
+        let mut a = vec![0u8; c.len()];
+
+        prt_compute_single_into(&c, &c_star, &mul, &mut a);
+        assert_eq!(a, prt_compute_single(&c, &c_star, &mul));
+    }
+
     #[test]
     fn test_partial_transform_roundtrips() {
-        // Test that compute_c_from_u_and_cstar and compute_u_from_c_and_ustar
-        // are consistent with prt_compute_both / pft_compute_both
+        let mul = GfMulTable::new(DEFAULT_GAMMA);
+        // Test that the partial transforms are consistent with the full
+        // PRT and PFT
         let c = vec![0x12, 0x34, 0x56, 0x78];
         let c_star = vec![0xAB, 0xCD, 0xEF, 0x01];
 
         // Full PRT: (C, C*) -> (U, U*)
-        let (u, u_star) = prt_compute_both(&c, &c_star);
+        let (u, u_star) = prt(&c, &c_star, &mul);
 
         // Partial: given U and C*, recover C
-        let c_recovered = compute_c_from_u_and_cstar(&u, &c_star);
-        assert_eq!(c, c_recovered, "compute_c_from_u_and_cstar failed");
+        let c_recovered = c_from_u(&u, &c_star, &mul);
+        assert_eq!(c, c_recovered, "compute_c_from_u_and_cstar_into failed");
 
         // Partial: given C and U*, recover U
-        let u_recovered = compute_u_from_c_and_ustar(&c, &u_star);
-        assert_eq!(u, u_recovered, "compute_u_from_c_and_ustar failed");
+        let u_recovered = u_from_c(&c, &u_star, &mul);
+        assert_eq!(u, u_recovered, "compute_u_from_c_and_ustar_into failed");
 
         // Also verify with PFT roundtrip
-        let (c_back, c_star_back) = pft_compute_both(&u, &u_star);
+        let (c_back, c_star_back) = pft(&u, &u_star, &mul);
         assert_eq!(c, c_back);
         assert_eq!(c_star, c_star_back);
     }
 
+    #[test]
+    fn test_mul_table_matches_gf_mul() {
+        for gamma in [DEFAULT_GAMMA, 3, 0x1D, 0x80, 0xFF] {
+            let mul = GfMulTable::new(gamma);
+            let det = gf_add(1, gf_mul(gamma, gamma));
+
+            for x in 0..=255u8 {
+                assert_eq!(mul.mul_gamma(x), gf_mul(gamma, x));
+                assert_eq!(mul.div_gamma(x), gf_mul(x, gf_inv(gamma)));
+                assert_eq!(mul.mul_det(x), gf_mul(det, x));
+                assert_eq!(mul.div_det(x), gf_mul(x, gf_inv(det)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_table_transforms_match_gf_mul() {
+        // Every byte pair, checked against the transforms written with gf_mul
+        let c: Vec<u8> = (0..=255u8).flat_map(|a| std::iter::repeat(a).take(256)).collect();
+        let c_star: Vec<u8> = (0..256).flat_map(|_| 0..=255u8).collect();
+
+        for gamma in [DEFAULT_GAMMA, 0x1D, 0xFF] {
+            let mul = GfMulTable::new(gamma);
+            let det = gf_add(1, gf_mul(gamma, gamma));
+            let det_inv = gf_inv(det);
+
+            let (u, u_star) = prt(&c, &c_star, &mul);
+            let (pc, pc_star) = pft(&c, &c_star, &mul);
+            let from_cstar = c_from_u(&c, &c_star, &mul);
+            let from_ustar = u_from_c(&c, &c_star, &mul);
+
+            for i in 0..c.len() {
+                let (a, b) = (c[i], c_star[i]);
+                assert_eq!(u[i], gf_add(a, gf_mul(gamma, b)));
+                assert_eq!(u_star[i], gf_add(gf_mul(gamma, a), b));
+                assert_eq!(pc[i], gf_mul(gf_add(a, gf_mul(gamma, b)), det_inv));
+                assert_eq!(pc_star[i], gf_mul(gf_add(gf_mul(gamma, a), b), det_inv));
+                assert_eq!(from_cstar[i], gf_add(a, gf_mul(gamma, b)));
+                assert_eq!(from_ustar[i], gf_add(gf_mul(det, a), gf_mul(gamma, b)));
+            }
+        }
+    }
+
     #[test]
     fn test_gf_arithmetic() {
         // Addition is XOR
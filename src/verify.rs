@@ -0,0 +1,349 @@
+//! Stripe consistency checks and error correction for Clay codes
+//!
+//! A full stripe is consistent when every uncoupled layer is a valid RS
+//! codeword. Checking this needs no reconstruction: the U-plane is derived
+//! from the stored chunks with PRT and each layer's parity is re-encoded.
+//!
+//! Corrupted chunks are located per node rather than per layer. PRT spreads
+//! a corrupted sub-chunk over two layers, and a whole rotten chunk can break
+//! up to q U values of one layer, so per-layer RS correction would exceed
+//! its floor(m/2) budget. At node level the code has distance m + 1, so any
+//! floor(m/2) corrupted chunks are found by erasing candidate sets and
+//! keeping the smallest one whose reconstruction is consistent.
+
+use alloc::borrow::Cow;
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use alloc::{format, vec};
+
+use crate::decode::{decode_layered, get_companion_layer, DecodeParams};
+use crate::encode::validate_sub_chunk_size;
+use crate::error::ClayError;
+use crate::transforms::prt_compute_single;
+
+/// Chunks by internal node index, borrowing the caller's where possible
+type InternalChunks<'a> = Vec<Cow<'a, [u8]>>;
+
+/// Check that a full stripe's parity matches its data
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `chunks`: All n chunks in node order
+///
+/// # Returns
+/// Ok(true) if every layer is consistent, Ok(false) on any mismatch, or
+/// error if the chunks are malformed
+pub fn verify(params: &DecodeParams, chunks: &[Vec<u8>]) -> Result<bool, ClayError> {
+    let (internal, sub_chunk_size) = internal_chunks(params, chunks)?;
+    stripe_consistent(params, &internal, sub_chunk_size)
+}
+
+/// Most suspect sets `decode_with_correction` tries before giving up
+///
+/// Each one costs a full layered decode plus a parity check of the
+/// stripe, so the search is cut at this many candidates.
+pub const MAX_CORRECTION_CANDIDATES: usize = 1024;
+
+/// Recover data from a full stripe, locating and correcting corrupted chunks
+///
+/// Requires m >= 2. Suspect sets are tried smallest first, each with a full
+/// decode and parity check, so locating e corrupted chunks costs up to
+/// C(n, 1) + ... + C(n, e) decodes. Up to floor(m/2) corrupted chunks are
+/// corrected, fewer on wide codes: e stops at the largest value whose
+/// candidate count fits in MAX_CORRECTION_CANDIDATES.
+///
+/// # Parameters
+/// - `params`: Code parameters
+/// - `chunks`: All n chunks in node order
+///
+/// # Returns
+/// The recovered data (as decode() returns it) and the sorted indices of
+/// the corrupted chunks, or error if the input is malformed or the
+/// corruption cannot be located
+pub fn decode_with_correction(
+    params: &DecodeParams,
+    chunks: &[Vec<u8>],
+) -> Result<(Vec<u8>, Vec<usize>), ClayError> {
+    if params.m < 2 {
+        return Err(ClayError::InvalidParameters(format!(
+            "Error correction needs m >= 2, got m = {}",
+            params.m
+        )));
+    }
+
+    let (internal, sub_chunk_size) = internal_chunks(params, chunks)?;
+    if stripe_consistent(params, &internal, sub_chunk_size)? {
+        return Ok((chunks[..params.k].concat(), Vec::new()));
+    }
+
+    // Try every set of suspects, smallest first. Any two consistent
+    // reconstructions from at most floor(m/2) suspects each differ in at
+    // most m nodes, so they are the same codeword.
+    let mut trial: Vec<Vec<u8>> = internal.iter().map(|c| c.to_vec()).collect();
+    let max_errors = correctable_errors(params.n, params.m);
+    for count in 1..=max_errors {
+        let mut suspects: Vec<usize> = (0..count).collect();
+        loop {
+            let erased: BTreeSet<usize> = suspects.iter().map(|&i| params.to_internal(i)).collect();
+
+            decode_layered(params, &erased, &mut trial, sub_chunk_size)?;
+
+            if stripe_consistent(params, &trial, sub_chunk_size)? {
+                trial.truncate(params.k);
+                return Ok((trial.concat(), suspects));
+            }
+
+            // Put the supplied chunks back before the next guess
+            for &node in &erased {
+                trial[node].copy_from_slice(&internal[node]);
+            }
+
+            if !next_combination(&mut suspects, params.n) {
+                break;
+            }
+        }
+    }
+
+    Err(ClayError::ReconstructionFailed(format!(
+        "Could not locate corrupted chunks; more than {} may be corrupted",
+        max_errors
+    )))
+}
+
+/// Largest e <= floor(m/2) whose suspect sets C(n, 1) + ... + C(n, e)
+/// number at most MAX_CORRECTION_CANDIDATES
+fn correctable_errors(n: usize, m: usize) -> usize {
+    let mut total = 0usize;
+    let mut binomial = 1usize;
+    for e in 1..=m / 2 {
+        // C(n, e) = C(n, e - 1) * (n - e + 1) / e, exact at every step
+        binomial = match binomial.checked_mul(n - e + 1) {
+            Some(product) => product / e,
+            None => return e - 1,
+        };
+        total = total.saturating_add(binomial);
+        if total > MAX_CORRECTION_CANDIDATES {
+            return e - 1;
+        }
+    }
+    m / 2
+}
+
+/// Check every uncoupled layer of a stripe laid out by internal index
+fn stripe_consistent<C: AsRef<[u8]>>(
+    params: &DecodeParams,
+    chunks: &[C],
+    sub_chunk_size: usize,
+) -> Result<bool, ClayError> {
+    for z in 0..params.sub_chunk_no {
+        let layer = uncoupled_layer(params, chunks, z, sub_chunk_size);
+        let consistent = params.rs.verify(&layer)?;
+        if !consistent {
+            return Ok(false);
+        }
+    }
+
+    Ok(true)
+}
+
+/// Advance `comb` to the next ascending combination of 0..n
+///
+/// Returns false once the last combination has been passed.
+fn next_combination(comb: &mut [usize], n: usize) -> bool {
+    let len = comb.len();
+    for i in (0..len).rev() {
+        if comb[i] < n - len + i {
+            comb[i] += 1;
+            for j in i + 1..len {
+                comb[j] = comb[j - 1] + 1;
+            }
+            return true;
+        }
+    }
+    false
+}
+
+/// Validate a full stripe and lay it out by internal node index
+///
+/// Shortened nodes are filled with zeros. Returns the internal chunks and
+/// the sub-chunk size.
+fn internal_chunks<'a>(
+    params: &DecodeParams,
+    chunks: &'a [Vec<u8>],
+) -> Result<(InternalChunks<'a>, usize), ClayError> {
+    if chunks.len() != params.n {
+        return Err(ClayError::InvalidParameters(format!(
+            "Expected {} chunks, got {}",
+            params.n,
+            chunks.len()
+        )));
+    }
+
+    let chunk_size = chunks[0].len();
+    validate_sub_chunk_size(params.sub_chunk_no, chunk_size)?;
+    for (idx, chunk) in chunks.iter().enumerate() {
+        if chunk.len() != chunk_size {
+            return Err(ClayError::InconsistentChunkSizes {
+                first_size: chunk_size,
+                mismatched_idx: idx,
+                mismatched_size: chunk.len(),
+            });
+        }
+    }
+
+    let total_nodes = params.q * params.t;
+    let mut internal = Vec::with_capacity(total_nodes);
+    internal.extend(chunks[..params.k].iter().map(|c| c.as_slice().into()));
+    internal.extend((0..params.nu).map(|_| vec![0u8; chunk_size].into()));
+    internal.extend(chunks[params.k..].iter().map(|c| c.as_slice().into()));
+
+    Ok((internal, chunk_size / params.sub_chunk_no))
+}
+
+/// Compute the U values of layer `z` from a full stripe of C values
+fn uncoupled_layer<C: AsRef<[u8]>>(
+    params: &DecodeParams,
+    chunks: &[C],
+    z: usize,
+    sub_chunk_size: usize,
+) -> Vec<Vec<u8>> {
+    let z_vec = params.plane_vector(z);
+    let offset_z = z * sub_chunk_size;
+
+    (0..params.q * params.t)
+        .map(|node_xy| {
+            let x = node_xy % params.q;
+            let y = node_xy / params.q;
+            let z_y = z_vec[y];
+            let c_xy = &chunks[node_xy].as_ref()[offset_z..offset_z + sub_chunk_size];
+
+            if z_y == x {
+                // Red vertex: U = C
+                c_xy.to_vec()
+            } else {
+                let node_sw = y * params.q + z_y;
+                let offset_zsw = get_companion_layer(params, z, x, y) * sub_chunk_size;
+                let c_sw = &chunks[node_sw].as_ref()[offset_zsw..offset_zsw + sub_chunk_size];
+                prt_compute_single(c_xy, c_sw, params.mul)
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ClayCode;
+
+    #[test]
+    fn test_verify_detects_corruption() {
+        let code = ClayCode::new(5, 3, 7).unwrap();
+        let params = code.encode_params();
+        let data: Vec<u8> = (0..1000).map(|i| (i % 256) as u8).collect();
+        let mut chunks = code.encode(&data).unwrap();
+
+        assert!(verify(&params, &chunks).unwrap());
+
+        // A single flipped byte in any node breaks consistency
+        for node in 0..params.n {
+            chunks[node][7] ^= 0x40;
+            assert!(!verify(&params, &chunks).unwrap(), "Node {} not detected", node);
+            chunks[node][7] ^= 0x40;
+        }
+    }
+
+    #[test]
+    fn test_decode_with_correction_locates_corruption() {
+        let code = ClayCode::new(6, 4, 8).unwrap();
+        let params = code.encode_params();
+        let data: Vec<u8> = (0..3000).map(|i| (i * 31 % 256) as u8).collect();
+        let chunks = code.encode(&data).unwrap();
+
+        let (decoded, corrupted) = decode_with_correction(&params, &chunks).unwrap();
+        assert_eq!(&decoded[..data.len()], &data[..]);
+        assert!(corrupted.is_empty());
+
+        // Whole-chunk rot in one node, and partial rot in two nodes
+        let cases: Vec<Vec<usize>> = vec![vec![0], vec![7], vec![2, 9], vec![4, 5]];
+        for bad in cases {
+            let mut rotten = chunks.clone();
+            for &node in &bad {
+                for (i, byte) in rotten[node].iter_mut().enumerate() {
+                    if node == 0 || i % 5 == 0 {
+                        *byte ^= 0xA5;
+                    }
+                }
+            }
+
+            let (decoded, corrupted) = decode_with_correction(&params, &rotten).unwrap();
+            assert_eq!(&decoded[..data.len()], &data[..], "Failed for {:?}", bad);
+            assert_eq!(corrupted, bad);
+        }
+    }
+
+    #[test]
+    fn test_decode_with_correction_limits() {
+        let code = ClayCode::new(4, 2, 5).unwrap();
+        let params = code.encode_params();
+        let mut chunks = code.encode(&[9; 100]).unwrap();
+
+        // Two corrupted chunks exceed floor(m/2) = 1
+        chunks[0][0] ^= 1;
+        chunks[1][0] ^= 1;
+        assert!(matches!(
+            decode_with_correction(&params, &chunks),
+            Err(ClayError::ReconstructionFailed(_))
+        ));
+    }
+
+    #[test]
+    fn test_correctable_errors() {
+        assert_eq!(correctable_errors(6, 2), 1);
+        assert_eq!(correctable_errors(10, 4), 2);
+        // 20 + 190 + 1140 suspect sets exceed the cap, so e = 3 is cut
+        assert_eq!(correctable_errors(20, 10), 2);
+        assert_eq!(correctable_errors(MAX_CORRECTION_CANDIDATES + 1, 4), 0);
+    }
+
+    #[test]
+    fn test_next_combination() {
+        let mut comb = vec![0, 1];
+        let mut all = vec![comb.clone()];
+        while next_combination(&mut comb, 4) {
+            all.push(comb.clone());
+        }
+        assert_eq!(
+            all,
+            vec![vec![0, 1], vec![0, 2], vec![0, 3], vec![1, 2], vec![1, 3], vec![2, 3]]
+        );
+    }
+
+    #[test]
+    fn test_verify_rejects_malformed_input() {
+        let code = ClayCode::new(4, 2, 5).unwrap();
+        let params = code.encode_params();
+        let mut chunks = code.encode(&[1, 2, 3]).unwrap();
+
+        assert!(matches!(
+            verify(&params, &chunks[1..]),
+            Err(ClayError::InvalidParameters(_))
+        ));
+
+        chunks[3].pop();
+        assert!(matches!(
+            verify(&params, &chunks),
+            Err(ClayError::InconsistentChunkSizes { mismatched_idx: 3, .. })
+        ));
+
+        // One-byte sub-chunks are rejected as decode would
+        let tiny = vec![vec![7u8; code.sub_chunk_no]; params.n];
+        assert!(matches!(
+            verify(&params, &tiny),
+            Err(ClayError::ChunkTooSmall { min: 16, actual: 8 })
+        ));
+        assert!(matches!(
+            decode_with_correction(&params, &tiny),
+            Err(ClayError::ChunkTooSmall { min: 16, actual: 8 })
+        ));
+    }
+}
@@ -0,0 +1,89 @@
+//! Non-fatal warnings about code parameters
+//!
+//! Every code [`ClayCode::new`](crate::ClayCode::new) accepts works, but
+//! some cost more than they need to. [`ClayCode::new_checked`](crate::ClayCode::new_checked)
+//! reports these so tooling can pass them on to operators.
+
+use core::fmt;
+
+use alloc::vec::Vec;
+
+use crate::ClayCode;
+
+/// α above which a code gets [`ClayWarning::LargeSubPacketization`]
+pub const LARGE_SUB_CHUNK_NO: usize = 1 << 12;
+
+/// A valid but inadvisable parameter choice
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ClayWarning {
+    /// α is above [`LARGE_SUB_CHUNK_NO`]: every chunk splits into that many
+    /// sub-chunks, so sub-chunks are tiny unless chunks are very large
+    LargeSubPacketization { alpha: usize },
+    /// d is below k + m - 1, so repair downloads more than the minimum
+    /// d / (k * q) allows with every survivor helping
+    SuboptimalD { d: usize, max_d: usize },
+    /// The nu zero nodes added by shortening are more than a quarter of
+    /// the k data nodes, and every layer computes over them as well
+    LargeNu { nu: usize, k: usize },
+}
+
+impl fmt::Display for ClayWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClayWarning::LargeSubPacketization { alpha } => {
+                write!(f, "Sub-packetization {} is above {}", alpha, LARGE_SUB_CHUNK_NO)
+            }
+            ClayWarning::SuboptimalD { d, max_d } => {
+                write!(f, "d = {} is below {}, repair reads more than necessary", d, max_d)
+            }
+            ClayWarning::LargeNu { nu, k } => {
+                write!(f, "Shortening adds {} zero nodes to {} data nodes", nu, k)
+            }
+        }
+    }
+}
+
+/// Collect the warnings that apply to `code`
+pub(crate) fn warnings_for(code: &ClayCode) -> Vec<ClayWarning> {
+    let mut warnings = Vec::new();
+    if code.sub_chunk_no > LARGE_SUB_CHUNK_NO {
+        warnings.push(ClayWarning::LargeSubPacketization { alpha: code.sub_chunk_no });
+    }
+    // With m = 1, d = k is the only choice
+    let max_d = code.k + code.m - 1;
+    if code.d < max_d {
+        warnings.push(ClayWarning::SuboptimalD { d: code.d, max_d });
+    }
+    if code.nu * 4 > code.k {
+        warnings.push(ClayWarning::LargeNu { nu: code.nu, k: code.k });
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_checked_warnings() {
+        // d = k + m - 1 and no shortening: nothing to report
+        let (code, warnings) = ClayCode::new_checked(10, 4, 13).unwrap();
+        assert_eq!(code.d, 13);
+        assert!(warnings.is_empty(), "{:?}", warnings);
+        assert!(ClayCode::new_checked(4, 1, 4).unwrap().1.is_empty());
+
+        let (_, warnings) = ClayCode::new_checked(10, 4, 12).unwrap();
+        assert_eq!(warnings, [ClayWarning::SuboptimalD { d: 12, max_d: 13 }]);
+
+        // n = 5, q = 2: one zero node next to three data nodes
+        let (_, warnings) = ClayCode::new_checked(3, 2, 4).unwrap();
+        assert_eq!(warnings, [ClayWarning::LargeNu { nu: 1, k: 3 }]);
+
+        // q = 2, n = 14: α = 2^7 is fine, n = 26: α = 2^13 is not
+        assert!(ClayCode::new_checked(12, 2, 13).unwrap().1.is_empty());
+        let (_, warnings) = ClayCode::new_checked(24, 2, 25).unwrap();
+        assert_eq!(warnings, [ClayWarning::LargeSubPacketization { alpha: 1 << 13 }]);
+
+        assert!(ClayCode::new_checked(4, 2, 6).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! Steady-state allocation checks for the workspace paths
+//!
+//! Runs under a counting global allocator. Counts are kept per thread so
+//! tests running in parallel don't see each other's allocations.
+
+use clay_codes::testing::partial_helper_data;
+use clay_codes::{ClayCode, EncodeWorkspace, RepairWorkspace};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+/// System allocator that counts allocations made by the current thread
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+fn count_allocation() {
+    let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        count_allocation();
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        count_allocation();
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+/// Number of allocations `f` makes on this thread
+fn allocations_in(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+/// Parameter configurations to check: (k, m, d)
+const CONFIGS: &[(usize, usize, usize)] = &[(4, 2, 5), (6, 3, 8), (10, 4, 13)];
+
+fn test_data(size: usize) -> Vec<u8> {
+    (0..size).map(|i| (i.wrapping_mul(31) + 7) as u8).collect()
+}
+
+#[test]
+#[cfg_attr(feature = "rayon", ignore = "rayon allocates for its parallel layers")]
+fn test_encode_with_does_not_allocate() {
+    for &(k, m, d) in CONFIGS {
+        let clay = ClayCode::new(k, m, d).unwrap();
+        let data = test_data(64 * 1024);
+        let chunk_size = clay.encode(&data).unwrap()[0].len();
+        let mut ws = EncodeWorkspace::new(&clay, chunk_size).unwrap();
+
+        // Warm up (fills the RS decode matrix cache), then steady state
+        // must not touch the heap
+        clay.encode_with(&mut ws, &data).unwrap();
+        let allocations = allocations_in(|| {
+            for _ in 0..3 {
+                clay.encode_with(&mut ws, &data).unwrap();
+            }
+        });
+        assert_eq!(allocations, 0, "encode_with allocated for ({}, {}, {})", k, m, d);
+
+        // Same for encode_into_with writing into caller-owned buffers
+        let mut bufs = vec![vec![0u8; chunk_size]; clay.n];
+        let mut out: Vec<&mut [u8]> = bufs.iter_mut().map(|b| b.as_mut_slice()).collect();
+        let allocations = allocations_in(|| {
+            for _ in 0..3 {
+                clay.encode_into_with(&mut ws, &data, &mut out).unwrap();
+            }
+        });
+        assert_eq!(allocations, 0, "encode_into_with allocated for ({}, {}, {})", k, m, d);
+    }
+}
+
+#[test]
+fn test_repair_with_does_not_allocate() {
+    for &(k, m, d) in CONFIGS {
+        let clay = ClayCode::new(k, m, d).unwrap();
+        let chunks = clay.encode(&test_data(64 * 1024)).unwrap();
+        let chunk_size = chunks[0].len();
+
+        let lost_node = 0;
+        let available: Vec<usize> = (1..clay.n).collect();
+        let plan = clay.minimum_to_repair(lost_node, &available).unwrap();
+        let partial = partial_helper_data(&chunks, &plan, chunk_size / clay.sub_chunk_no);
+        let mut ws = RepairWorkspace::new(&clay, chunk_size).unwrap();
+
+        clay.repair_with(&mut ws, lost_node, &partial).unwrap();
+        let allocations = allocations_in(|| {
+            for _ in 0..3 {
+                clay.repair_with(&mut ws, lost_node, &partial).unwrap();
+            }
+        });
+        assert_eq!(allocations, 0, "repair_with allocated for ({}, {}, {})", k, m, d);
+        let repaired = clay.repair_with(&mut ws, lost_node, &partial).unwrap();
+        assert_eq!(repaired, &chunks[lost_node][..]);
+    }
+}